@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Delta } from "./Delta";
+
+export type AccountDelta = { account: { tb: string, id: { String: string }}, name: string, 
+/**
+ * Sum of the incoming amounts.
+ */
+income: Delta, 
+/**
+ * Sum of the outgoing amounts, positive.
+ */
+spent: Delta, };
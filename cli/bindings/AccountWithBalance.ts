@@ -1,4 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Account } from "./Account";
 
-export type AccountWithBalance = { account: Account, balance: number, };
+export type AccountWithBalance = { account: Account, balance: string, 
+/**
+ * Sum of incoming transactions, transfers excluded.
+ */
+income: string, 
+/**
+ * Sum of outgoing transactions, transfers excluded.
+ */
+expenses: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Rewrite } from "./Rewrite";
+
+/**
+ * Changes made to the transactions a rule applies to.
+ */
+export type Actions = { 
+/**
+ * Labels of the tags added to the transaction.
+ */
+add_tags: Array<string>, rewrite_description?: Rewrite, set_payee?: string, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Allocation } from "./Allocation";
+import type { Frequency } from "./Frequency";
+
+export type AddBudgetOptions = { name: string, accounts: Array<{ tb: string, id: { String: string }}>, period: Frequency, start: string, allocations: Array<Allocation>, rollover: boolean, filter?: string, };
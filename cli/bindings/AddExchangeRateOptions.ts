@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AddExchangeRateOptions = { base: string, quote: string, date: string, rate: string, source: string, };
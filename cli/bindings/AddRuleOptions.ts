@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actions } from "./Actions";
+import type { Conditions } from "./Conditions";
+
+export type AddRuleOptions = { name: string, enabled: boolean, priority: bigint, conditions: Conditions, actions: Actions, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Recurrence } from "./Recurrence";
+import type { Tag } from "./Tag";
+
+export type AddScheduleOptions = { account: { tb: string, id: { String: string }}, amount: string, description: string, tags: Array<Tag>, start: string, recurrence: Recurrence, };
@@ -1,4 +1,27 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Split } from "./Split";
+import type { Status } from "./Status";
 import type { Tag } from "./Tag";
 
-export type AddTransactionOptions = { amount: number, description: string, tags: Array<Tag>, date?: string, };
+export type AddTransactionOptions = { 
+/**
+ * Rounded to the minor units of the account currency.
+ */
+amount: string, description: string, tags: Array<Tag>, 
+/**
+ * Lines to split the amount into, they must sum to `amount`.
+ */
+splits?: Array<Split>, date?: string, 
+/**
+ * Identifier given to the transaction by the bank, like the FITID of OFX statements.
+ */
+fitid?: string, 
+/**
+ * Name of the other party of the transaction.
+ */
+payee?: string, payee_iban?: string, 
+/**
+ * Date the amount is taken into account by the bank for interests, when it
+ * differs from the booking date.
+ */
+value_date?: string, status?: Status, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Tag } from "./Tag";
+
+export type AddTransferOptions = { from: { tb: string, id: { String: string }}, to: { tb: string, id: { String: string }}, 
+/**
+ * Amount withdrawn from the `from` account, in its currency.
+ */
+amount: string, 
+/**
+ * Amount of the `to` account currency for one unit of the `from` account currency.
+ * Required when both accounts have different currencies.
+ */
+rate?: string | null, description: string, tags: Array<Tag>, date?: string, 
+/**
+ * Identifier of the transfer in the file it was imported from, given to both transactions.
+ */
+fitid?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Amount that can be spent each period on transactions with a tag.
+ */
+export type Allocation = { tag: string, amount: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Column } from "./Column";
+
+/**
+ * Where the amount of a transaction is read from, and how its sign is given.
+ */
+export type AmountColumns = { "type": "signed", column: Column, negate: boolean, } | { "type": "debit_credit", debit: Column, credit: Column, } | { "type": "indicator", column: Column, indicator: Column, debit: string, };
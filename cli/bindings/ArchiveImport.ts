@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Summary of what an archive import wrote to the database.
+ */
+export type ArchiveImport = { accounts: number, transactions: number, tags: number, };
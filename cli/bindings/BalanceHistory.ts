@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BalancePoint } from "./BalancePoint";
+
+/**
+ * Balances of an account, or of all the accounts of a currency, period by period.
+ */
+export type BalanceHistory = { currency: string, points: Array<BalancePoint>, };
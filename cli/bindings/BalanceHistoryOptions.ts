@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Frequency } from "./Frequency";
+
+export type BalanceHistoryOptions = { 
+/**
+ * Account to get the balance of, all accounts grouped by currency if omitted.
+ */
+account?: { tb: string, id: { String: string }}, 
+/**
+ * Length of the periods.
+ */
+period: Frequency, 
+/**
+ * Date in the first period.
+ */
+start: string, 
+/**
+ * Date in the last period, defaults to now.
+ */
+end?: string, 
+/**
+ * Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+ */
+filter?: string, };
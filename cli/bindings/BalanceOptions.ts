@@ -1,3 +1,24 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type BalanceOptions = { period_start?: string, period_end?: string, tag?: string, };
+export type BalanceOptions = { 
+/**
+ * Included.
+ */
+period_start?: string, 
+/**
+ * Excluded.
+ */
+period_end?: string, 
+/**
+ * Only count amounts tagged with this label. For split transactions, only the
+ * lines with the tag are counted.
+ */
+tag?: string, 
+/**
+ * Ignore transfers between accounts.
+ */
+exclude_transfers?: boolean, 
+/**
+ * Only count transactions matching this filter, see [`filter`] for the syntax.
+ */
+filter?: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Balance at the end of a period.
+ */
+export type BalancePoint = { 
+/**
+ * Included.
+ */
+period_start: string, 
+/**
+ * Excluded.
+ */
+period_end: string, 
+/**
+ * Sum of the transactions dated before the end of the period.
+ */
+balance: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Range of dates to compare a report with.
+ */
+export type Baseline = { "kind": "previous_period" } | { "kind": "previous_year" } | { "kind": "range", 
+/**
+ * Included.
+ */
+start: string, 
+/**
+ * Excluded.
+ */
+end: string, };
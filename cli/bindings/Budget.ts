@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Allocation } from "./Allocation";
+import type { Frequency } from "./Frequency";
+
+export type Budget = { id: { tb: string, id: { String: string }}, name: string, 
+/**
+ * Accounts whose transactions are counted, all in the same currency.
+ */
+accounts: Array<{ tb: string, id: { String: string }}>, period: Frequency, 
+/**
+ * Start of the first period.
+ */
+start: string, allocations: Array<Allocation>, 
+/**
+ * Carry what is left of an envelope (or overspent) over to the next period.
+ */
+rollover: boolean, 
+/**
+ * Only count transactions matching this filter, like `not tag:work`.
+ */
+filter?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Envelope } from "./Envelope";
+
+/**
+ * Budget versus actual spendings for a period of a budget.
+ */
+export type BudgetReport = { name: string, currency: string, period_start: string, 
+/**
+ * Excluded.
+ */
+period_end: string, envelopes: Array<Envelope>, };
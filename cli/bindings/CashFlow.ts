@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CashFlowPeriod } from "./CashFlowPeriod";
+
+/**
+ * Incomes and expenses of the accounts of a currency, period by period, transfers
+ * between accounts excluded.
+ */
+export type CashFlow = { currency: string, periods: Array<CashFlowPeriod>, 
+/**
+ * Sums of all the periods.
+ */
+total: CashFlowPeriod, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Incomes and expenses of a period.
+ */
+export type CashFlowPeriod = { 
+/**
+ * Included.
+ */
+period_start: string, 
+/**
+ * Excluded.
+ */
+period_end: string, 
+/**
+ * Sum of incoming transactions.
+ */
+income: string, 
+/**
+ * Sum of outgoing transactions, negative.
+ */
+expenses: string, 
+/**
+ * Incomes plus expenses.
+ */
+net: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A value before and after rules are applied.
+ */
+export type Change<T> = { before: T, after: T, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Checkpoint = { id: { tb: string, id: { String: string }}, account: { tb: string, id: { String: string }}, date: string, balance: string, 
+/**
+ * Where the balance comes from, like `manual` or the name of an imported file.
+ */
+source: string, 
+/**
+ * When the transactions up to the checkpoint were reconciled.
+ */
+reconciled_at?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column of the file, either its index starting at 0 or the label of its header.
+ */
+export type Column = number | string;
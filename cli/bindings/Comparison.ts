@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccountDelta } from "./AccountDelta";
+import type { Delta } from "./Delta";
+import type { TagDelta } from "./TagDelta";
+
+/**
+ * Spendings and incomes of the accounts of a currency over the range of a report,
+ * compared with a baseline. Transfers between accounts are excluded.
+ */
+export type Comparison = { currency: string, 
+/**
+ * Included.
+ */
+start: string, 
+/**
+ * Excluded.
+ */
+end: string, 
+/**
+ * Included.
+ */
+baseline_start: string, 
+/**
+ * Excluded.
+ */
+baseline_end: string, 
+/**
+ * Sum of the outgoing amounts, positive.
+ */
+spent: Delta, 
+/**
+ * By decreasing current spendings.
+ */
+tags: Array<TagDelta>, 
+/**
+ * Accounts with transactions in either range, by name.
+ */
+accounts: Array<AccountDelta>, };
@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Conditions a transaction must all match for a rule to apply. Conditions that
+ * are not set always match.
+ */
+export type Conditions = { 
+/**
+ * The description contains this text, ignoring case.
+ */
+description_contains?: string, 
+/**
+ * The description matches this regular expression.
+ */
+description_matches?: string, 
+/**
+ * Included.
+ */
+amount_min?: string, 
+/**
+ * Included.
+ */
+amount_max?: string, 
+/**
+ * The transaction belongs to one of these accounts.
+ */
+accounts: Array<{ tb: string, id: { String: string }}>, 
+/**
+ * Included.
+ */
+date_start?: string, 
+/**
+ * Excluded.
+ */
+date_end?: string, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ConversionOptions = { 
+/**
+ * Currency to convert to, defaults to the base currency of the settings.
+ */
+currency?: string, 
+/**
+ * Convert the balances at this date, with the rates valid at this date. Only
+ * transactions dated before it are counted. Defaults to now.
+ */
+date?: string, 
+/**
+ * Convert each transaction with the rate valid at its own date instead.
+ */
+at_transaction_date?: boolean, 
+/**
+ * Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+ */
+filter?: string, };
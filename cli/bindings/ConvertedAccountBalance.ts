@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Account } from "./Account";
+
+export type ConvertedAccountBalance = { account: Account, 
+/**
+ * Balance in the currency of the account.
+ */
+balance: string, 
+/**
+ * Balance in the currency of the conversion.
+ */
+converted: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConvertedAccountBalance } from "./ConvertedAccountBalance";
+
+export type ConvertedBalances = { currency: string, total: string, accounts: Array<ConvertedAccountBalance>, };
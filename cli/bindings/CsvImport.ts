@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AddTransactionOptions } from "./AddTransactionOptions";
+import type { ImportReport } from "./ImportReport";
+
+/**
+ * Transactions read from a file with a profile.
+ */
+export type CsvImport = { transactions: Array<AddTransactionOptions>, 
+/**
+ * Nothing is added to the account when this is a dry run.
+ */
+dry_run: boolean, report: ImportReport, };
@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AmountColumns } from "./AmountColumns";
+import type { Column } from "./Column";
+
+/**
+ * Mapping of the columns and formats of the CSV exports of a bank.
+ */
+export type CsvProfile = { 
+/**
+ * Unique name of the profile, usually the name of the bank.
+ */
+name: string, 
+/**
+ * Single ASCII character separating the columns.
+ */
+delimiter: string, 
+/**
+ * Separator of the fractional part of amounts, other separators like spaces
+ * or thousands separators are ignored.
+ */
+decimal_separator: string, 
+/**
+ * Label of the encoding of the file, like `utf-8` or `windows-1252`.
+ */
+encoding: string, 
+/**
+ * Lines to ignore at the beginning of the file, before the header.
+ */
+skip_rows: number, 
+/**
+ * The first row after the skipped ones holds the labels of the columns.
+ */
+has_headers: boolean, date: Column, 
+/**
+ * Format of dates, as described in https://docs.rs/chrono/latest/chrono/format/strftime.
+ */
+date_format: string, 
+/**
+ * Columns joined with a space to make the description.
+ */
+description: Array<Column>, amount: AmountColumns, };
@@ -1,4 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AccountWithBalance } from "./AccountWithBalance";
 
-export type CurrencyBalance = { currency: string, total_balance: number, accounts: Array<AccountWithBalance>, };
+export type CurrencyBalance = { currency: string, total_balance: string, total_income: string, total_expenses: string, accounts: Array<AccountWithBalance>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Evolution of an amount from the baseline to the range of the report.
+ */
+export type Delta = { 
+/**
+ * Over the range of the report.
+ */
+current: string, 
+/**
+ * Over the baseline.
+ */
+previous: string, 
+/**
+ * Current minus previous amount.
+ */
+change: string, 
+/**
+ * Change relative to the previous amount in percent, `None` if it was zero.
+ */
+percentage: string | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransactionWithId } from "./TransactionWithId";
+
+/**
+ * Two transactions that are likely the same.
+ */
+export type Duplicate = { 
+/**
+ * The transaction to keep when merging, the one with the most details.
+ */
+keep: TransactionWithId, duplicate: TransactionWithId, 
+/**
+ * Likelihood that both transactions are the same, between 0 and 1.
+ */
+score: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Decision } from "./Decision";
+
+/**
+ * A decision taken on a pair of transactions proposed as duplicates.
+ */
+export type DuplicateDecision = { id: { tb: string, id: { String: string }}, 
+/**
+ * Both transactions of the pair, the kept one first when they were merged.
+ */
+transactions: Array<{ tb: string, id: { String: string }}>, decision: Decision, decided_at: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Budget and actual spendings of a tag for a period.
+ */
+export type Envelope = { tag: string, allocated: string, 
+/**
+ * Left over from previous periods, negative if they were overspent.
+ */
+rolled_over: string, 
+/**
+ * Spendings of the period with the tag, minus incomes with the tag.
+ */
+spent: string, remaining: string, };
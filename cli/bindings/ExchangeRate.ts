@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Amount of the `quote` currency for one unit of the `base` currency, valid from
+ * `date` until the next rate of the same pair.
+ */
+export type ExchangeRate = { id: { tb: string, id: { String: string }}, base: string, quote: string, date: string, rate: string, 
+/**
+ * Where the rate comes from, like `manual` or the name of an imported file.
+ */
+source: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FindDuplicatesOptions = { 
+/**
+ * Only search the transactions of this account.
+ */
+account?: { tb: string, id: { String: string }}, 
+/**
+ * Maximum number of days between the dates of duplicates, defaults to [`DEFAULT_DAYS`].
+ */
+days?: number, 
+/**
+ * Minimum score of the proposed pairs, between 0 and 1, defaults to [`DEFAULT_MIN_SCORE`].
+ */
+min_score?: number, };
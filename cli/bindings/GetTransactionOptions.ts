@@ -1,8 +1,31 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Sort } from "./Sort";
 
 export type GetTransactionOptions = { start?: string, end?: string, 
 /**
  * Get transactions dated in the last `last_x_days` days before today.
  * Cannot be combined with `start` and `end` options.
  */
-last_x_days?: number, };
+last_x_days?: number, 
+/**
+ * Only get transactions matching this filter, see [`filter`] for the syntax.
+ */
+filter?: string, 
+/**
+ * Columns to sort by, in order. Transactions are sorted by date by default.
+ */
+sort?: Array<Sort>, 
+/**
+ * Maximum number of transactions to get.
+ */
+limit?: number, 
+/**
+ * Get the transactions after this cursor, returned with the previous page.
+ * The sort must be the same as for the previous page.
+ */
+cursor?: string, 
+/**
+ * Also get the balance of the account after each transaction, counting all the
+ * transactions of the account in date order, whatever the filter and the sort.
+ */
+running_balance?: boolean, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Summary of what an import added to the database.
+ */
+export type ImportReport = { account_name: string, account_id: { tb: string, id: { String: string }}, 
+/**
+ * The account did not exist and was created by the import.
+ */
+account_created: boolean, transactions_imported: number, 
+/**
+ * Labels of the tags that did not exist before the import.
+ */
+tags_created: Array<string>, 
+/**
+ * Entries that were not imported, with the reason why.
+ */
+skipped: Array<string>, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Checkpoint } from "./Checkpoint";
+import type { ImportReport } from "./ImportReport";
+
+/**
+ * Summary of what a journal import added to the database.
+ */
+export type JournalImport = { 
+/**
+ * One report per asset or liability account of the journal.
+ */
+accounts: Array<ImportReport>, transfers_imported: number, rates_imported: number, 
+/**
+ * Balance assertions that were not already recorded.
+ */
+checkpoints: Array<Checkpoint>, 
+/**
+ * Labels of the tags that did not exist before the import.
+ */
+tags_created: Array<string>, 
+/**
+ * Directives and transactions that could not be mapped, with the reason why.
+ */
+skipped: Array<string>, };
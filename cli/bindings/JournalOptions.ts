@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type JournalOptions = { 
+/**
+ * Post amounts to an income or expense account named after their first tag,
+ * instead of writing tags as tags of the transactions.
+ */
+tags_as_accounts: boolean, 
+/**
+ * Accounts amounts are posted to by tag, like `rent` to `Expenses:Home:Rent`,
+ * when `tags_as_accounts` is set. Other tags are posted to `Expenses:<tag>`
+ * or `Income:<tag>`.
+ */
+accounts: { [key in string]?: string }, 
+/**
+ * Only write the transactions dated from this day. The older ones are summed
+ * into the opening balances of the accounts.
+ */
+start?: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The nth weekday of a month, like the second tuesday or the last friday.
+ */
+export type NthWeekday = { 
+/**
+ * From 1 to 5, or -1 for the last weekday of the month.
+ */
+nth: number, weekday: "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun", };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Tag } from "./Tag";
+
+/**
+ * A transaction that will be created by a schedule.
+ */
+export type Occurrence = { schedule: { tb: string, id: { String: string }}, account: { tb: string, id: { String: string }}, date: string, amount: string, description: string, tags: Array<Tag>, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Checkpoint } from "./Checkpoint";
+import type { TransactionWithId } from "./TransactionWithId";
+
+/**
+ * Comparison of a checkpoint with the balance of its account.
+ */
+export type Reconciliation = { checkpoint: Checkpoint, 
+/**
+ * Balance of the account at the end of the day of the checkpoint.
+ */
+balance: string, 
+/**
+ * Balance of the checkpoint minus the balance of the account, zero when both agree.
+ */
+difference: string, 
+/**
+ * Transactions up to the checkpoint that are not reconciled yet.
+ */
+transactions: Array<TransactionWithId>, };
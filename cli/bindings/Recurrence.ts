@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Frequency } from "./Frequency";
+import type { NthWeekday } from "./NthWeekday";
+
+export type Recurrence = { frequency: Frequency, 
+/**
+ * Number of periods between two occurrences.
+ */
+interval: number, 
+/**
+ * Occur on the nth weekday of the month instead of the day of the start date.
+ * Only used by monthly and yearly recurrences.
+ */
+nth_weekday?: NthWeekday, 
+/**
+ * Date after which there are no more occurrences.
+ */
+until?: string, 
+/**
+ * Total number of occurrences.
+ */
+count?: number, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ReportOptions = { 
+/**
+ * Accounts to report on, all accounts if empty.
+ */
+accounts: Array<{ tb: string, id: { String: string }}>, 
+/**
+ * Included.
+ */
+start: string, 
+/**
+ * Excluded, defaults to now.
+ */
+end?: string, 
+/**
+ * Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+ */
+filter?: string, };
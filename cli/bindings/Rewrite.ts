@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Replacement of the parts of a description matching a regular expression.
+ */
+export type Rewrite = { pattern: string, 
+/**
+ * Can reference the groups of the pattern, like `$1` or `${name}`.
+ */
+replacement: string, };
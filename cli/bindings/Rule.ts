@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actions } from "./Actions";
+import type { Conditions } from "./Conditions";
+
+export type Rule = { id: { tb: string, id: { String: string }}, name: string, enabled: boolean, 
+/**
+ * Rules are applied by increasing priority.
+ */
+priority: bigint, conditions: Conditions, actions: Actions, };
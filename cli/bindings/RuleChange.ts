@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Change } from "./Change";
+
+/**
+ * Changes made by rules to an existing transaction.
+ */
+export type RuleChange = { transaction: { tb: string, id: { String: string }}, date: string, 
+/**
+ * Names of the rules that changed the transaction.
+ */
+rules: Array<string>, description: Change<string> | null, payee: Change<string | null> | null, 
+/**
+ * Labels of the tags added to the transaction.
+ */
+added_tags: Array<string>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RunRulesOptions = { 
+/**
+ * Only run the rules over the transactions of this account.
+ */
+account?: { tb: string, id: { String: string }}, 
+/**
+ * Only compute the changes, without writing them.
+ */
+dry_run: boolean, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Recurrence } from "./Recurrence";
+import type { Tag } from "./Tag";
+
+export type Schedule = { id: { tb: string, id: { String: string }}, account: { tb: string, id: { String: string }}, amount: string, description: string, tags: Array<Tag>, 
+/**
+ * Date of the first occurrence.
+ */
+start: string, recurrence: Recurrence, 
+/**
+ * Index of the next occurrence to create.
+ */
+next_occurrence: number, 
+/**
+ * Occurrences that will not be created.
+ */
+skipped: Array<string>, 
+/**
+ * Postponed occurrences, created at these dates instead.
+ */
+postponed: Array<string>, };
@@ -1,4 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Theme } from "./Theme";
 
-export type Settings = { theme: Theme, tags: Array<string>, backups_path: string, };
+export type Settings = { theme: Theme, tags: Array<string>, backups_path: string, 
+/**
+ * Number of days deleted transactions are kept in the trash.
+ */
+trash_retention_days: number, 
+/**
+ * Currency all balances are converted to when they are displayed together.
+ */
+base_currency: string, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortDirection } from "./SortDirection";
+import type { SortField } from "./SortField";
+
+/**
+ * A column to sort by, in the format of the items of the sort model of the
+ * transaction grid, `transaction_grid_sort_model`.
+ */
+export type Sort = { field: SortField, 
+/**
+ * The column is ignored without direction.
+ */
+sort?: SortDirection, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Column of the transactions to sort by.
+ */
+export type SortField = "date" | "description" | "amount" | "tags" | "payee" | "status";
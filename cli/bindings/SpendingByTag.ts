@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TagSpending } from "./TagSpending";
+
+/**
+ * Spendings of the accounts of a currency, tag by tag, transfers between accounts
+ * excluded.
+ */
+export type SpendingByTag = { currency: string, 
+/**
+ * Sum of the outgoing amounts, positive.
+ */
+total: string, 
+/**
+ * By decreasing spendings.
+ */
+tags: Array<TagSpending>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Tag } from "./Tag";
+
+/**
+ * Part of the amount of a split transaction, with its own tags.
+ */
+export type Split = { amount: string, tags: Array<Tag>, memo: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Checkpoint } from "./Checkpoint";
+import type { ImportReport } from "./ImportReport";
+
+export type StatementImport = { report: ImportReport, 
+/**
+ * Balances of the statement that were not already recorded.
+ */
+checkpoints: Array<Checkpoint>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether a transaction was seen on a statement of the bank, ordered from uncleared
+ * to reconciled.
+ */
+export type Status = "uncleared" | "cleared" | "reconciled";
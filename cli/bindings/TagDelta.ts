@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Delta } from "./Delta";
+
+export type TagDelta = { 
+/**
+ * `None` for the spendings without tags.
+ */
+tag: string | null, 
+/**
+ * Sum of the outgoing amounts, positive.
+ */
+spent: Delta, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Spendings on a tag.
+ */
+export type TagSpending = { 
+/**
+ * `None` for the spendings without tags.
+ */
+tag: string | null, 
+/**
+ * Sum of the outgoing amounts, positive.
+ */
+spent: string, 
+/**
+ * Share of the total spendings, in percent. Transactions with several tags are
+ * counted for each of them, so shares can add up to more than 100.
+ */
+percentage: string, };
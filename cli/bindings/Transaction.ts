@@ -1,4 +1,24 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Split } from "./Split";
+import type { Status } from "./Status";
 import type { Tag } from "./Tag";
 
-export type Transaction = { date: string, amount: number, description: string, tags: Array<Tag>, };
+export type Transaction = { date: string, amount: string, description: string, tags: Array<Tag>, 
+/**
+ * Lines the amount is split into, empty if the transaction is not split.
+ * When aggregating by tag, the tags of the lines are used instead of `tags`.
+ */
+splits: Array<Split>, 
+/**
+ * Identifier given to the transaction by the bank, used to skip it when the
+ * same statement is imported again.
+ */
+fitid?: string, 
+/**
+ * Name of the other party of the transaction.
+ */
+payee?: string, payee_iban?: string, 
+/**
+ * Date the amount is taken into account by the bank for interests.
+ */
+value_date?: string, status?: Status, };
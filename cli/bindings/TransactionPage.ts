@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransactionWithId } from "./TransactionWithId";
+
+/**
+ * A page of the transactions of an account.
+ */
+export type TransactionPage = { transactions: Array<TransactionWithId>, 
+/**
+ * Number of transactions matching the options, on all pages.
+ */
+total: number, 
+/**
+ * Cursor of the next page, `None` on the last page.
+ */
+next_cursor: string | null, 
+/**
+ * Balance of the account after each of the transactions, in the same order, when
+ * the running balance is requested. Empty otherwise.
+ */
+running_balances: Array<string>, };
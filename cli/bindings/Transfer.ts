@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransactionWithId } from "./TransactionWithId";
+
+/**
+ * Both legs of a transfer between two accounts.
+ */
+export type Transfer = { from: TransactionWithId, to: TransactionWithId, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransactionWithId } from "./TransactionWithId";
+
+/**
+ * A deleted transaction, kept until the trash retention period of the settings expires.
+ */
+export type TrashedTransaction = { id: { tb: string, id: { String: string }}, transaction: TransactionWithId, deleted_at: string, };
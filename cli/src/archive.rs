@@ -0,0 +1,707 @@
+//! Portable archives of the store, readable without SurrealDB.
+//!
+//! Backups made with `backup_export` are SurrealQL dumps whose syntax follows the
+//! version of the database. Archives only hold plain values (strings, decimal numbers
+//! written as strings and RFC 3339 dates) and carry the version of their format, so
+//! they can be read back by later versions of the app whatever the storage engine.
+//!
+//! An archive is written either as a single JSON document, or as a directory with a
+//! `manifest.json` file and one CSV file per table. Both layouts are described in the
+//! README.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    settings::Theme,
+    transaction::{Split, Status, Tag, Transaction},
+    Error, Record,
+};
+
+/// Name of the format, written in every archive.
+pub const FORMAT: &str = "thunes-archive";
+
+/// Version of the format written by this version of the app. It is increased each
+/// time a field is removed or changes meaning, older archives are still read.
+pub const VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SETTINGS_FILE: &str = "settings.csv";
+const ACCOUNTS_FILE: &str = "accounts.csv";
+const TAGS_FILE: &str = "tags.csv";
+const TRANSACTIONS_FILE: &str = "transactions.csv";
+const SPLITS_FILE: &str = "splits.csv";
+const TRANSACTION_TAGS_FILE: &str = "transaction_tags.csv";
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// A single JSON document.
+    Json,
+    /// A directory of CSV files, one per table.
+    Csv,
+}
+
+/// Format and version of an archive.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    /// Always [`FORMAT`].
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl Manifest {
+    /// Check that an archive can be read by this version of the app.
+    fn check(&self) -> Result<(), String> {
+        if self.format != FORMAT {
+            return Err(format!(
+                "not a thunes archive: unknown format {}",
+                self.format
+            ));
+        }
+
+        if self.version == 0 || self.version > VERSION {
+            return Err(format!(
+                "the archive is in version {} of the format, this version of thunes reads versions up to {VERSION}",
+                self.version
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Settings kept in archives. The backups directory depends on the machine and is
+/// left out.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedSettings {
+    pub theme: Theme,
+    pub base_currency: String,
+    pub trash_retention_days: u32,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedAccount {
+    /// Key of the account, referenced by its transactions.
+    pub id: String,
+    pub name: String,
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedSplit {
+    pub amount: Decimal,
+    /// Labels of the tags of the line.
+    pub tags: Vec<String>,
+    pub memo: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedTransaction {
+    pub id: String,
+    /// Key of the account of the transaction.
+    pub account: String,
+    pub date: DateTime<Utc>,
+    pub amount: Decimal,
+    pub description: String,
+    /// Labels of the tags of the transaction, their colors are in the tags of the archive.
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub splits: Vec<ArchivedSplit>,
+    #[serde(default)]
+    pub fitid: Option<String>,
+    #[serde(default)]
+    pub payee: Option<String>,
+    #[serde(default)]
+    pub payee_iban: Option<String>,
+    #[serde(default)]
+    pub value_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub status: Status,
+    /// Key of the other leg of a transfer between two accounts.
+    #[serde(default)]
+    pub transfer: Option<String>,
+}
+
+/// Accounts, transactions, tags and settings of the store.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Archive {
+    #[serde(flatten)]
+    pub manifest: Manifest,
+    pub settings: ArchivedSettings,
+    pub accounts: Vec<ArchivedAccount>,
+    pub tags: Vec<Tag>,
+    pub transactions: Vec<ArchivedTransaction>,
+}
+
+/// A transaction of the store with the keys of its records.
+#[derive(serde::Deserialize)]
+struct StoredTransaction {
+    #[serde(flatten)]
+    inner: Transaction,
+    key: String,
+    account_key: String,
+    transfer_key: Option<String>,
+}
+
+fn labels(tags: Vec<Tag>) -> Vec<String> {
+    tags.into_iter().map(|tag| tag.label).collect()
+}
+
+/// Read the accounts, transactions, tags and settings of the store.
+pub async fn export(db: &Surreal<Db>) -> Result<Archive, Error> {
+    let settings: crate::settings::Settings = db
+        .select(("settings", "main"))
+        .await?
+        .ok_or(Error::RecordNotFound)?;
+    let accounts: Vec<ArchivedAccount> = db
+        .query("SELECT record::id(id) AS id, name, currency FROM account ORDER BY name")
+        .await?
+        .take(0)?;
+    let tags: Vec<Tag> = db
+        .query("SELECT label, color FROM tag ORDER BY label")
+        .await?
+        .take(0)?;
+    let transactions: Vec<StoredTransaction> = db
+        .query(
+            r#"
+            SELECT *,
+                record::id(id) AS key,
+                record::id(account) AS account_key,
+                IF transfer THEN record::id(transfer) END AS transfer_key
+            OMIT id, account, transfer
+            FROM transaction ORDER BY date"#,
+        )
+        .await?
+        .take(0)?;
+
+    Ok(Archive {
+        manifest: Manifest {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            exported_at: Utc::now(),
+        },
+        settings: ArchivedSettings {
+            theme: settings.theme,
+            base_currency: settings.base_currency,
+            trash_retention_days: settings.trash_retention_days,
+        },
+        accounts,
+        tags,
+        transactions: transactions
+            .into_iter()
+            .map(|transaction| {
+                let inner = transaction.inner;
+
+                ArchivedTransaction {
+                    id: transaction.key,
+                    account: transaction.account_key,
+                    date: inner.date,
+                    amount: inner.amount,
+                    description: inner.description,
+                    tags: labels(inner.tags),
+                    splits: inner
+                        .splits
+                        .into_iter()
+                        .map(|split| ArchivedSplit {
+                            amount: split.amount,
+                            tags: labels(split.tags),
+                            memo: split.memo,
+                        })
+                        .collect(),
+                    fitid: inner.fitid,
+                    payee: inner.payee,
+                    payee_iban: inner.payee_iban,
+                    value_date: inner.value_date,
+                    status: inner.status,
+                    transfer: transaction.transfer_key,
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Summary of what an archive import wrote to the database.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct ArchiveImport {
+    pub accounts: usize,
+    pub transactions: usize,
+    pub tags: usize,
+}
+
+impl Archive {
+    /// Check that the archive can be read and that its records reference each other.
+    fn check(&self) -> Result<(), String> {
+        self.manifest.check()?;
+
+        let accounts: Vec<&str> = self
+            .accounts
+            .iter()
+            .map(|account| account.id.as_str())
+            .collect();
+        let transactions: Vec<&str> = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect();
+
+        for transaction in &self.transactions {
+            if !accounts.contains(&transaction.account.as_str()) {
+                return Err(format!(
+                    "transaction {} belongs to the unknown account {}",
+                    transaction.id, transaction.account
+                ));
+            }
+
+            if let Some(transfer) = &transaction.transfer {
+                if !transactions.contains(&transfer.as_str()) {
+                    return Err(format!(
+                        "transaction {} is a transfer with the unknown transaction {transfer}",
+                        transaction.id
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write an archive in the store. Records are written with the keys they have in the
+/// archive, replacing the records of the store with the same keys and leaving the
+/// others untouched, so importing the same archive twice does not duplicate anything.
+pub async fn import(db: &Surreal<Db>, archive: Archive) -> Result<ArchiveImport, Error> {
+    archive.check().map_err(Error::Invalid)?;
+
+    let report = ArchiveImport {
+        accounts: archive.accounts.len(),
+        transactions: archive.transactions.len(),
+        tags: archive.tags.len(),
+    };
+
+    let colors: HashMap<String, Option<String>> = archive
+        .tags
+        .iter()
+        .map(|tag| (tag.label.clone(), tag.color.clone()))
+        .collect();
+    let to_tags = |labels: Vec<String>| -> Vec<Tag> {
+        labels
+            .into_iter()
+            .map(|label| Tag {
+                color: colors.get(&label).cloned().flatten(),
+                label,
+            })
+            .collect()
+    };
+
+    crate::add_tags(db, archive.tags.clone()).await?;
+
+    for account in archive.accounts {
+        db.query("UPSERT $id SET name = $name, currency = $currency")
+            .bind(("id", RecordId::from(("account", account.id.as_str()))))
+            .bind(("name", account.name))
+            .bind(("currency", account.currency))
+            .await?
+            .check()?;
+    }
+
+    let query = r#"
+    UPSERT $id SET
+        date = $date,
+        amount = $amount,
+        description = $description,
+        tags = $tags,
+        splits = $splits,
+        fitid = $fitid,
+        payee = $payee,
+        payee_iban = $payee_iban,
+        value_date = $value_date,
+        status = $status,
+        account = $account_id,
+        transfer = $transfer"#;
+
+    for transaction in archive.transactions {
+        let splits: Vec<Split> = transaction
+            .splits
+            .into_iter()
+            .map(|split| Split {
+                amount: split.amount,
+                tags: to_tags(split.tags),
+                memo: split.memo,
+            })
+            .collect();
+
+        db.query(query)
+            .bind((
+                "id",
+                RecordId::from(("transaction", transaction.id.as_str())),
+            ))
+            .bind(("date", surrealdb::Datetime::from(transaction.date)))
+            .bind(("amount", transaction.amount))
+            .bind(("description", transaction.description))
+            .bind(("tags", serde_json::json!(to_tags(transaction.tags))))
+            .bind(("splits", serde_json::json!(splits)))
+            .bind(("fitid", transaction.fitid))
+            .bind(("payee", transaction.payee))
+            .bind(("payee_iban", transaction.payee_iban))
+            .bind((
+                "value_date",
+                transaction.value_date.map(surrealdb::Datetime::from),
+            ))
+            .bind(("status", transaction.status))
+            .bind((
+                "account_id",
+                RecordId::from(("account", transaction.account.as_str())),
+            ))
+            .bind((
+                "transfer",
+                transaction
+                    .transfer
+                    .map(|transfer| RecordId::from(("transaction", transfer.as_str()))),
+            ))
+            .await?
+            .check()?;
+    }
+
+    let _: Option<Record> = db
+        .update(("settings", "main"))
+        .merge(archive.settings)
+        .await?;
+
+    Ok(report)
+}
+
+/// Prefix an error with the path of the file of the archive it happened on.
+fn file_error<E: std::fmt::Display>(path: &std::path::Path) -> impl FnOnce(E) -> String + '_ {
+    move |error| format!("{}: {error}", path.display())
+}
+
+/// Write an archive as a JSON document.
+pub fn write_json(archive: &Archive, path: &std::path::Path) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(file_error(path))?;
+
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), archive).map_err(file_error(path))
+}
+
+/// Read an archive written as a JSON document.
+pub fn read_json(path: &std::path::Path) -> Result<Archive, String> {
+    let content = std::fs::read(path).map_err(file_error(path))?;
+
+    // Check the version first, an archive of a later version may not deserialize.
+    let manifest: Manifest = serde_json::from_slice(&content).map_err(file_error(path))?;
+    manifest.check()?;
+
+    serde_json::from_slice(&content).map_err(file_error(path))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SettingRow {
+    key: String,
+    value: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TransactionRow {
+    id: String,
+    account: String,
+    date: DateTime<Utc>,
+    amount: String,
+    description: String,
+    fitid: Option<String>,
+    payee: Option<String>,
+    payee_iban: Option<String>,
+    value_date: Option<DateTime<Utc>>,
+    status: Status,
+    transfer: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SplitRow {
+    transaction: String,
+    /// Position of the line in the transaction, from 0.
+    split: usize,
+    amount: String,
+    memo: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TransactionTagRow {
+    transaction: String,
+    /// Position of the line the tag is on, empty for the tags of the transaction.
+    split: Option<usize>,
+    label: String,
+}
+
+fn write_rows<T: serde::Serialize>(
+    directory: &std::path::Path,
+    file: &str,
+    rows: impl IntoIterator<Item = T>,
+) -> Result<(), String> {
+    let path = directory.join(file);
+    let mut writer = csv::Writer::from_path(&path).map_err(file_error(&path))?;
+
+    for row in rows {
+        writer.serialize(row).map_err(file_error(&path))?;
+    }
+
+    writer.flush().map_err(file_error(&path))
+}
+
+fn read_rows<T: serde::de::DeserializeOwned>(
+    directory: &std::path::Path,
+    file: &str,
+) -> Result<Vec<T>, String> {
+    let path = directory.join(file);
+    let mut reader = csv::Reader::from_path(&path).map_err(file_error(&path))?;
+
+    reader
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(file_error(&path))
+}
+
+/// Write an archive as a directory of CSV files, creating the directory if needed.
+pub fn write_csv(archive: &Archive, directory: &std::path::Path) -> Result<(), String> {
+    let manifest = directory.join(MANIFEST_FILE);
+
+    std::fs::create_dir_all(directory).map_err(file_error(directory))?;
+    let file = std::fs::File::create(&manifest).map_err(file_error(&manifest))?;
+    serde_json::to_writer_pretty(file, &archive.manifest).map_err(file_error(&manifest))?;
+
+    write_rows(
+        directory,
+        SETTINGS_FILE,
+        [
+            ("theme", serde_json::json!(archive.settings.theme)),
+            (
+                "base_currency",
+                archive.settings.base_currency.clone().into(),
+            ),
+            (
+                "trash_retention_days",
+                archive.settings.trash_retention_days.into(),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| SettingRow {
+            key: key.to_string(),
+            value: match value {
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            },
+        }),
+    )?;
+    write_rows(directory, ACCOUNTS_FILE, &archive.accounts)?;
+    write_rows(directory, TAGS_FILE, &archive.tags)?;
+    write_rows(
+        directory,
+        TRANSACTIONS_FILE,
+        archive
+            .transactions
+            .iter()
+            .map(|transaction| TransactionRow {
+                id: transaction.id.clone(),
+                account: transaction.account.clone(),
+                date: transaction.date,
+                amount: transaction.amount.to_string(),
+                description: transaction.description.clone(),
+                fitid: transaction.fitid.clone(),
+                payee: transaction.payee.clone(),
+                payee_iban: transaction.payee_iban.clone(),
+                value_date: transaction.value_date,
+                status: transaction.status,
+                transfer: transaction.transfer.clone(),
+            }),
+    )?;
+    write_rows(
+        directory,
+        SPLITS_FILE,
+        archive.transactions.iter().flat_map(|transaction| {
+            transaction
+                .splits
+                .iter()
+                .enumerate()
+                .map(|(index, split)| SplitRow {
+                    transaction: transaction.id.clone(),
+                    split: index,
+                    amount: split.amount.to_string(),
+                    memo: split.memo.clone(),
+                })
+        }),
+    )?;
+    write_rows(
+        directory,
+        TRANSACTION_TAGS_FILE,
+        archive.transactions.iter().flat_map(|transaction| {
+            let tags = transaction.tags.iter().map(|label| (None, label));
+            let split_tags = transaction
+                .splits
+                .iter()
+                .enumerate()
+                .flat_map(|(index, split)| {
+                    split.tags.iter().map(move |label| (Some(index), label))
+                });
+
+            tags.chain(split_tags)
+                .map(|(split, label)| TransactionTagRow {
+                    transaction: transaction.id.clone(),
+                    split,
+                    label: label.clone(),
+                })
+        }),
+    )?;
+
+    Ok(())
+}
+
+fn parse_decimal(value: &str, file: &str) -> Result<Decimal, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{file}: invalid amount {value}"))
+}
+
+/// Read an archive written as a directory of CSV files.
+pub fn read_csv(directory: &std::path::Path) -> Result<Archive, String> {
+    let path = directory.join(MANIFEST_FILE);
+    let file = std::fs::File::open(&path).map_err(file_error(&path))?;
+    let manifest: Manifest = serde_json::from_reader(file).map_err(file_error(&path))?;
+    manifest.check()?;
+
+    let settings: HashMap<String, String> = read_rows::<SettingRow>(directory, SETTINGS_FILE)?
+        .into_iter()
+        .map(|row| (row.key, row.value))
+        .collect();
+    let setting = |key: &str| {
+        settings
+            .get(key)
+            .ok_or_else(|| format!("{SETTINGS_FILE}: missing {key}"))
+    };
+    let settings = ArchivedSettings {
+        theme: serde_json::from_value(setting("theme")?.as_str().into())
+            .map_err(|_| format!("{SETTINGS_FILE}: invalid theme"))?,
+        base_currency: setting("base_currency")?.clone(),
+        trash_retention_days: setting("trash_retention_days")?
+            .parse()
+            .map_err(|_| format!("{SETTINGS_FILE}: invalid trash_retention_days"))?,
+    };
+
+    let mut transactions = read_rows::<TransactionRow>(directory, TRANSACTIONS_FILE)?
+        .into_iter()
+        .map(|row| {
+            Ok(ArchivedTransaction {
+                amount: parse_decimal(&row.amount, TRANSACTIONS_FILE)?,
+                id: row.id,
+                account: row.account,
+                date: row.date,
+                description: row.description,
+                tags: vec![],
+                splits: vec![],
+                fitid: row.fitid,
+                payee: row.payee,
+                payee_iban: row.payee_iban,
+                value_date: row.value_date,
+                status: row.status,
+                transfer: row.transfer,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let positions: HashMap<String, usize> = transactions
+        .iter()
+        .enumerate()
+        .map(|(index, transaction)| (transaction.id.clone(), index))
+        .collect();
+    let position = |file: &str, transaction: &str| {
+        positions
+            .get(transaction)
+            .copied()
+            .ok_or_else(|| format!("{file}: unknown transaction {transaction}"))
+    };
+
+    let mut splits = read_rows::<SplitRow>(directory, SPLITS_FILE)?;
+    splits.sort_by_key(|row| row.split);
+    for row in splits {
+        let index = position(SPLITS_FILE, &row.transaction)?;
+        // Lines are sorted, so each line of a transaction must follow the previous one.
+        let expected = transactions[index].splits.len();
+        if row.split < expected {
+            return Err(format!(
+                "{SPLITS_FILE}: transaction {} has line {} twice",
+                row.transaction, row.split
+            ));
+        }
+        if row.split > expected {
+            return Err(format!(
+                "{SPLITS_FILE}: transaction {} has no line {expected}",
+                row.transaction
+            ));
+        }
+
+        transactions[index].splits.push(ArchivedSplit {
+            amount: parse_decimal(&row.amount, SPLITS_FILE)?,
+            tags: vec![],
+            memo: row.memo,
+        });
+    }
+
+    for row in read_rows::<TransactionTagRow>(directory, TRANSACTION_TAGS_FILE)? {
+        let transaction = &mut transactions[position(TRANSACTION_TAGS_FILE, &row.transaction)?];
+        let tags = match row.split {
+            None => &mut transaction.tags,
+            Some(split) => match transaction.splits.get_mut(split) {
+                Some(split) => &mut split.tags,
+                None => {
+                    return Err(format!(
+                        "{TRANSACTION_TAGS_FILE}: transaction {} has no line {split}",
+                        row.transaction
+                    ))
+                }
+            },
+        };
+        tags.push(row.label);
+    }
+
+    Ok(Archive {
+        manifest,
+        settings,
+        accounts: read_rows(directory, ACCOUNTS_FILE)?,
+        tags: read_rows(directory, TAGS_FILE)?,
+        transactions,
+    })
+}
+
+/// Export the store as an archive at `path`, a file for JSON archives and a directory
+/// for CSV archives.
+pub async fn export_file(
+    db: &Surreal<Db>,
+    format: ArchiveFormat,
+    path: &std::path::Path,
+) -> Result<(), Error> {
+    let archive = export(db).await?;
+
+    match format {
+        ArchiveFormat::Json => write_json(&archive, path),
+        ArchiveFormat::Csv => write_csv(&archive, path),
+    }
+    .map_err(Error::Invalid)
+}
+
+/// Import an archive, read as a directory of CSV files if `path` is a directory and
+/// as a JSON document otherwise.
+pub async fn import_file(db: &Surreal<Db>, path: &std::path::Path) -> Result<ArchiveImport, Error> {
+    let archive = if path.is_dir() {
+        read_csv(path)
+    } else {
+        read_json(path)
+    }
+    .map_err(Error::Invalid)?;
+
+    import(db, archive).await
+}
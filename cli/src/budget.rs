@@ -0,0 +1,291 @@
+//! Budgets, splitting the money that can be spent each period into envelopes per tag.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    schedule::{Frequency, Recurrence},
+    BalanceOptions, Error, Record,
+};
+
+/// Amount that can be spent each period on transactions with a tag.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Allocation {
+    pub tag: String,
+    #[ts(type = "string")]
+    pub amount: Decimal,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Budget {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    pub name: String,
+    /// Accounts whose transactions are counted, all in the same currency.
+    #[ts(type = "Array<{ tb: string, id: { String: string }}>")]
+    pub accounts: Vec<RecordId>,
+    pub period: Frequency,
+    /// Start of the first period.
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    pub allocations: Vec<Allocation>,
+    /// Carry what is left of an envelope (or overspent) over to the next period.
+    pub rollover: bool,
+    /// Only count transactions matching this filter, like `not tag:work`.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+impl Budget {
+    fn recurrence(&self) -> Recurrence {
+        Recurrence {
+            frequency: self.period,
+            interval: 1,
+            nth_weekday: None,
+            until: None,
+            count: None,
+        }
+    }
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddBudgetOptions {
+    pub name: String,
+    #[ts(type = "Array<{ tb: string, id: { String: string }}>")]
+    pub accounts: Vec<RecordId>,
+    pub period: Frequency,
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    pub allocations: Vec<Allocation>,
+    pub rollover: bool,
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+/// Budget and actual spendings of a tag for a period.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Envelope {
+    pub tag: String,
+    #[ts(type = "string")]
+    pub allocated: Decimal,
+    /// Left over from previous periods, negative if they were overspent.
+    #[ts(type = "string")]
+    pub rolled_over: Decimal,
+    /// Spendings of the period with the tag, minus incomes with the tag.
+    #[ts(type = "string")]
+    pub spent: Decimal,
+    #[ts(type = "string")]
+    pub remaining: Decimal,
+}
+
+/// Budget versus actual spendings for a period of a budget.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BudgetReport {
+    pub name: String,
+    pub currency: String,
+    #[ts(as = "String")]
+    pub period_start: DateTime<Utc>,
+    /// Excluded.
+    #[ts(as = "String")]
+    pub period_end: DateTime<Utc>,
+    pub envelopes: Vec<Envelope>,
+}
+
+async fn check_accounts(db: &Surreal<Db>, accounts: &[RecordId]) -> Result<String, Error> {
+    let mut currencies = vec![];
+
+    for account in accounts {
+        currencies.push(crate::get_currency(db, account.clone()).await?);
+    }
+
+    currencies.dedup();
+
+    match currencies.as_slice() {
+        [currency] => Ok(currency.clone()),
+        [] => Err(Error::Invalid(
+            "a budget needs at least one account".to_string(),
+        )),
+        _ => Err(Error::Invalid(
+            "the accounts of a budget must have the same currency".to_string(),
+        )),
+    }
+}
+
+pub async fn add_budget(db: &Surreal<Db>, options: AddBudgetOptions) -> Result<Budget, Error> {
+    let currency = check_accounts(db, &options.accounts).await?;
+    crate::filter::parse(options.filter.as_deref())?;
+    let allocations: Vec<Allocation> = options
+        .allocations
+        .into_iter()
+        .map(|allocation| Allocation {
+            amount: crate::money::round(allocation.amount, &currency),
+            ..allocation
+        })
+        .collect();
+
+    let query = r#"
+    CREATE ONLY budget SET
+        name = $name,
+        accounts = $accounts,
+        period = $period,
+        start = $start,
+        allocations = $allocations,
+        rollover = $rollover,
+        filter = $filter"#;
+
+    let budget: Option<Budget> = db
+        .query(query)
+        .bind(("name", options.name))
+        .bind(("accounts", options.accounts))
+        .bind(("period", options.period))
+        .bind(("start", options.start))
+        .bind(("allocations", allocations))
+        .bind(("rollover", options.rollover))
+        .bind(("filter", options.filter))
+        .await?
+        .take(0)?;
+
+    budget.ok_or(Error::RecordNotFound)
+}
+
+pub async fn list_budgets(db: &Surreal<Db>) -> Result<Vec<Budget>, surrealdb::Error> {
+    db.select("budget").await
+}
+
+pub async fn get_budget(db: &Surreal<Db>, budget_id: RecordId) -> Result<Budget, Error> {
+    let budget: Option<Budget> = db.select(budget_id).await?;
+
+    budget.ok_or(Error::RecordNotFound)
+}
+
+pub async fn update_budget(db: &Surreal<Db>, mut budget: Budget) -> Result<(), Error> {
+    let currency = check_accounts(db, &budget.accounts).await?;
+    crate::filter::parse(budget.filter.as_deref())?;
+    for allocation in &mut budget.allocations {
+        allocation.amount = crate::money::round(allocation.amount, &currency);
+    }
+
+    let _: Option<Record> = db
+        .update(("budget", budget.id.key().clone()))
+        .merge(budget)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_budget(db: &Surreal<Db>, budget_id: RecordId) -> Result<(), surrealdb::Error> {
+    let _: Option<Record> = db.delete(budget_id).await?;
+
+    Ok(())
+}
+
+/// Spendings minus incomes of the accounts of a budget tagged with `tag` between `start`
+/// included and `end` excluded, transfers and transactions not matching the filter of
+/// the budget excluded.
+async fn spendings(
+    db: &Surreal<Db>,
+    budget: &Budget,
+    tag: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Decimal, Error> {
+    let mut spent = Decimal::ZERO;
+
+    for account in &budget.accounts {
+        spent -= match crate::balance(
+            db,
+            account.clone(),
+            BalanceOptions {
+                period_start: Some(start.into()),
+                period_end: Some(end.into()),
+                tag: Some(tag.to_string()),
+                exclude_transfers: Some(true),
+                filter: budget.filter.clone(),
+            },
+        )
+        .await
+        {
+            Ok(balance) => balance,
+            // No transactions matched the options.
+            Err(Error::RecordNotFound) => Decimal::ZERO,
+            Err(error) => return Err(error),
+        };
+    }
+
+    Ok(spent)
+}
+
+/// Compare the allocations of a budget with the actual spendings of the period that
+/// contains `date`.
+pub async fn budget_vs_actual(
+    db: &Surreal<Db>,
+    budget_id: RecordId,
+    date: DateTime<Utc>,
+) -> Result<BudgetReport, Error> {
+    let budget = get_budget(db, budget_id).await?;
+    let currency = check_accounts(db, &budget.accounts).await?;
+    let recurrence = budget.recurrence();
+
+    if date < budget.start {
+        return Err(Error::Invalid(format!("the budget starts after {date}")));
+    }
+
+    // Periods before the one of `date`, that are only needed to roll amounts over.
+    let mut periods = vec![];
+    let mut index = 0;
+    let (period_start, period_end) = loop {
+        let start = recurrence.occurrence(budget.start, index);
+        let end = recurrence.occurrence(budget.start, index + 1);
+
+        match start.zip(end) {
+            Some((start, end)) if date < end => break (start, end),
+            Some(period) => periods.push(period),
+            None => return Err(Error::Invalid(format!("no budget period contains {date}"))),
+        }
+
+        index += 1;
+    };
+
+    let mut envelopes = vec![];
+    for allocation in &budget.allocations {
+        let mut rolled_over = Decimal::ZERO;
+
+        if budget.rollover {
+            for (start, end) in &periods {
+                let spent = spendings(db, &budget, &allocation.tag, *start, *end).await?;
+                rolled_over += allocation.amount - spent;
+            }
+        }
+
+        let spent = spendings(db, &budget, &allocation.tag, period_start, period_end).await?;
+
+        envelopes.push(Envelope {
+            tag: allocation.tag.clone(),
+            allocated: allocation.amount,
+            rolled_over,
+            spent,
+            remaining: allocation.amount + rolled_over - spent,
+        });
+    }
+
+    Ok(BudgetReport {
+        name: budget.name,
+        currency,
+        period_start,
+        period_end,
+        envelopes,
+    })
+}
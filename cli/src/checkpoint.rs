@@ -0,0 +1,198 @@
+//! Balances of accounts given by the bank at a date, like the ledger balance of
+//! an imported statement.
+//!
+//! Reconciling a checkpoint compares it with the balance computed from the transactions
+//! of the account. When both agree, transactions up to the checkpoint are marked as
+//! reconciled, and their amount and date cannot change anymore.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{transaction::TransactionWithId, BalanceOptions, Error, Record};
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub balance: Decimal,
+    /// Where the balance comes from, like `manual` or the name of an imported file.
+    pub source: String,
+    /// When the transactions up to the checkpoint were reconciled.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub reconciled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddCheckpointOptions {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub balance: Decimal,
+    pub source: String,
+}
+
+pub async fn add_checkpoint(
+    db: &Surreal<Db>,
+    options: AddCheckpointOptions,
+) -> Result<Checkpoint, Error> {
+    let currency = crate::get_currency(db, options.account.clone()).await?;
+
+    let query = r#"
+    CREATE ONLY checkpoint SET
+        account = $account,
+        date = $date,
+        balance = $balance,
+        source = $source"#;
+
+    let checkpoint: Option<Checkpoint> = db
+        .query(query)
+        .bind(("account", options.account))
+        .bind(("date", surrealdb::Datetime::from(options.date)))
+        .bind(("balance", crate::money::round(options.balance, &currency)))
+        .bind(("source", options.source))
+        .await?
+        .take(0)?;
+
+    checkpoint.ok_or(Error::RecordNotFound)
+}
+
+/// List the checkpoints of an account, sorted by date.
+pub async fn list_checkpoints(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+) -> Result<Vec<Checkpoint>, surrealdb::Error> {
+    db.query("SELECT * FROM checkpoint WHERE account = $account_id ORDER BY date")
+        .bind(("account_id", account_id))
+        .await?
+        .take(0)
+}
+
+pub async fn delete_checkpoint(
+    db: &Surreal<Db>,
+    checkpoint_id: RecordId,
+) -> Result<(), surrealdb::Error> {
+    let _: Option<Record> = db.delete(checkpoint_id).await?;
+
+    Ok(())
+}
+
+/// Comparison of a checkpoint with the balance of its account.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Reconciliation {
+    pub checkpoint: Checkpoint,
+    /// Balance of the account at the end of the day of the checkpoint.
+    #[ts(type = "string")]
+    pub balance: Decimal,
+    /// Balance of the checkpoint minus the balance of the account, zero when both agree.
+    #[ts(type = "string")]
+    pub difference: Decimal,
+    /// Transactions up to the checkpoint that are not reconciled yet.
+    pub transactions: Vec<TransactionWithId>,
+}
+
+/// Compare a checkpoint with the balance of its account at the end of the day of
+/// the checkpoint.
+pub async fn get_reconciliation(
+    db: &Surreal<Db>,
+    checkpoint_id: RecordId,
+) -> Result<Reconciliation, Error> {
+    let checkpoint: Option<Checkpoint> = db.select(checkpoint_id).await?;
+    let checkpoint = checkpoint.ok_or(Error::RecordNotFound)?;
+    let end = checkpoint
+        .date
+        .date_naive()
+        .succ_opt()
+        .unwrap_or(chrono::NaiveDate::MAX)
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc();
+
+    let balance = match crate::balance(
+        db,
+        checkpoint.account.clone(),
+        BalanceOptions {
+            period_end: Some(end.into()),
+            ..Default::default()
+        },
+    )
+    .await
+    {
+        Ok(balance) => balance,
+        // No transactions before the checkpoint.
+        Err(Error::RecordNotFound) => Decimal::ZERO,
+        Err(error) => return Err(error),
+    };
+    let transactions: Vec<TransactionWithId> = db
+        .query(
+            "SELECT * FROM transaction WHERE account = $account AND date < $end AND status != 'reconciled' ORDER BY date",
+        )
+        .bind(("account", checkpoint.account.clone()))
+        .bind(("end", surrealdb::Datetime::from(end)))
+        .await?
+        .take(0)?;
+
+    Ok(Reconciliation {
+        difference: checkpoint.balance - balance,
+        checkpoint,
+        balance,
+        transactions,
+    })
+}
+
+/// Reconcile the transactions of an account up to a checkpoint, if the balance of
+/// the account agrees with the checkpoint.
+///
+/// Returns the reconciliation, with the transactions that were reconciled.
+pub async fn reconcile(db: &Surreal<Db>, checkpoint_id: RecordId) -> Result<Reconciliation, Error> {
+    let mut reconciliation = get_reconciliation(db, checkpoint_id).await?;
+
+    if !reconciliation.difference.is_zero() {
+        return Err(Error::Invalid(format!(
+            "the balance of the account is {} on {}, {} away from the checkpoint",
+            reconciliation.balance,
+            reconciliation.checkpoint.date.format("%Y-%m-%d"),
+            reconciliation.difference
+        )));
+    }
+
+    let ids: Vec<RecordId> = reconciliation
+        .transactions
+        .iter()
+        .map(|transaction| transaction.id.clone())
+        .collect();
+
+    let query = r#"
+    BEGIN TRANSACTION;
+    UPDATE transaction SET status = 'reconciled' WHERE id IN $ids;
+    UPDATE ONLY $checkpoint SET reconciled_at = time::now();
+    COMMIT TRANSACTION;"#;
+
+    let mut response = db
+        .query(query)
+        .bind(("ids", ids))
+        .bind(("checkpoint", reconciliation.checkpoint.id.clone()))
+        .await?
+        .check()?;
+    let checkpoint: Option<Checkpoint> = response.take(1)?;
+
+    for transaction in &mut reconciliation.transactions {
+        transaction.inner.status = crate::transaction::Status::Reconciled;
+    }
+    reconciliation.checkpoint = checkpoint.ok_or(Error::RecordNotFound)?;
+
+    Ok(reconciliation)
+}
@@ -0,0 +1,383 @@
+//! Detection of transactions recorded twice, like a transaction entered by hand and
+//! imported again from a statement of the bank.
+//!
+//! Candidates are pairs of transactions of the same account with the same amount and
+//! close dates, scored by the similarity of their descriptions. Once a pair is merged
+//! or dismissed, the decision is logged and the pair is not proposed again.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    transaction::{Status, TransactionWithId},
+    Error,
+};
+
+/// Number of days between the dates of duplicates, by default.
+pub const DEFAULT_DAYS: u32 = 3;
+
+/// Score from which a pair of transactions is proposed as duplicates, by default.
+pub const DEFAULT_MIN_SCORE: f64 = 0.5;
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Debug, serde::Deserialize)]
+pub struct FindDuplicatesOptions {
+    /// Only search the transactions of this account.
+    #[ts(type = "{ tb: string, id: { String: string }}", optional)]
+    #[serde(default)]
+    pub account: Option<RecordId>,
+    /// Maximum number of days between the dates of duplicates, defaults to [`DEFAULT_DAYS`].
+    #[ts(optional)]
+    #[serde(default)]
+    pub days: Option<u32>,
+    /// Minimum score of the proposed pairs, between 0 and 1, defaults to [`DEFAULT_MIN_SCORE`].
+    #[ts(optional)]
+    #[serde(default)]
+    pub min_score: Option<f64>,
+}
+
+/// Two transactions that are likely the same.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Duplicate {
+    /// The transaction to keep when merging, the one with the most details.
+    pub keep: TransactionWithId,
+    pub duplicate: TransactionWithId,
+    /// Likelihood that both transactions are the same, between 0 and 1.
+    pub score: f64,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// The duplicate was merged into the other transaction.
+    Merged,
+    /// Both transactions are distinct.
+    Dismissed,
+}
+
+/// A decision taken on a pair of transactions proposed as duplicates.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DuplicateDecision {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    /// Both transactions of the pair, the kept one first when they were merged.
+    #[ts(type = "Array<{ tb: string, id: { String: string }}>")]
+    pub transactions: Vec<RecordId>,
+    pub decision: Decision,
+    #[ts(as = "String")]
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Identifies a pair of transactions regardless of their order.
+fn pair_key(first: &RecordId, second: &RecordId) -> (String, String) {
+    let (first, second) = (first.to_string(), second.to_string());
+
+    if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// Lower case words of a text, without punctuation.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Similarity of two texts between 0 and 1, as the Dice coefficient of their pairs
+/// of characters.
+fn similarity(first: &str, second: &str) -> f64 {
+    let (first, second) = (normalize(first), normalize(second));
+    if first == second {
+        return 1.0;
+    }
+
+    let bigrams = |text: &str| {
+        let chars: Vec<char> = text.chars().collect();
+        chars
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>()
+    };
+    let first = bigrams(&first);
+    let mut second = bigrams(&second);
+    let total = first.len() + second.len();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let mut common = 0;
+    for bigram in first {
+        if let Some(position) = second.iter().position(|other| *other == bigram) {
+            second.swap_remove(position);
+            common += 1;
+        }
+    }
+
+    (2 * common) as f64 / total as f64
+}
+
+/// Score of a pair of transactions of the same account and amount, or `None` if
+/// they cannot be the same.
+fn score(first: &TransactionWithId, second: &TransactionWithId, days: u32) -> Option<f64> {
+    let (a, b) = (&first.inner, &second.inner);
+
+    // Both legs of transfers are kept in sync, merging them would break the transfer.
+    if first.transfer.is_some() && second.transfer.is_some() {
+        return None;
+    }
+
+    match (&a.fitid, &b.fitid) {
+        (Some(first), Some(second)) if first == second => return Some(1.0),
+        // The bank identifies them as distinct transactions.
+        (Some(_), Some(_)) => return None,
+        _ => {}
+    }
+
+    let apart = (a.date - b.date).num_days().unsigned_abs();
+    if apart > u64::from(days) {
+        return None;
+    }
+
+    let mut description = similarity(&a.description, &b.description);
+    if let (Some(first), Some(second)) = (&a.payee, &b.payee) {
+        description = description.max(similarity(first, second));
+    }
+    let date = 1.0 - apart as f64 / (f64::from(days) + 1.0);
+    let score = 0.6 * description + 0.4 * date;
+
+    Some((score * 100.0).round() / 100.0)
+}
+
+/// Number of details of a transaction, the one with the most is kept when merging.
+fn details(transaction: &TransactionWithId) -> (bool, Status, usize) {
+    let inner = &transaction.inner;
+
+    (
+        transaction.transfer.is_some(),
+        inner.status,
+        [
+            inner.fitid.is_some(),
+            inner.payee.is_some(),
+            inner.payee_iban.is_some(),
+            inner.value_date.is_some(),
+            !inner.splits.is_empty(),
+        ]
+        .into_iter()
+        .filter(|detail| *detail)
+        .count()
+            + inner.tags.len(),
+    )
+}
+
+pub async fn list_duplicate_decisions(
+    db: &Surreal<Db>,
+) -> Result<Vec<DuplicateDecision>, surrealdb::Error> {
+    db.query("SELECT * FROM duplicate_decision ORDER BY decided_at DESC")
+        .await?
+        .take(0)
+}
+
+/// Find pairs of transactions that are likely duplicates, by decreasing score.
+/// Pairs with a logged decision are not proposed.
+pub async fn find_duplicates(
+    db: &Surreal<Db>,
+    options: FindDuplicatesOptions,
+) -> Result<Vec<Duplicate>, Error> {
+    let days = options.days.unwrap_or(DEFAULT_DAYS);
+    let min_score = options.min_score.unwrap_or(DEFAULT_MIN_SCORE);
+
+    let transactions: Vec<TransactionWithId> = match options.account {
+        Some(account) => db
+            .query("SELECT * FROM transaction WHERE account = $account ORDER BY date")
+            .bind(("account", account))
+            .await?
+            .take(0)?,
+        None => db
+            .query("SELECT * FROM transaction ORDER BY date")
+            .await?
+            .take(0)?,
+    };
+    let decided: HashSet<(String, String)> = list_duplicate_decisions(db)
+        .await?
+        .into_iter()
+        .filter_map(|decision| match decision.transactions.as_slice() {
+            [first, second] => Some(pair_key(first, second)),
+            _ => None,
+        })
+        .collect();
+
+    let mut groups: HashMap<(String, Decimal), Vec<&TransactionWithId>> = HashMap::new();
+    for transaction in &transactions {
+        groups
+            .entry((transaction.account.to_string(), transaction.inner.amount))
+            .or_default()
+            .push(transaction);
+    }
+
+    let mut duplicates = vec![];
+    for group in groups.values() {
+        for (index, first) in group.iter().enumerate() {
+            // Transactions are sorted by date, the following ones are too far apart
+            // once one of them is.
+            for second in group[index + 1..].iter().take_while(|second| {
+                (second.inner.date - first.inner.date).num_days() <= i64::from(days)
+            }) {
+                if decided.contains(&pair_key(&first.id, &second.id)) {
+                    continue;
+                }
+
+                let Some(score) = score(first, second, days).filter(|score| *score >= min_score)
+                else {
+                    continue;
+                };
+
+                let (keep, duplicate) = if details(second) > details(first) {
+                    (second, first)
+                } else {
+                    (first, second)
+                };
+
+                duplicates.push(Duplicate {
+                    keep: (*keep).clone(),
+                    duplicate: (*duplicate).clone(),
+                    score,
+                });
+            }
+        }
+    }
+
+    duplicates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.keep.inner.date.cmp(&b.keep.inner.date))
+    });
+
+    Ok(duplicates)
+}
+
+async fn log_decision(
+    db: &Surreal<Db>,
+    transactions: Vec<RecordId>,
+    decision: Decision,
+) -> Result<(), Error> {
+    db.query("CREATE duplicate_decision SET transactions = $transactions, decision = $decision, decided_at = time::now()")
+        .bind(("transactions", transactions))
+        .bind(("decision", decision))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Record that two transactions proposed as duplicates are distinct.
+pub async fn dismiss_duplicate(
+    db: &Surreal<Db>,
+    first: RecordId,
+    second: RecordId,
+) -> Result<(), Error> {
+    log_decision(db, vec![first, second], Decision::Dismissed).await
+}
+
+/// Merge a duplicate into the transaction to keep, and move the duplicate to the trash.
+///
+/// The kept transaction gets the tags of both transactions, and the details it does
+/// not have, like the bank reference, from the duplicate.
+pub async fn merge_duplicates(
+    db: &Surreal<Db>,
+    keep: RecordId,
+    duplicate: RecordId,
+) -> Result<TransactionWithId, Error> {
+    if keep == duplicate {
+        return Err(Error::Invalid(
+            "a transaction cannot be merged with itself".to_string(),
+        ));
+    }
+
+    let kept: Option<TransactionWithId> = db.select(keep.clone()).await?;
+    let removed: Option<TransactionWithId> = db.select(duplicate.clone()).await?;
+    let (kept, removed) = kept.zip(removed).ok_or(Error::RecordNotFound)?;
+
+    if kept.account != removed.account || kept.inner.amount != removed.inner.amount {
+        return Err(Error::Invalid(
+            "only transactions of the same account and amount can be merged".to_string(),
+        ));
+    }
+
+    if removed.inner.status == Status::Reconciled {
+        return Err(Error::Invalid(
+            "the duplicate is reconciled, keep it instead".to_string(),
+        ));
+    }
+
+    if removed.transfer.is_some() {
+        return Err(Error::Invalid(
+            "the duplicate is a leg of a transfer, keep it instead".to_string(),
+        ));
+    }
+
+    let (mut merged, removed) = (kept.inner, removed.inner);
+    for tag in removed.tags {
+        if !merged.tags.contains(&tag) {
+            merged.tags.push(tag);
+        }
+    }
+    if merged.splits.is_empty() {
+        merged.splits = removed.splits;
+    }
+    if merged.description.is_empty() {
+        merged.description = removed.description;
+    }
+    merged.fitid = merged.fitid.or(removed.fitid);
+    merged.payee = merged.payee.or(removed.payee);
+    merged.payee_iban = merged.payee_iban.or(removed.payee_iban);
+    merged.value_date = merged.value_date.or(removed.value_date);
+    merged.status = merged.status.max(removed.status);
+
+    let query = r#"
+    UPDATE $id SET
+        description = $description,
+        tags = $tags,
+        splits = $splits,
+        fitid = $fitid,
+        payee = $payee,
+        payee_iban = $payee_iban,
+        value_date = $value_date,
+        status = $status"#;
+
+    let merged: Option<TransactionWithId> = db
+        .query(query)
+        .bind(("id", keep.clone()))
+        .bind(("description", merged.description))
+        .bind(("tags", serde_json::json!(merged.tags)))
+        .bind(("splits", serde_json::json!(merged.splits)))
+        .bind(("fitid", merged.fitid))
+        .bind(("payee", merged.payee))
+        .bind(("payee_iban", merged.payee_iban))
+        .bind((
+            "value_date",
+            merged.value_date.map(surrealdb::Datetime::from),
+        ))
+        .bind(("status", merged.status))
+        .await?
+        .take(0)?;
+
+    crate::delete_transaction(db, duplicate.clone()).await?;
+    log_decision(db, vec![keep, duplicate], Decision::Merged).await?;
+
+    merged.ok_or(Error::RecordNotFound)
+}
@@ -0,0 +1,329 @@
+//! Exchange rates between currencies, used to convert balances into a single currency.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{account::Account, Error, Record};
+
+/// Amount of the `quote` currency for one unit of the `base` currency, valid from
+/// `date` until the next rate of the same pair.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ExchangeRate {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    pub base: String,
+    pub quote: String,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub rate: Decimal,
+    /// Where the rate comes from, like `manual` or the name of an imported file.
+    pub source: String,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddExchangeRateOptions {
+    pub base: String,
+    pub quote: String,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub rate: Decimal,
+    pub source: String,
+}
+
+/// Add a rate, currencies being stored in upper case.
+pub async fn add_exchange_rate(
+    db: &Surreal<Db>,
+    options: AddExchangeRateOptions,
+) -> Result<ExchangeRate, Error> {
+    let base = options.base.trim().to_uppercase();
+    let quote = options.quote.trim().to_uppercase();
+
+    if options.rate <= Decimal::ZERO {
+        return Err(Error::Invalid(
+            "an exchange rate must be positive".to_string(),
+        ));
+    }
+
+    if base == quote {
+        return Err(Error::Invalid(
+            "an exchange rate needs two different currencies".to_string(),
+        ));
+    }
+
+    let query = r#"
+    CREATE ONLY exchange_rate SET
+        base = $base,
+        quote = $quote,
+        date = $date,
+        rate = $rate,
+        source = $source"#;
+
+    let rate: Option<ExchangeRate> = db
+        .query(query)
+        .bind(("base", base))
+        .bind(("quote", quote))
+        .bind(("date", surrealdb::Datetime::from(options.date)))
+        .bind(("rate", options.rate))
+        .bind(("source", options.source))
+        .await?
+        .take(0)?;
+
+    rate.ok_or(Error::RecordNotFound)
+}
+
+/// List exchange rates, sorted by date.
+pub async fn list_exchange_rates(db: &Surreal<Db>) -> Result<Vec<ExchangeRate>, surrealdb::Error> {
+    db.query("SELECT * FROM exchange_rate ORDER BY date")
+        .await?
+        .take(0)
+}
+
+pub async fn delete_exchange_rate(
+    db: &Surreal<Db>,
+    rate_id: RecordId,
+) -> Result<(), surrealdb::Error> {
+    let _: Option<Record> = db.delete(rate_id).await?;
+
+    Ok(())
+}
+
+/// Import exchange rates from a CSV file with `date,base,quote,rate` lines, dates
+/// being formatted as `YYYY-MM-DD`. A header line and lines starting with `#` are ignored.
+///
+/// Returns the number of imported rates.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    path: &std::path::Path,
+) -> Result<usize, crate::import::Error> {
+    let content = std::fs::read_to_string(path)?;
+    let source = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut rates = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || (index == 0 && line.starts_with("date")) {
+            continue;
+        }
+
+        let invalid =
+            |reason: &str| crate::import::Error::Invalid(format!("line {}: {reason}", index + 1));
+        let [date, base, quote, rate] = line.split(',').map(str::trim).collect::<Vec<_>>()[..]
+        else {
+            return Err(invalid("expected date,base,quote,rate"));
+        };
+
+        rates.push(AddExchangeRateOptions {
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+            date: chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|error| invalid(&error.to_string()))?
+                .and_time(chrono::NaiveTime::MIN)
+                .and_utc(),
+            rate: rate.parse().map_err(|_| invalid("invalid rate"))?,
+            source: source.clone(),
+        });
+    }
+
+    let imported = rates.len();
+    for rate in rates {
+        add_exchange_rate(db, rate).await?;
+    }
+
+    Ok(imported)
+}
+
+/// Exchange rates of every pair of currencies, to convert amounts at any date.
+pub struct Rates(Vec<ExchangeRate>);
+
+impl Rates {
+    pub async fn load(db: &Surreal<Db>) -> Result<Self, surrealdb::Error> {
+        list_exchange_rates(db).await.map(Self)
+    }
+
+    /// Get the last rate from `from` to `to` valid at `date`, using the inverse of the
+    /// rate from `to` to `from` when there is no direct rate. Currencies are compared
+    /// case-insensitively.
+    pub fn get(&self, from: &str, to: &str, date: DateTime<Utc>) -> Option<Decimal> {
+        if from.eq_ignore_ascii_case(to) {
+            return Some(Decimal::ONE);
+        }
+
+        self.0
+            .iter()
+            .rev()
+            .filter(|rate| rate.date <= date)
+            .find_map(|rate| {
+                if rate.base.eq_ignore_ascii_case(from) && rate.quote.eq_ignore_ascii_case(to) {
+                    Some(rate.rate)
+                } else if rate.base.eq_ignore_ascii_case(to)
+                    && rate.quote.eq_ignore_ascii_case(from)
+                {
+                    Some(Decimal::ONE / rate.rate)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Convert an amount with the rate valid at `date`.
+    pub fn convert(
+        &self,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+        date: DateTime<Utc>,
+    ) -> Result<Decimal, String> {
+        self.get(from, to, date)
+            .map(|rate| amount * rate)
+            .ok_or_else(|| {
+                format!(
+                    "no exchange rate from {from} to {to} at {}",
+                    date.format("%Y-%m-%d")
+                )
+            })
+    }
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Debug, serde::Deserialize)]
+pub struct ConversionOptions {
+    /// Currency to convert to, defaults to the base currency of the settings.
+    #[ts(optional)]
+    pub currency: Option<String>,
+    /// Convert the balances at this date, with the rates valid at this date. Only
+    /// transactions dated before it are counted. Defaults to now.
+    #[ts(as = "Option<String>", optional)]
+    pub date: Option<DateTime<Utc>>,
+    /// Convert each transaction with the rate valid at its own date instead.
+    #[ts(optional)]
+    pub at_transaction_date: Option<bool>,
+    /// Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct ConvertedAccountBalance {
+    pub account: Account,
+    /// Balance in the currency of the account.
+    #[ts(type = "string")]
+    pub balance: Decimal,
+    /// Balance in the currency of the conversion.
+    #[ts(type = "string")]
+    pub converted: Decimal,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct ConvertedBalances {
+    pub currency: String,
+    #[ts(type = "string")]
+    pub total: Decimal,
+    pub accounts: Vec<ConvertedAccountBalance>,
+}
+
+#[derive(serde::Deserialize)]
+struct DatedAmount {
+    date: DateTime<Utc>,
+    amount: Decimal,
+}
+
+/// Balances of every account converted into a single currency.
+pub async fn convert_balances(
+    db: &Surreal<Db>,
+    options: ConversionOptions,
+) -> Result<ConvertedBalances, Error> {
+    let currency = match options.currency {
+        Some(currency) => currency,
+        None => {
+            let settings: Option<crate::settings::Settings> =
+                db.select(("settings", "main")).await?;
+            settings.ok_or(Error::RecordNotFound)?.base_currency
+        }
+    };
+    let date = options.date.unwrap_or_else(Utc::now);
+    let rates = Rates::load(db).await?;
+    let filter = crate::filter::parse(options.filter.as_deref())?;
+
+    let mut total = Decimal::ZERO;
+    let mut accounts = vec![];
+
+    for identifiers in crate::list_account(db).await? {
+        let account = crate::get_account(db, identifiers.id).await?;
+        let from = &account.data.currency;
+        let balance = match crate::balance(
+            db,
+            account.id.clone(),
+            crate::BalanceOptions {
+                period_end: options.date.map(surrealdb::Datetime::from),
+                filter: options.filter.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        {
+            Ok(balance) => balance,
+            // The account has no transactions, or none matched the filter.
+            Err(Error::RecordNotFound) => Decimal::ZERO,
+            Err(error) => return Err(error),
+        };
+        let converted = if options.at_transaction_date.unwrap_or_default() {
+            let mut query =
+                "SELECT date, amount FROM transaction WHERE account = $account_id".to_string();
+            if options.date.is_some() {
+                query.push_str(" AND date < $date");
+            }
+            let params = crate::filter::Filter::push_condition(filter.as_ref(), &mut query);
+            let transactions: Vec<DatedAmount> = crate::filter::bind(db.query(query), params)
+                .bind(("account_id", account.id.clone()))
+                .bind(("date", surrealdb::Datetime::from(date)))
+                .await?
+                .take(0)?;
+
+            let mut converted = Decimal::ZERO;
+            for transaction in transactions {
+                converted += rates
+                    .convert(transaction.amount, from, &currency, transaction.date)
+                    .map_err(Error::Invalid)?;
+            }
+            converted
+        } else if balance.is_zero() {
+            // Empty accounts do not need a rate.
+            Decimal::ZERO
+        } else {
+            rates
+                .convert(balance, from, &currency, date)
+                .map_err(Error::Invalid)?
+        };
+        let converted = crate::money::round(converted, &currency);
+
+        total += converted;
+        accounts.push(ConvertedAccountBalance {
+            account,
+            balance,
+            converted,
+        });
+    }
+
+    Ok(ConvertedBalances {
+        currency,
+        total,
+        accounts,
+    })
+}
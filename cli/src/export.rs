@@ -0,0 +1,367 @@
+//! Export of the transactions of the store to the formats of other tools.
+//!
+//! Plain text accounting journals (ledger, hledger and beancount) hold every account
+//! of the store as an asset account, and post the amount of each transaction to an
+//! income or expense account.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, Surreal};
+
+use crate::{
+    account::Account,
+    transaction::{Status, Tag, TransactionWithId},
+};
+
+mod beancount;
+mod ledger;
+pub mod qif;
+
+/// Account the balance of accounts before the start of an export is taken from.
+const OPENING_BALANCES: &str = "Equity:Opening-Balances";
+
+/// Name of the income and expense accounts of amounts without tags.
+pub(crate) const UNCATEGORIZED: &str = "Uncategorized";
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct JournalOptions {
+    /// Post amounts to an income or expense account named after their first tag,
+    /// instead of writing tags as tags of the transactions.
+    #[serde(default)]
+    pub tags_as_accounts: bool,
+    /// Accounts amounts are posted to by tag, like `rent` to `Expenses:Home:Rent`,
+    /// when `tags_as_accounts` is set. Other tags are posted to `Expenses:<tag>`
+    /// or `Income:<tag>`.
+    #[serde(default)]
+    pub accounts: HashMap<String, String>,
+    /// Only write the transactions dated from this day. The older ones are summed
+    /// into the opening balances of the accounts.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+}
+
+struct Posting {
+    account: String,
+    amount: Decimal,
+    commodity: String,
+    /// Total cost of the posting in another commodity, for transfers between
+    /// accounts of different currencies.
+    cost: Option<(Decimal, String)>,
+    tags: Vec<String>,
+}
+
+struct Entry {
+    date: DateTime<Utc>,
+    status: Status,
+    payee: Option<String>,
+    description: String,
+    tags: Vec<String>,
+    fitid: Option<String>,
+    postings: Vec<Posting>,
+}
+
+/// Accounts and transactions of the store, in a form close to the journals of
+/// plain text accounting tools.
+struct Journal {
+    commodities: BTreeSet<String>,
+    /// Every account posted to, with the date of its first posting and the
+    /// commodity of asset accounts.
+    accounts: BTreeMap<String, (DateTime<Utc>, Option<String>)>,
+    entries: Vec<Entry>,
+}
+
+/// Write a commodity with the characters allowed by all formats: upper case
+/// letters, digits and a few punctuation marks, starting with a letter.
+fn commodity(currency: &str) -> String {
+    let commodity: String = currency
+        .to_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "'._-".contains(*c))
+        .collect();
+
+    if commodity.starts_with(|c: char| c.is_ascii_uppercase()) {
+        commodity
+    } else {
+        format!("X{commodity}")
+    }
+}
+
+/// Write the components of an account name, separated by `:`, with letters,
+/// digits and dashes only, starting with an upper case letter or a digit.
+fn account_name(name: &str) -> String {
+    name.split(':')
+        .map(|component| {
+            let component: String = component
+                .trim()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect();
+            let mut chars = component.chars();
+
+            match chars.next() {
+                Some(first) if first.is_alphanumeric() => {
+                    first.to_uppercase().chain(chars).collect::<String>()
+                }
+                _ => format!("X{component}"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Write a tag without spaces or separators of the journal formats.
+fn tag_name(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_/.".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Write a text on a single line.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl JournalOptions {
+    /// Account an amount spent or earned with `tags` is posted to, and the tags
+    /// left to write on the posting.
+    fn category(&self, tags: &[Tag], amount: Decimal) -> (String, Vec<String>) {
+        let root = if amount.is_sign_negative() {
+            "Expenses"
+        } else {
+            "Income"
+        };
+        let labels = tags.iter().map(|tag| tag_name(&tag.label));
+
+        match tags.first().filter(|_| self.tags_as_accounts) {
+            Some(tag) => (
+                account_name(
+                    self.accounts
+                        .get(&tag.label)
+                        .cloned()
+                        .unwrap_or_else(|| format!("{root}:{}", tag.label))
+                        .as_str(),
+                ),
+                labels.skip(1).collect(),
+            ),
+            None => (format!("{root}:{UNCATEGORIZED}"), labels.collect()),
+        }
+    }
+}
+
+async fn journal(db: &Surreal<Db>, options: &JournalOptions) -> Result<Journal, surrealdb::Error> {
+    let accounts: Vec<Account> = db
+        .query("SELECT * FROM account ORDER BY name")
+        .await?
+        .take(0)?;
+    let transactions: Vec<TransactionWithId> = db
+        .query("SELECT * FROM transaction ORDER BY date")
+        .await?
+        .take(0)?;
+
+    // Names of accounts in the journal, made unique once written with the
+    // characters allowed in account names.
+    let mut names = HashMap::new();
+    let mut used = BTreeSet::new();
+    for account in &accounts {
+        let base = account_name(&format!("Assets:{}", account.data.name));
+        let mut name = base.clone();
+        let mut index = 2;
+        while !used.insert(name.clone()) {
+            name = format!("{base}-{index}");
+            index += 1;
+        }
+        names.insert(
+            account.id.to_string(),
+            (name, commodity(&account.data.currency)),
+        );
+    }
+
+    let by_id: HashMap<String, &TransactionWithId> = transactions
+        .iter()
+        .map(|transaction| (transaction.id.to_string(), transaction))
+        .collect();
+
+    let mut openings: BTreeMap<String, (Decimal, String)> = BTreeMap::new();
+    let mut entries = vec![];
+
+    for transaction in &transactions {
+        let Some((account, commodity)) = names.get(&transaction.account.to_string()) else {
+            continue;
+        };
+        let inner = &transaction.inner;
+
+        if options.start.is_some_and(|start| inner.date < start) {
+            let (balance, _) = openings
+                .entry(account.clone())
+                .or_insert((Decimal::ZERO, commodity.clone()));
+            *balance += inner.amount;
+            continue;
+        }
+
+        let mut postings = vec![Posting {
+            account: account.clone(),
+            amount: inner.amount,
+            commodity: commodity.clone(),
+            cost: None,
+            tags: vec![],
+        }];
+        let mut tags = vec![];
+
+        let other = transaction
+            .transfer
+            .as_ref()
+            .and_then(|other| by_id.get(&other.to_string()))
+            .and_then(|other| {
+                names
+                    .get(&other.account.to_string())
+                    .map(|names| (other, names))
+            });
+
+        if let Some((other, (other_account, other_commodity))) = other {
+            // Both legs of a transfer are written once, from the account money
+            // is taken from.
+            if inner.amount.is_sign_positive() {
+                continue;
+            }
+
+            postings.push(Posting {
+                account: other_account.clone(),
+                amount: other.inner.amount,
+                commodity: other_commodity.clone(),
+                cost: Some((inner.amount.abs(), commodity.clone()))
+                    .filter(|_| other_commodity != commodity),
+                tags: vec![],
+            });
+            tags = inner.tags.iter().map(|tag| tag_name(&tag.label)).collect();
+        } else if inner.splits.is_empty() {
+            let (category, left) = options.category(&inner.tags, inner.amount);
+            postings.push(Posting {
+                account: category,
+                amount: -inner.amount,
+                commodity: commodity.clone(),
+                cost: None,
+                tags: vec![],
+            });
+            tags = left;
+        } else {
+            for split in &inner.splits {
+                let (category, left) = options.category(&split.tags, split.amount);
+                postings.push(Posting {
+                    account: category,
+                    amount: -split.amount,
+                    commodity: commodity.clone(),
+                    cost: None,
+                    tags: left,
+                });
+            }
+        }
+
+        entries.push(Entry {
+            date: inner.date,
+            status: inner.status,
+            payee: inner.payee.as_deref().map(single_line),
+            description: single_line(&inner.description),
+            tags,
+            fitid: inner.fitid.clone(),
+            postings,
+        });
+    }
+
+    if let Some(start) = options.start {
+        let opening_entries = openings
+            .into_iter()
+            .filter(|(_, (balance, _))| !balance.is_zero())
+            .map(|(account, (balance, commodity))| Entry {
+                date: start,
+                status: Status::Uncleared,
+                payee: None,
+                description: "Opening balance".to_string(),
+                tags: vec![],
+                fitid: None,
+                postings: vec![
+                    Posting {
+                        account,
+                        amount: balance,
+                        commodity: commodity.clone(),
+                        cost: None,
+                        tags: vec![],
+                    },
+                    Posting {
+                        account: OPENING_BALANCES.to_string(),
+                        amount: -balance,
+                        commodity,
+                        cost: None,
+                        tags: vec![],
+                    },
+                ],
+            })
+            .collect::<Vec<_>>();
+
+        entries.splice(0..0, opening_entries);
+    }
+
+    // Accounts without transactions are opened with the first transaction of the journal.
+    let first = entries
+        .first()
+        .map(|entry| entry.date)
+        .or(options.start)
+        .unwrap_or_else(Utc::now);
+    let mut opened: BTreeMap<String, (DateTime<Utc>, Option<String>)> = names
+        .values()
+        .map(|(name, commodity)| (name.clone(), (first, Some(commodity.clone()))))
+        .collect();
+    for entry in &entries {
+        for posting in &entry.postings {
+            let (date, _) = opened
+                .entry(posting.account.clone())
+                .or_insert((entry.date, None));
+            *date = (*date).min(entry.date);
+        }
+    }
+
+    Ok(Journal {
+        commodities: names
+            .into_values()
+            .map(|(_, commodity)| commodity)
+            .collect(),
+        accounts: opened,
+        entries,
+    })
+}
+
+/// Write every account and transaction of the store as a journal.
+pub async fn export_journal(
+    db: &Surreal<Db>,
+    format: JournalFormat,
+    options: &JournalOptions,
+) -> Result<String, surrealdb::Error> {
+    let journal = journal(db, options).await?;
+
+    Ok(match format {
+        JournalFormat::Ledger => ledger::write(&journal, false),
+        JournalFormat::Hledger => ledger::write(&journal, true),
+        JournalFormat::Beancount => beancount::write(&journal),
+    })
+}
@@ -0,0 +1,76 @@
+//! Journals of beancount.
+
+use std::fmt::Write;
+
+use super::Journal;
+use crate::transaction::Status;
+
+/// Write a string between double quotes.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Write a journal read by beancount. Every transaction is complete (`*`), the
+/// status of cleared and reconciled transactions is kept as metadata.
+pub(super) fn write(journal: &Journal) -> String {
+    // Writing to a string never fails.
+    let mut output = String::new();
+    let first = journal
+        .accounts
+        .values()
+        .map(|(date, _)| *date)
+        .min()
+        .unwrap_or_else(chrono::Utc::now)
+        .format("%Y-%m-%d");
+
+    for commodity in &journal.commodities {
+        let _ = writeln!(output, "{first} commodity {commodity}");
+    }
+    output.push('\n');
+
+    for (account, (date, commodity)) in &journal.accounts {
+        let _ = write!(output, "{} open {account}", date.format("%Y-%m-%d"));
+        if let Some(commodity) = commodity {
+            let _ = write!(output, " {commodity}");
+        }
+        output.push('\n');
+    }
+
+    for entry in &journal.entries {
+        let _ = write!(output, "\n{} *", entry.date.format("%Y-%m-%d"));
+        if let Some(payee) = &entry.payee {
+            let _ = write!(output, " {}", quote(payee));
+        }
+        let _ = write!(output, " {}", quote(&entry.description));
+        for tag in &entry.tags {
+            let _ = write!(output, " #{tag}");
+        }
+        output.push('\n');
+
+        match entry.status {
+            Status::Uncleared => {}
+            Status::Cleared => output.push_str("  status: \"cleared\"\n"),
+            Status::Reconciled => output.push_str("  status: \"reconciled\"\n"),
+        }
+        if let Some(fitid) = &entry.fitid {
+            let _ = writeln!(output, "  fitid: {}", quote(fitid));
+        }
+
+        for posting in &entry.postings {
+            let _ = write!(
+                output,
+                "  {}  {} {}",
+                posting.account, posting.amount, posting.commodity
+            );
+            if let Some((cost, commodity)) = &posting.cost {
+                let _ = write!(output, " @@ {cost} {commodity}");
+            }
+            output.push('\n');
+            if !posting.tags.is_empty() {
+                let _ = writeln!(output, "    tags: {}", quote(&posting.tags.join(" ")));
+            }
+        }
+    }
+
+    output
+}
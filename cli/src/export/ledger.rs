@@ -0,0 +1,84 @@
+//! Journals of ledger and hledger, which only differ by the way they write payees
+//! and tags.
+
+use std::fmt::Write;
+
+use super::{Journal, Posting};
+use crate::transaction::Status;
+
+fn tags(tags: &[String], hledger: bool) -> String {
+    if hledger {
+        tags.iter()
+            .map(|tag| format!("{tag}:"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else {
+        format!(":{}:", tags.join(":"))
+    }
+}
+
+fn write_posting(output: &mut String, posting: &Posting, hledger: bool) {
+    let _ = write!(
+        output,
+        "    {}  {} {}",
+        posting.account, posting.amount, posting.commodity
+    );
+    if let Some((cost, commodity)) = &posting.cost {
+        let _ = write!(output, " @@ {cost} {commodity}");
+    }
+    if !posting.tags.is_empty() {
+        let _ = write!(output, "  ; {}", tags(&posting.tags, hledger));
+    }
+    output.push('\n');
+}
+
+/// Write a journal read by ledger, or by hledger when `hledger` is set.
+pub(super) fn write(journal: &Journal, hledger: bool) -> String {
+    // Writing to a string never fails.
+    let mut output = String::new();
+
+    for commodity in &journal.commodities {
+        let _ = writeln!(output, "commodity {commodity}");
+    }
+    output.push('\n');
+
+    for account in journal.accounts.keys() {
+        let _ = writeln!(output, "account {account}");
+    }
+
+    for entry in &journal.entries {
+        let mark = match entry.status {
+            Status::Uncleared => "",
+            Status::Cleared => " !",
+            Status::Reconciled => " *",
+        };
+        let _ = write!(output, "\n{}{mark}", entry.date.format("%Y-%m-%d"));
+
+        // hledger splits the description in a payee and a note, ledger only has a
+        // payee and notes written as comments.
+        match &entry.payee {
+            Some(payee) if hledger => {
+                let _ = writeln!(output, " {payee} | {}", entry.description);
+            }
+            Some(payee) if *payee != entry.description => {
+                let _ = writeln!(output, " {payee}  ; {}", entry.description);
+            }
+            _ => {
+                let _ = writeln!(output, " {}", entry.description);
+            }
+        }
+
+        if !entry.tags.is_empty() {
+            let _ = writeln!(output, "    ; {}", tags(&entry.tags, hledger));
+        }
+        if let Some(fitid) = &entry.fitid {
+            let _ = writeln!(output, "    ; fitid: {fitid}");
+        }
+
+        for posting in &entry.postings {
+            write_posting(&mut output, posting, hledger);
+        }
+    }
+
+    output
+}
@@ -0,0 +1,64 @@
+//! Export of the transactions of an account to a QIF file, readable by Quicken,
+//! Homebank or GnuCash, and by the QIF import of this crate.
+
+use std::fmt::Write;
+
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::transaction::{Status, Tag};
+
+/// Tags written as a category field. The first tag is the category and the
+/// others are read back as classes.
+fn category(tags: &[Tag]) -> String {
+    tags.iter()
+        .map(|tag| tag.label.as_str())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Text of a field on a single line, as a line break would end the field.
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Write every transaction of an account in a `Bank` section.
+pub async fn export(db: &Surreal<Db>, account_id: RecordId) -> Result<String, crate::Error> {
+    let transactions = crate::get_transactions(db, account_id, Default::default()).await?;
+    let mut output = String::from("!Type:Bank\n");
+
+    for transaction in transactions {
+        let transaction = transaction.inner;
+        let payee = transaction
+            .payee
+            .as_deref()
+            .unwrap_or(&transaction.description);
+
+        // Writing to a string never fails.
+        let _ = writeln!(output, "D{}", transaction.date.format("%m/%d/%Y"));
+        let _ = writeln!(output, "T{}", transaction.amount);
+        match transaction.status {
+            Status::Uncleared => {}
+            Status::Cleared => output.push_str("C*\n"),
+            Status::Reconciled => output.push_str("CX\n"),
+        }
+        if !payee.is_empty() {
+            let _ = writeln!(output, "P{}", single_line(payee));
+        }
+        if transaction.description != payee {
+            let _ = writeln!(output, "M{}", single_line(&transaction.description));
+        }
+        if !transaction.tags.is_empty() {
+            let _ = writeln!(output, "L{}", category(&transaction.tags));
+        }
+        for split in &transaction.splits {
+            let _ = writeln!(output, "S{}", category(&split.tags));
+            if !split.memo.is_empty() {
+                let _ = writeln!(output, "E{}", single_line(&split.memo));
+            }
+            let _ = writeln!(output, "${}", split.amount);
+        }
+        output.push_str("^\n");
+    }
+
+    Ok(output)
+}
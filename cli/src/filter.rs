@@ -0,0 +1,571 @@
+//! A small language to filter transactions, like
+//! `tag:food and not tag:work and amount < -50 and desc~"amazon" and date:2025-Q1`.
+//!
+//! Filters are made of conditions combined with `and`, `or`, `not` and parentheses.
+//! Conditions next to each other without an operator must all match.
+//!
+//! | Condition         | Matches transactions                                            |
+//! |-------------------|-----------------------------------------------------------------|
+//! | `tag:food`        | tagged with `food`, or with a line tagged with `food`           |
+//! | `desc~amazon`     | whose description contains `amazon`, ignoring case              |
+//! | `payee~"le cafe"` | whose payee contains `le cafe`, ignoring case                   |
+//! | `amount < -50`    | compared with an amount, with `<`, `<=`, `>`, `>=`, `=` or `!=` |
+//! | `date:2025-Q1`    | dated in a year, quarter, month or day                          |
+//! | `date >= 2025-03` | dated before or after a year, quarter, month or day             |
+//! | `status:cleared`  | with a status, `uncleared`, `cleared` or `reconciled`           |
+//! | `account:main`    | of the account named `main`                                     |
+//! | `amazon`          | whose description contains `amazon`, ignoring case              |
+//!
+//! Values with spaces or operators are written between double quotes, with `\"` for
+//! quotes. Filters are converted to parameterized SurrealQL conditions, values are
+//! never written in the queries.
+
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, method::Query};
+
+use crate::transaction::Status;
+
+/// Error found while parsing a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    /// Position of the error, in characters from 1.
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid filter at column {}: {}",
+            self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl From<FilterError> for crate::Error {
+    fn from(value: FilterError) -> Self {
+        Self::Invalid(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Lower,
+    LowerOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn operator(self) -> &'static str {
+        match self {
+            Comparison::Lower => "<",
+            Comparison::LowerOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Equal => "=",
+            Comparison::NotEqual => "!=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Tag(String),
+    /// Lower case text contained in the description.
+    Description(String),
+    /// Lower case text contained in the payee.
+    Payee(String),
+    Amount(Comparison, Decimal),
+    /// Comparison with a period, from its first instant included to its last excluded.
+    Date(Comparison, DateTime<Utc>, DateTime<Utc>),
+    Status(Status),
+    Account(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    All,
+    Condition(Condition),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+/// A parsed filter, see the [module documentation](self) for the syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(Expression);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+const OPERATORS: [&str; 9] = ["<=", ">=", "!=", "<", ">", "=", ":", "~", "!"];
+
+/// Split a filter into tokens, with the column of each of them.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    while index < chars.len() {
+        let column = index + 1;
+        let c = chars[index];
+
+        if c.is_whitespace() {
+            index += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push((column, if c == '(' { Token::Open } else { Token::Close }));
+            index += 1;
+        } else if c == '"' {
+            let mut value = String::new();
+            index += 1;
+            loop {
+                match chars.get(index) {
+                    None => {
+                        return Err(FilterError {
+                            column,
+                            message: "the quote is never closed".to_string(),
+                        })
+                    }
+                    Some('"') => break,
+                    Some('\\') if chars.get(index + 1) == Some(&'"') => {
+                        value.push('"');
+                        index += 1;
+                    }
+                    Some(c) => value.push(*c),
+                }
+                index += 1;
+            }
+            tokens.push((column, Token::Quoted(value)));
+            index += 1;
+        } else if let Some(operator) = OPERATORS.iter().find(|operator| {
+            operator
+                .chars()
+                .enumerate()
+                .all(|(offset, c)| chars.get(index + offset) == Some(&c))
+        }) {
+            if *operator == "!" {
+                return Err(FilterError {
+                    column,
+                    message: "unexpected '!', use `not` to negate a condition".to_string(),
+                });
+            }
+            tokens.push((column, Token::Operator(operator)));
+            index += operator.len();
+        } else {
+            let start = index;
+            while index < chars.len()
+                && !chars[index].is_whitespace()
+                && !"()\"<>=!:~".contains(chars[index])
+            {
+                index += 1;
+            }
+            tokens.push((column, Token::Word(chars[start..index].iter().collect())));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// First and last instants of a year (`2025`), quarter (`2025-Q1`), month (`2025-03`)
+/// or day (`2025-03-14`), the last one excluded.
+fn period(value: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let year: i32 = parts.first().filter(|year| year.len() == 4)?.parse().ok()?;
+
+    let (start, months) = match parts[1..] {
+        [] => (NaiveDate::from_ymd_opt(year, 1, 1)?, 12),
+        [quarter] if quarter.starts_with(['q', 'Q']) => {
+            let quarter: u32 = quarter[1..].parse().ok().filter(|q| (1..=4).contains(q))?;
+            (NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1)?, 3)
+        }
+        [month] => (NaiveDate::from_ymd_opt(year, month.parse().ok()?, 1)?, 1),
+        [month, day] => {
+            let day = NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)?;
+            let midnight = |date: NaiveDate| date.and_time(chrono::NaiveTime::MIN).and_utc();
+            return Some((midnight(day), midnight(day.succ_opt()?)));
+        }
+        _ => return None,
+    };
+    let end = start.checked_add_months(Months::new(months))?;
+
+    Some((
+        start.and_time(chrono::NaiveTime::MIN).and_utc(),
+        end.and_time(chrono::NaiveTime::MIN).and_utc(),
+    ))
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Column after the last character, for errors at the end of the filter.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map(|(column, _)| *column)
+            .unwrap_or(self.end)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, FilterError> {
+        Err(FilterError {
+            column: self.column(),
+            message: message.into(),
+        })
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(_, token)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> Result<Expression, FilterError> {
+        let mut expression = self.and()?;
+
+        while self.keyword("or") {
+            self.position += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Expression, FilterError> {
+        let mut expression = self.not()?;
+
+        loop {
+            if self.keyword("and") {
+                self.position += 1;
+            } else if self.peek().is_none()
+                || self.peek() == Some(&Token::Close)
+                || self.keyword("or")
+            {
+                break;
+            }
+
+            expression = Expression::And(Box::new(expression), Box::new(self.not()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn not(&mut self) -> Result<Expression, FilterError> {
+        if self.keyword("not") {
+            self.position += 1;
+            return Ok(Expression::Not(Box::new(self.not()?)));
+        }
+
+        match self.peek() {
+            Some(Token::Open) => {
+                self.position += 1;
+                let expression = self.or()?;
+                if self.next() != Some(Token::Close) {
+                    self.position -= 1;
+                    return self.error("expected ')'");
+                }
+                Ok(expression)
+            }
+            Some(Token::Word(word))
+                if ["and", "or"]
+                    .iter()
+                    .any(|keyword| word.eq_ignore_ascii_case(keyword)) =>
+            {
+                self.error(format!("expected a condition before '{word}'"))
+            }
+            Some(Token::Word(_) | Token::Quoted(_)) => self.condition().map(Expression::Condition),
+            Some(Token::Close) => self.error("unexpected ')'"),
+            Some(Token::Operator(operator)) => {
+                self.error(format!("expected a condition before '{operator}'"))
+            }
+            None => self.error("expected a condition"),
+        }
+    }
+
+    /// Value of a condition, a word or a quoted text.
+    fn value(&mut self, field: &str, operator: &str) -> Result<String, FilterError> {
+        match self.next() {
+            Some(Token::Word(value) | Token::Quoted(value)) => Ok(value),
+            _ => {
+                self.position -= 1;
+                self.error(format!("expected a value after '{field}{operator}'"))
+            }
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, FilterError> {
+        let column = self.column();
+        let field = match self.next() {
+            Some(Token::Word(field)) => field,
+            Some(Token::Quoted(text)) => return Ok(Condition::Description(text.to_lowercase())),
+            _ => unreachable!("conditions start with a word or a quoted text"),
+        };
+
+        let Some(Token::Operator(operator)) = self.peek().cloned() else {
+            // A word alone is searched in descriptions.
+            return Ok(Condition::Description(field.to_lowercase()));
+        };
+        let operator_column = self.column();
+        self.position += 1;
+        let value_column = self.column();
+        let value = self.value(&field, operator)?;
+        let invalid = |message: String| {
+            Err(FilterError {
+                column: value_column,
+                message,
+            })
+        };
+        let expect = |expected: &[&str]| {
+            if expected.contains(&operator) {
+                Ok(())
+            } else {
+                Err(FilterError {
+                    column: operator_column,
+                    message: format!(
+                        "expected {} after '{field}'",
+                        expected
+                            .iter()
+                            .map(|operator| format!("'{operator}'"))
+                            .collect::<Vec<_>>()
+                            .join(" or ")
+                    ),
+                })
+            }
+        };
+        let comparison = match operator {
+            "<" => Comparison::Lower,
+            "<=" => Comparison::LowerOrEqual,
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterOrEqual,
+            "!=" => Comparison::NotEqual,
+            _ => Comparison::Equal,
+        };
+
+        match field.to_lowercase().as_str() {
+            "tag" => {
+                expect(&[":"])?;
+                Ok(Condition::Tag(value))
+            }
+            "desc" | "description" => {
+                expect(&["~"])?;
+                Ok(Condition::Description(value.to_lowercase()))
+            }
+            "payee" => {
+                expect(&["~"])?;
+                Ok(Condition::Payee(value.to_lowercase()))
+            }
+            "amount" => {
+                expect(&[":", "<", "<=", ">", ">=", "=", "!="])?;
+                match value.parse() {
+                    Ok(amount) => Ok(Condition::Amount(comparison, amount)),
+                    Err(_) => invalid(format!("invalid amount '{value}'")),
+                }
+            }
+            "date" => {
+                expect(&[":", "<", "<=", ">", ">=", "=", "!="])?;
+                match period(&value) {
+                    Some((start, end)) => Ok(Condition::Date(comparison, start, end)),
+                    None => invalid(format!(
+                        "invalid date '{value}', expected YYYY, YYYY-QN, YYYY-MM or YYYY-MM-DD"
+                    )),
+                }
+            }
+            "status" => {
+                expect(&[":"])?;
+                match value.to_lowercase().as_str() {
+                    "uncleared" => Ok(Condition::Status(Status::Uncleared)),
+                    "cleared" => Ok(Condition::Status(Status::Cleared)),
+                    "reconciled" => Ok(Condition::Status(Status::Reconciled)),
+                    _ => invalid(format!(
+                        "invalid status '{value}', expected uncleared, cleared or reconciled"
+                    )),
+                }
+            }
+            "account" => {
+                expect(&[":"])?;
+                Ok(Condition::Account(value))
+            }
+            _ => Err(FilterError {
+                column,
+                message: format!(
+                    "unknown field '{field}', expected tag, desc, payee, amount, date, status or account"
+                ),
+            }),
+        }
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+            end: input.chars().count() + 1,
+        };
+
+        if parser.peek().is_none() {
+            return Ok(Filter(Expression::All));
+        }
+
+        let expression = parser.or()?;
+        if parser.peek().is_some() {
+            return parser.error("unexpected ')'");
+        }
+
+        Ok(Filter(expression))
+    }
+}
+
+/// Parse the filter of options, if any.
+pub(crate) fn parse(filter: Option<&str>) -> Result<Option<Filter>, FilterError> {
+    filter.map(str::parse).transpose()
+}
+
+impl Expression {
+    fn write(&self, sql: &mut String, params: &mut Vec<(String, String)>) {
+        let mut param = |value: String| {
+            let name = format!("filter_{}", params.len());
+            params.push((name.clone(), value));
+            format!("${name}")
+        };
+
+        match self {
+            Expression::All => sql.push_str("true"),
+            Expression::Condition(condition) => match condition {
+                Condition::Tag(label) => {
+                    let label = param(label.clone());
+                    sql.push_str(&format!(
+                        "(tags.label CONTAINS {label} OR (splits ?? []).tags.label.flatten() CONTAINS {label})"
+                    ));
+                }
+                Condition::Description(text) => {
+                    let text = param(text.clone());
+                    sql.push_str(&format!("string::lowercase(description) CONTAINS {text}"));
+                }
+                Condition::Payee(text) => {
+                    let text = param(text.clone());
+                    sql.push_str(&format!("string::lowercase(payee ?? '') CONTAINS {text}"));
+                }
+                Condition::Amount(comparison, amount) => {
+                    let amount = param(amount.to_string());
+                    sql.push_str(&format!(
+                        "amount {} <decimal> {amount}",
+                        comparison.operator()
+                    ));
+                }
+                Condition::Date(comparison, start, end) => {
+                    let mut bound =
+                        |date: &DateTime<Utc>| format!("<datetime> {}", param(date.to_rfc3339()));
+                    sql.push_str(&match comparison {
+                        Comparison::Lower => format!("date < {}", bound(start)),
+                        Comparison::LowerOrEqual => format!("date < {}", bound(end)),
+                        Comparison::Greater => format!("date >= {}", bound(end)),
+                        Comparison::GreaterOrEqual => format!("date >= {}", bound(start)),
+                        Comparison::Equal => {
+                            format!("(date >= {} AND date < {})", bound(start), bound(end))
+                        }
+                        Comparison::NotEqual => {
+                            format!("(date < {} OR date >= {})", bound(start), bound(end))
+                        }
+                    });
+                }
+                Condition::Status(status) => {
+                    let status = param(
+                        match status {
+                            Status::Uncleared => "uncleared",
+                            Status::Cleared => "cleared",
+                            Status::Reconciled => "reconciled",
+                        }
+                        .to_string(),
+                    );
+                    sql.push_str(&format!("(status ?? 'uncleared') = {status}"));
+                }
+                Condition::Account(name) => {
+                    let name = param(name.clone());
+                    sql.push_str(&format!("account.name = {name}"));
+                }
+            },
+            Expression::Not(expression) => {
+                sql.push_str("!(");
+                expression.write(sql, params);
+                sql.push(')');
+            }
+            Expression::And(left, right) | Expression::Or(left, right) => {
+                sql.push('(');
+                left.write(sql, params);
+                sql.push_str(if matches!(self, Expression::And(..)) {
+                    " AND "
+                } else {
+                    " OR "
+                });
+                right.write(sql, params);
+                sql.push(')');
+            }
+        }
+    }
+}
+
+impl Filter {
+    /// SurrealQL condition on transactions, with the values of its parameters.
+    pub(crate) fn condition(&self) -> (String, Vec<(String, String)>) {
+        let mut sql = String::new();
+        let mut params = vec![];
+        self.0.write(&mut sql, &mut params);
+
+        (sql, params)
+    }
+
+    /// Append the condition of a filter, if any, to a query with ` AND `.
+    ///
+    /// Returns the values of its parameters, to bind with [`bind`].
+    pub(crate) fn push_condition(
+        filter: Option<&Self>,
+        query: &mut String,
+    ) -> Vec<(String, String)> {
+        let Some(filter) = filter else {
+            return vec![];
+        };
+        let (condition, params) = filter.condition();
+        query.push_str(" AND ");
+        query.push_str(&condition);
+
+        params
+    }
+}
+
+/// Bind the parameters of the condition of a filter to a query.
+pub(crate) fn bind<'a>(mut query: Query<'a, Db>, params: Vec<(String, String)>) -> Query<'a, Db> {
+    for param in params {
+        query = query.bind(param);
+    }
+
+    query
+}
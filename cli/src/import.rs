@@ -0,0 +1,347 @@
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    checkpoint::{AddCheckpointOptions, Checkpoint},
+    transaction::Tag,
+    AddTransactionOptions,
+};
+
+pub mod camt;
+pub mod csv;
+pub mod journal;
+pub mod legacy;
+pub mod mt940;
+pub mod ofx;
+pub mod qif;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Serde(serde_json::Error),
+    Database(crate::Error),
+    /// A value of the imported file could not be converted.
+    Invalid(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{error}"),
+            Error::Serde(error) => write!(f, "{error}"),
+            Error::Database(error) => write!(f, "{error}"),
+            Error::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serde(value)
+    }
+}
+
+impl From<crate::Error> for Error {
+    fn from(value: crate::Error) -> Self {
+        Self::Database(value)
+    }
+}
+
+impl From<surrealdb::Error> for Error {
+    fn from(value: surrealdb::Error) -> Self {
+        Self::Database(value.into())
+    }
+}
+
+/// Summary of what an import added to the database.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ImportReport {
+    pub account_name: String,
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account_id: RecordId,
+    /// The account did not exist and was created by the import.
+    pub account_created: bool,
+    pub transactions_imported: usize,
+    /// Labels of the tags that did not exist before the import.
+    pub tags_created: Vec<String>,
+    /// Entries that were not imported, with the reason why.
+    pub skipped: Vec<String>,
+}
+
+impl ImportReport {
+    pub(crate) fn new(account_name: String, account_id: RecordId, account_created: bool) -> Self {
+        Self {
+            account_name,
+            account_id,
+            account_created,
+            transactions_imported: 0,
+            tags_created: vec![],
+            skipped: vec![],
+        }
+    }
+}
+
+/// Get the id of the account named `name`, creating it with `currency` if it does not exist.
+pub(crate) async fn find_or_create_account(
+    db: &Surreal<Db>,
+    name: &str,
+    currency: &str,
+) -> Result<(RecordId, bool), Error> {
+    if let Some(account) = crate::list_account(db)
+        .await?
+        .into_iter()
+        .find(|account| account.name == name)
+    {
+        return Ok((account.id, false));
+    }
+
+    let account = crate::add_account(
+        db,
+        crate::AddAccountOptions {
+            name: name.to_string(),
+            currency: currency.to_string(),
+        },
+    )
+    .await?;
+
+    Ok((account.id, true))
+}
+
+/// Create tags that do not exist yet, leaving existing ones untouched.
+///
+/// Returns the labels of the created tags.
+pub(crate) async fn create_missing_tags(
+    db: &Surreal<Db>,
+    labels: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, Error> {
+    let existing = crate::get_tags(db).await?;
+    let mut created: Vec<String> = vec![];
+
+    for label in labels {
+        if label.is_empty()
+            || created.contains(&label)
+            || existing.iter().any(|tag| tag.label == label)
+        {
+            continue;
+        }
+
+        created.push(label);
+    }
+
+    crate::add_tags(
+        db,
+        created
+            .iter()
+            .map(|label| Tag {
+                label: label.clone(),
+                color: None,
+            })
+            .collect(),
+    )
+    .await?;
+
+    Ok(created)
+}
+
+/// References given by the bank to the transactions of an account.
+pub(crate) async fn fitids(db: &Surreal<Db>, account_id: RecordId) -> Result<Vec<String>, Error> {
+    Ok(db
+        .query("SELECT VALUE fitid FROM transaction WHERE account = $account_id AND fitid != NONE")
+        .bind(("account_id", account_id))
+        .await?
+        .take(0)?)
+}
+
+/// Transactions and balances read from a bank statement.
+#[derive(Debug, Default)]
+pub struct Statement {
+    /// Currency of the account, when the statement gives it.
+    pub currency: Option<String>,
+    /// Transactions with the reference given by the bank in `fitid`, if any.
+    pub transactions: Vec<AddTransactionOptions>,
+    /// Balances of the account given by the bank, with their date.
+    pub balances: Vec<(Decimal, chrono::DateTime<chrono::Utc>)>,
+    /// Entries that could not be read, with the reason why.
+    pub skipped: Vec<String>,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct StatementImport {
+    pub report: ImportReport,
+    /// Balances of the statement that were not already recorded.
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// Read a statement with `read` and import it in an existing account. Transactions
+/// whose bank reference is already used in the account are skipped, and balances
+/// of the statement are recorded as checkpoints.
+pub(crate) async fn import_statement_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+    read: impl FnOnce(&[u8]) -> Result<Statement, String>,
+) -> Result<StatementImport, Error> {
+    let account = crate::get_account(db, account_id.clone()).await?;
+    let statement = read(&std::fs::read(path)?).map_err(Error::Invalid)?;
+
+    if let Some(currency) = &statement.currency {
+        if !currency.eq_ignore_ascii_case(&account.data.currency) {
+            return Err(Error::Invalid(format!(
+                "the statement is in {currency} but the account is in {}",
+                account.data.currency
+            )));
+        }
+    }
+
+    let mut report = ImportReport::new(account.data.name, account_id.clone(), false);
+    report.skipped = statement.skipped;
+    report.tags_created = create_missing_tags(
+        db,
+        statement.transactions.iter().flat_map(|transaction| {
+            transaction
+                .tags
+                .iter()
+                .chain(
+                    transaction
+                        .splits
+                        .iter()
+                        .flat_map(|split| split.tags.iter()),
+                )
+                .map(|tag| tag.label.clone())
+        }),
+    )
+    .await?;
+
+    let mut fitids = fitids(db, account_id.clone()).await?;
+    let rules = crate::rule::enabled_rules(db).await?;
+
+    for transaction in statement.transactions {
+        if let Some(fitid) = &transaction.fitid {
+            if fitids.contains(fitid) {
+                report
+                    .skipped
+                    .push(format!("transaction {fitid}: already imported"));
+                continue;
+            }
+
+            fitids.push(fitid.clone());
+        }
+
+        crate::add_transaction_with_rules(db, &rules, account_id.clone(), transaction).await?;
+        report.transactions_imported += 1;
+    }
+
+    let checkpoints =
+        record_checkpoints(db, account_id, statement.balances, &source_name(path)).await?;
+
+    Ok(StatementImport {
+        report,
+        checkpoints,
+    })
+}
+
+/// Parse a number with an optional sign, with a comma or a dot as decimal mark.
+pub(crate) fn parse_number(value: &str, decimal_comma: bool) -> Option<Decimal> {
+    let value = value.trim();
+    let value = if decimal_comma {
+        value.replace('.', "").replace(',', ".")
+    } else if value.contains('.') {
+        value.replace(',', "")
+    } else {
+        // A lone comma followed by three digits separates thousands.
+        match value.split_once(',') {
+            Some((_, decimals)) if decimals.len() != 3 && !decimals.contains(',') => {
+                value.replace(',', ".")
+            }
+            _ => value.replace(',', ""),
+        }
+    };
+
+    value.replace(' ', "").parse().ok()
+}
+
+/// Name of an imported file, used as the source of the records it creates.
+pub(crate) fn source_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Record balances of an account as checkpoints, unless the same balance is
+/// already recorded at the same date.
+///
+/// Returns the created checkpoints.
+pub(crate) async fn record_checkpoints(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    balances: Vec<(Decimal, chrono::DateTime<chrono::Utc>)>,
+    source: &str,
+) -> Result<Vec<Checkpoint>, Error> {
+    let mut recorded = crate::checkpoint::list_checkpoints(db, account_id.clone()).await?;
+    let mut checkpoints = vec![];
+
+    for (balance, date) in balances {
+        if recorded
+            .iter()
+            .any(|checkpoint| checkpoint.date == date && checkpoint.balance == balance)
+        {
+            continue;
+        }
+
+        let checkpoint = crate::checkpoint::add_checkpoint(
+            db,
+            AddCheckpointOptions {
+                account: account_id.clone(),
+                date,
+                balance,
+                source: source.to_string(),
+            },
+        )
+        .await?;
+
+        recorded.push(checkpoint.clone());
+        checkpoints.push(checkpoint);
+    }
+
+    Ok(checkpoints)
+}
+
+/// Import a statement in an existing account, reading it as OFX, camt.053, MT940
+/// or QIF depending on the extension of the file.
+pub async fn import_statement(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+) -> Result<StatementImport, Error> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let read = match extension.as_str() {
+        "ofx" | "qfx" => ofx::read,
+        "xml" => camt::read,
+        "sta" | "mt940" | "940" | "txt" => mt940::read,
+        "qif" => qif::read,
+        _ => {
+            return Err(Error::Invalid(format!(
+                "unknown statement format: {}",
+                path.display()
+            )))
+        }
+    };
+
+    import_statement_file(db, account_id, path, read).await
+}
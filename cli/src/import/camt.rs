@@ -0,0 +1,339 @@
+//! Import of ISO 20022 camt.053 bank to customer statements.
+//!
+//! Each booked entry becomes a transaction, or one transaction per detail for
+//! batch entries, keeping the reference given by the bank to skip entries that
+//! were already imported.
+
+use quick_xml::events::Event;
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, Statement, StatementImport};
+use crate::AddTransactionOptions;
+
+/// An element of an XML document, without its namespace.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    /// Get the element at `path`, made of element names separated by `/`.
+    fn find(&self, path: &str) -> Option<&Element> {
+        path.split('/').try_fold(self, |element, name| {
+            element.children.iter().find(|child| child.name == name)
+        })
+    }
+
+    fn text(&self, path: &str) -> Option<&str> {
+        self.find(path)
+            .map(|element| element.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+
+    fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn parse(content: &str) -> Result<Element, String> {
+    let mut reader = quick_xml::Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut stack = vec![Element::default()];
+    let element = |start: &quick_xml::events::BytesStart| -> Result<Element, String> {
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).to_string(),
+            attributes: start
+                .attributes()
+                .map(|attribute| {
+                    let attribute = attribute.map_err(|error| error.to_string())?;
+                    Ok((
+                        String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_string(),
+                        attribute
+                            .unescape_value()
+                            .map_err(|error| error.to_string())?
+                            .to_string(),
+                    ))
+                })
+                .collect::<Result<_, String>>()?,
+            ..Default::default()
+        })
+    };
+
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Start(start) => stack.push(element(&start)?),
+            Event::Empty(start) => stack
+                .last_mut()
+                .expect("the root is never closed")
+                .children
+                .push(element(&start)?),
+            Event::Text(text) => stack
+                .last_mut()
+                .expect("the root is never closed")
+                .text
+                .push_str(&text.unescape().map_err(|error| error.to_string())?),
+            Event::CData(text) => stack
+                .last_mut()
+                .expect("the root is never closed")
+                .text
+                .push_str(&String::from_utf8_lossy(&text)),
+            Event::End(_) => {
+                let element = stack.pop().expect("the reader checks that tags match");
+                stack
+                    .last_mut()
+                    .ok_or("unexpected closing tag")?
+                    .children
+                    .push(element);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    match stack.pop() {
+        Some(root) if stack.is_empty() => Ok(root),
+        _ => Err("unexpected end of document".to_string()),
+    }
+}
+
+/// Get the date of an element holding either a `Dt` or a `DtTm` element.
+fn parse_date(element: Option<&Element>) -> Option<chrono::DateTime<chrono::Utc>> {
+    let element = element?;
+    let date = element.text("Dt").or_else(|| element.text("DtTm"))?;
+
+    chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// Get the signed amount of an element holding `Amt` and `CdtDbtInd` elements.
+fn parse_amount(element: &Element, indicator: Option<&str>) -> Result<Decimal, String> {
+    let amount = element.text("Amt").ok_or("missing amount")?;
+    let amount: Decimal = amount
+        .parse()
+        .map_err(|_| format!("invalid amount: {amount}"))?;
+
+    match element.text("CdtDbtInd").or(indicator) {
+        Some("DBIT") => Ok(-amount),
+        Some("CRDT") => Ok(amount),
+        indicator => Err(format!(
+            "invalid credit or debit indicator: {}",
+            indicator.unwrap_or_default()
+        )),
+    }
+}
+
+/// Name of a party, written directly in `Nm` or in `Pty/Nm` since version 8 of camt.053.
+fn party_name<'a>(details: &'a Element, party: &str) -> Option<&'a str> {
+    let party = details.find("RltdPties")?.find(party)?;
+
+    party.text("Nm").or_else(|| party.text("Pty/Nm"))
+}
+
+/// Read a statement of a camt.053 document. Documents holding the statements of
+/// several accounts are rejected.
+pub fn read(content: &[u8]) -> Result<Statement, String> {
+    let document = parse(&String::from_utf8_lossy(content))?;
+    let statements = document
+        .find("Document/BkToCstmrStmt")
+        .ok_or("not a camt.053 document, the BkToCstmrStmt element is missing")?;
+
+    let mut result = Statement::default();
+    let mut account = None;
+
+    for statement in statements.all("Stmt") {
+        let id = statement
+            .text("Acct/Id/IBAN")
+            .or_else(|| statement.text("Acct/Id/Othr/Id"));
+        if account.is_some() && account != id {
+            return Err(
+                "the file holds the statements of several accounts, import them separately"
+                    .to_string(),
+            );
+        }
+        account = id;
+
+        // The currency of the account is optional, the one of the amounts is not.
+        if let Some(currency) = statement.text("Acct/Ccy").or_else(|| {
+            statement
+                .find("Bal/Amt")
+                .and_then(|amount| amount.attribute("Ccy"))
+        }) {
+            result.currency = Some(currency.to_string());
+        }
+
+        for balance in statement.all("Bal") {
+            if balance.text("Tp/CdOrPrtry/Cd") != Some("CLBD") {
+                continue;
+            }
+
+            if let (Ok(amount), Some(date)) =
+                (parse_amount(balance, None), parse_date(balance.find("Dt")))
+            {
+                result.balances.push((amount, date));
+            }
+        }
+
+        for (index, entry) in statement.all("Ntry").enumerate() {
+            let reference = entry
+                .text("AcctSvcrRef")
+                .or_else(|| entry.text("NtryRef"))
+                .map(str::to_string);
+            let name = reference.clone().unwrap_or_else(|| format!("#{index}"));
+
+            match read_entry(entry, reference) {
+                Ok(transactions) => result.transactions.extend(transactions),
+                Err(error) => result.skipped.push(format!("entry {name}: {error}")),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn read_entry(
+    entry: &Element,
+    reference: Option<String>,
+) -> Result<Vec<AddTransactionOptions>, String> {
+    // The status is written directly before version 8 of camt.053.
+    let status = entry.text("Sts").or_else(|| entry.text("Sts/Cd"));
+    if status.is_some_and(|status| status != "BOOK") {
+        return Err(format!(
+            "not booked, its status is {}",
+            status.unwrap_or_default()
+        ));
+    }
+
+    let amount = parse_amount(entry, None)?;
+    let indicator = entry.text("CdtDbtInd");
+    let date = parse_date(entry.find("BookgDt"));
+    let value_date = parse_date(entry.find("ValDt"));
+    let date = date.or(value_date).ok_or("missing booking date")?;
+
+    let details = entry
+        .find("NtryDtls")
+        .map(|details| details.all("TxDtls").collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    // Batch entries list the transactions they are made of, with their own amounts.
+    let batch = details.len() > 1
+        && details
+            .iter()
+            .all(|details| details.find("Amt").is_some() || details.find("AmtDtls").is_some());
+
+    if !batch {
+        return Ok(vec![to_options(
+            entry,
+            details.first().copied(),
+            amount,
+            date,
+            value_date,
+            reference,
+        )]);
+    }
+
+    details
+        .into_iter()
+        .enumerate()
+        .map(|(index, details)| {
+            let amount = match details.find("Amt") {
+                Some(_) => parse_amount(details, indicator)?,
+                None => parse_amount(
+                    details
+                        .find("AmtDtls/TxAmt")
+                        .ok_or("missing amount of a transaction of the batch")?,
+                    details.text("CdtDbtInd").or(indicator),
+                )?,
+            };
+            let reference = details
+                .text("Refs/AcctSvcrRef")
+                .map(str::to_string)
+                .or_else(|| {
+                    reference
+                        .as_ref()
+                        .map(|reference| format!("{reference}/{index}"))
+                });
+
+            Ok(to_options(
+                entry,
+                Some(details),
+                amount,
+                date,
+                value_date,
+                reference,
+            ))
+        })
+        .collect()
+}
+
+fn to_options(
+    entry: &Element,
+    details: Option<&Element>,
+    amount: Decimal,
+    date: chrono::DateTime<chrono::Utc>,
+    value_date: Option<chrono::DateTime<chrono::Utc>>,
+    reference: Option<String>,
+) -> AddTransactionOptions {
+    // The counterparty is the creditor of spendings and the debtor of incomes.
+    let (party, party_account) = if amount.is_sign_negative() {
+        ("Cdtr", "RltdPties/CdtrAcct/Id/IBAN")
+    } else {
+        ("Dbtr", "RltdPties/DbtrAcct/Id/IBAN")
+    };
+    let payee = details.and_then(|details| party_name(details, party));
+    let payee_iban = details.and_then(|details| details.text(party_account));
+
+    let remittance = details
+        .and_then(|details| details.find("RmtInf"))
+        .and_then(|information| {
+            let unstructured = information
+                .all("Ustrd")
+                .map(|line| line.text.trim())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            if unstructured.is_empty() {
+                information.text("Strd/CdtrRefInf/Ref").map(str::to_string)
+            } else {
+                Some(unstructured)
+            }
+        });
+    let description = remittance
+        .or_else(|| details.and_then(|details| details.text("AddtlTxInf").map(str::to_string)))
+        .or_else(|| entry.text("AddtlNtryInf").map(str::to_string))
+        .or_else(|| payee.map(str::to_string))
+        .unwrap_or_default();
+
+    AddTransactionOptions {
+        amount,
+        description,
+        date: Some(date),
+        value_date: value_date.filter(|value_date| *value_date != date),
+        fitid: reference,
+        payee: payee.map(str::to_string),
+        payee_iban: payee_iban.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// Import a camt.053 statement in an existing account. Entries whose reference
+/// is already used in the account are skipped.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+) -> Result<StatementImport, Error> {
+    super::import_statement_file(db, account_id, path, read).await
+}
@@ -0,0 +1,386 @@
+//! Import of CSV bank statements, read with a mapping profile describing
+//! how the bank lays out its exports.
+
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, ImportReport};
+use crate::AddTransactionOptions;
+
+/// A column of the file, either its index starting at 0 or the label of its header.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Header(String),
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Column::Index(index) => write!(f, "#{index}"),
+            Column::Header(header) => write!(f, "\"{header}\""),
+        }
+    }
+}
+
+/// Where the amount of a transaction is read from, and how its sign is given.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AmountColumns {
+    /// A single signed column. Set `negate` when the bank writes spendings as
+    /// positive amounts, like most credit card exports.
+    Signed {
+        column: Column,
+        #[serde(default)]
+        negate: bool,
+    },
+    /// Spendings and incomes in two columns, one of them being empty on each row.
+    DebitCredit { debit: Column, credit: Column },
+    /// An unsigned amount with a column telling if it is a spending, when its
+    /// value is `debit`, or an income.
+    Indicator {
+        column: Column,
+        indicator: Column,
+        debit: String,
+    },
+}
+
+fn default_delimiter() -> String {
+    ",".to_string()
+}
+
+fn default_decimal_separator() -> String {
+    ".".to_string()
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+fn default_has_headers() -> bool {
+    true
+}
+
+/// Mapping of the columns and formats of the CSV exports of a bank.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CsvProfile {
+    /// Unique name of the profile, usually the name of the bank.
+    pub name: String,
+    /// Single ASCII character separating the columns.
+    #[serde(default = "default_delimiter")]
+    pub delimiter: String,
+    /// Separator of the fractional part of amounts, other separators like spaces
+    /// or thousands separators are ignored.
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: String,
+    /// Label of the encoding of the file, like `utf-8` or `windows-1252`.
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+    /// Lines to ignore at the beginning of the file, before the header.
+    #[serde(default)]
+    pub skip_rows: usize,
+    /// The first row after the skipped ones holds the labels of the columns.
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    pub date: Column,
+    /// Format of dates, as described in https://docs.rs/chrono/latest/chrono/format/strftime.
+    pub date_format: String,
+    /// Columns joined with a space to make the description.
+    pub description: Vec<Column>,
+    pub amount: AmountColumns,
+}
+
+/// Transactions read from a file with a profile.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct CsvImport {
+    pub transactions: Vec<AddTransactionOptions>,
+    /// Nothing is added to the account when this is a dry run.
+    pub dry_run: bool,
+    pub report: ImportReport,
+}
+
+/// Create or replace the profile with the same name.
+pub async fn save_profile(
+    db: &Surreal<Db>,
+    profile: CsvProfile,
+) -> Result<CsvProfile, crate::Error> {
+    if profile.name.is_empty() {
+        return Err(crate::Error::Invalid("a profile needs a name".to_string()));
+    }
+
+    profile.check().map_err(crate::Error::Invalid)?;
+
+    let profile: Option<CsvProfile> = db
+        .upsert(("csv_profile", profile.name.clone()))
+        .content(profile)
+        .await?;
+
+    profile.ok_or(crate::Error::RecordNotFound)
+}
+
+pub async fn list_profiles(db: &Surreal<Db>) -> Result<Vec<CsvProfile>, surrealdb::Error> {
+    db.query("SELECT * FROM csv_profile ORDER BY name")
+        .await?
+        .take(0)
+}
+
+pub async fn get_profile(db: &Surreal<Db>, name: &str) -> Result<CsvProfile, crate::Error> {
+    let profile: Option<CsvProfile> = db.select(("csv_profile", name)).await?;
+
+    profile.ok_or(crate::Error::RecordNotFound)
+}
+
+pub async fn delete_profile(db: &Surreal<Db>, name: &str) -> Result<(), surrealdb::Error> {
+    let _: Option<CsvProfile> = db.delete(("csv_profile", name)).await?;
+
+    Ok(())
+}
+
+/// Parse an amount written with `decimal_separator`, ignoring currency symbols and
+/// thousands separators. Amounts between parentheses or followed by a minus are negative.
+fn parse_amount(value: &str, decimal_separator: char) -> Option<Decimal> {
+    let value = value.trim();
+    let negative = (value.starts_with('(') && value.ends_with(')')) || value.ends_with('-');
+    let number: String = value
+        .chars()
+        .filter_map(|c| match c {
+            '0'..='9' | '-' | '+' => Some(c),
+            c if c == decimal_separator => Some('.'),
+            _ => None,
+        })
+        .collect();
+    let number = number.trim_end_matches('-');
+
+    if number.is_empty() {
+        return None;
+    }
+
+    let amount: Decimal = number.parse().ok()?;
+
+    Some(if negative { -amount } else { amount })
+}
+
+fn parse_date(value: &str, format: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let value = value.trim();
+
+    chrono::NaiveDate::parse_from_str(value, format)
+        .map(|date| date.and_time(chrono::NaiveTime::MIN))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, format))
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+impl CsvProfile {
+    fn check(&self) -> Result<(u8, char, &'static encoding_rs::Encoding), String> {
+        let delimiter = match self.delimiter.as_bytes() {
+            [delimiter] => *delimiter,
+            _ => return Err(format!("invalid delimiter: {}", self.delimiter)),
+        };
+        let mut chars = self.decimal_separator.chars();
+        let decimal_separator = match (chars.next(), chars.next()) {
+            (Some(separator), None) => separator,
+            _ => {
+                return Err(format!(
+                    "invalid decimal separator: {}",
+                    self.decimal_separator
+                ))
+            }
+        };
+        let encoding = encoding_rs::Encoding::for_label(self.encoding.as_bytes())
+            .ok_or_else(|| format!("unknown encoding: {}", self.encoding))?;
+
+        Ok((delimiter, decimal_separator, encoding))
+    }
+
+    fn index(&self, column: &Column, headers: &[String]) -> Result<usize, String> {
+        match column {
+            Column::Index(index) => Ok(*index),
+            Column::Header(header) => headers
+                .iter()
+                .position(|label| label.trim() == header)
+                .ok_or_else(|| format!("column {column} not found in the header")),
+        }
+    }
+
+    fn to_options(
+        &self,
+        record: &::csv::StringRecord,
+        headers: &[String],
+        decimal_separator: char,
+    ) -> Result<AddTransactionOptions, String> {
+        let field = |column: &Column| -> Result<&str, String> {
+            record
+                .get(self.index(column, headers)?)
+                .ok_or_else(|| format!("column {column} is missing"))
+        };
+        let amount = |column: &Column| -> Result<Option<Decimal>, String> {
+            let value = field(column)?;
+            match parse_amount(value, decimal_separator) {
+                Some(amount) => Ok(Some(amount)),
+                None if value.trim().is_empty() => Ok(None),
+                None => Err(format!("invalid amount: {value}")),
+            }
+        };
+
+        let date = field(&self.date)?;
+        let date = parse_date(date, &self.date_format).ok_or_else(|| {
+            format!(
+                "invalid date: {date}, expected the format {}",
+                self.date_format
+            )
+        })?;
+
+        let amount = match &self.amount {
+            AmountColumns::Signed { column, negate } => {
+                let amount = amount(column)?.ok_or("empty amount")?;
+                if *negate {
+                    -amount
+                } else {
+                    amount
+                }
+            }
+            AmountColumns::DebitCredit { debit, credit } => {
+                match (amount(debit)?, amount(credit)?) {
+                    (None, None) => return Err("empty amount".to_string()),
+                    (debit, credit) => {
+                        credit.unwrap_or_default().abs() - debit.unwrap_or_default().abs()
+                    }
+                }
+            }
+            AmountColumns::Indicator {
+                column,
+                indicator,
+                debit,
+            } => {
+                let amount = amount(column)?.ok_or("empty amount")?.abs();
+                if field(indicator)?.trim().eq_ignore_ascii_case(debit) {
+                    -amount
+                } else {
+                    amount
+                }
+            }
+        };
+
+        let mut description = vec![];
+        for column in &self.description {
+            let value = field(column)?.split_whitespace().collect::<Vec<_>>();
+            if !value.is_empty() {
+                description.push(value.join(" "));
+            }
+        }
+
+        Ok(AddTransactionOptions {
+            amount,
+            description: description.join(" "),
+            tags: vec![],
+            splits: vec![],
+            date: Some(date),
+            ..Default::default()
+        })
+    }
+
+    /// Read the transactions of a file. Rows that cannot be read are returned with
+    /// the reason why, and are not imported.
+    pub fn read(
+        &self,
+        content: &[u8],
+    ) -> Result<(Vec<AddTransactionOptions>, Vec<String>), String> {
+        let (delimiter, decimal_separator, encoding) = self.check()?;
+        let (content, _, _) = encoding.decode(content);
+
+        // Skip lines by hand, because the lines before the header often have
+        // another number of columns or unbalanced quotes.
+        let mut lines = content.split_inclusive('\n');
+        for _ in 0..self.skip_rows {
+            lines.next();
+        }
+        let content: String = lines.collect();
+
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(self.has_headers)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+
+        let headers: Vec<String> = if self.has_headers {
+            reader
+                .headers()
+                .map_err(|error| error.to_string())?
+                .iter()
+                .map(str::to_string)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let mut transactions = vec![];
+        let mut skipped = vec![];
+        let line = |position: Option<&::csv::Position>| {
+            position
+                .map(|position| position.line() as usize + self.skip_rows)
+                .unwrap_or_default()
+        };
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(error) => {
+                    skipped.push(format!("line {}: {error}", line(error.position())));
+                    continue;
+                }
+            };
+            let line = line(record.position());
+
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            match self.to_options(&record, &headers, decimal_separator) {
+                Ok(options) => transactions.push(options),
+                Err(error) => skipped.push(format!("line {line}: {error}")),
+            }
+        }
+
+        Ok((transactions, skipped))
+    }
+}
+
+/// Import the transactions of a CSV file in an existing account. With `dry_run`,
+/// the file is only read, to preview what would be imported.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+    profile: &CsvProfile,
+    dry_run: bool,
+) -> Result<CsvImport, Error> {
+    let account = crate::get_account(db, account_id.clone()).await?;
+    let content = std::fs::read(path)?;
+    let (transactions, skipped) = profile.read(&content).map_err(Error::Invalid)?;
+
+    let mut report = ImportReport::new(account.data.name, account_id.clone(), false);
+    report.skipped = skipped;
+
+    if !dry_run {
+        let rules = crate::rule::enabled_rules(db).await?;
+        for options in &transactions {
+            crate::add_transaction_with_rules(db, &rules, account_id.clone(), options.clone())
+                .await?;
+            report.transactions_imported += 1;
+        }
+    }
+
+    Ok(CsvImport {
+        transactions,
+        dry_run,
+        report,
+    })
+}
@@ -0,0 +1,1125 @@
+//! Import of plain text accounting journals, in the common subset of the beancount
+//! and hledger syntaxes.
+//!
+//! Asset and liability accounts of the journal become accounts, named without their
+//! root (`Assets:Bank:Checking` becomes `Bank:Checking`). Income and expense accounts
+//! become tags of the transactions posted to them. Price directives become exchange
+//! rates and balance assertions become checkpoints.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, ImportReport};
+use crate::{
+    checkpoint::Checkpoint,
+    exchange::AddExchangeRateOptions,
+    transaction::{Split, Status, Tag},
+    AddTransactionOptions, AddTransferOptions,
+};
+
+#[derive(Clone, Debug)]
+struct Amount {
+    number: Decimal,
+    /// Empty for amounts written without commodity.
+    commodity: String,
+}
+
+#[derive(Debug)]
+struct Posting {
+    account: String,
+    amount: Option<Amount>,
+    /// Total cost of the amount in another commodity, signed like the amount.
+    cost: Option<Amount>,
+    tags: Vec<String>,
+}
+
+impl Posting {
+    /// Amount the posting weighs in the balance of its transaction.
+    fn weight(&self) -> Option<&Amount> {
+        self.cost.as_ref().or(self.amount.as_ref())
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    line: usize,
+    date: chrono::NaiveDate,
+    status: Status,
+    payee: Option<String>,
+    description: String,
+    tags: Vec<String>,
+    fitid: Option<String>,
+    postings: Vec<Posting>,
+}
+
+/// Directives of a journal that can be mapped to records of the store.
+#[derive(Debug, Default)]
+struct Journal {
+    /// Currencies of the accounts declared with an `open` directive.
+    currencies: BTreeMap<String, String>,
+    entries: Vec<Entry>,
+    /// Prices of commodities: date, base, rate and quote.
+    prices: Vec<(chrono::NaiveDate, String, Decimal, String)>,
+    /// Balances of accounts at the end of a day.
+    balances: Vec<(usize, chrono::NaiveDate, String, Amount)>,
+    skipped: Vec<String>,
+}
+
+enum Kind {
+    Asset,
+    Category,
+    Equity,
+    Unknown,
+}
+
+/// Kind of an account, from the name of its root like hledger does.
+fn kind(account: &str) -> Kind {
+    let root = account.split(':').next().unwrap_or_default().to_lowercase();
+
+    if root.starts_with("asset") || root.starts_with("liabilit") {
+        Kind::Asset
+    } else if root.starts_with("income")
+        || root.starts_with("revenue")
+        || root.starts_with("expense")
+    {
+        Kind::Category
+    } else if root.starts_with("equity") {
+        Kind::Equity
+    } else {
+        Kind::Unknown
+    }
+}
+
+/// Name of an account of the store, the name of the journal account without its root.
+fn account_name(account: &str) -> &str {
+    account
+        .split_once(':')
+        .map(|(_, name)| name)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(account)
+}
+
+/// Label of the tag of an income or expense account, if any.
+fn category(account: &str) -> Option<String> {
+    match kind(account) {
+        Kind::Category => account
+            .split_once(':')
+            .map(|(_, name)| name)
+            .filter(|name| !name.is_empty() && *name != crate::export::UNCATEGORIZED)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Parse a date written with `-`, `/` or `.` separators. Secondary dates of hledger,
+/// after a `=`, are ignored.
+fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
+    let value = value.split('=').next()?.replace(['/', '.'], "-");
+
+    chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+}
+
+/// Split a line at its comment, ignoring `;` between double quotes.
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quoted = false;
+
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return (line[..index].trim_end(), Some(line[index + 1..].trim())),
+            _ => {}
+        }
+    }
+
+    (line.trim_end(), None)
+}
+
+/// Read the strings between double quotes of a beancount line, and the text after them.
+fn strings(line: &str) -> (Vec<String>, &str) {
+    let mut strings = vec![];
+    let mut rest = line.trim_start();
+
+    while let Some(text) = rest.strip_prefix('"') {
+        let mut string = String::new();
+        let mut chars = text.char_indices();
+        let mut end = text.len();
+
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    end = index + 1;
+                    break;
+                }
+                '\\' => string.extend(chars.next().map(|(_, c)| c)),
+                c => string.push(c),
+            }
+        }
+
+        strings.push(string);
+        rest = text[end..].trim_start();
+    }
+
+    (strings, rest)
+}
+
+/// Read the tags of an hledger comment, `name:` or `name:value` separated by commas,
+/// or ledger tags like `:name:other:`.
+fn comment_tags(comment: &str) -> Vec<(String, String)> {
+    let comment = comment.trim();
+
+    if comment.len() > 1 && comment.starts_with(':') && comment.ends_with(':') {
+        return comment
+            .split(':')
+            .filter(|tag| !tag.is_empty() && !tag.contains(char::is_whitespace))
+            .map(|tag| (tag.to_string(), String::new()))
+            .collect();
+    }
+
+    comment
+        .split(',')
+        .filter_map(|part| {
+            let (name, value) = part.split_once(':')?;
+            let name = name.trim().rsplit(char::is_whitespace).next()?;
+
+            (!name.is_empty()).then(|| (name.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parse an amount with its commodity before or after the number, like `-12.50 EUR`,
+/// `$12.50` or `-$12.50`.
+fn parse_amount(value: &str, decimal_comma: bool) -> Option<Amount> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value.trim_start()),
+        None => (false, value.strip_prefix('+').unwrap_or(value).trim_start()),
+    };
+    let is_number = |c: char| c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+');
+
+    let (number, commodity) = if value.starts_with(is_number) {
+        let end = value.find(|c| !is_number(c)).unwrap_or(value.len());
+        (&value[..end], value[end..].trim())
+    } else {
+        let start = value.find(is_number)?;
+        (value[start..].trim(), value[..start].trim())
+    };
+
+    let number = super::parse_number(number, decimal_comma)?;
+    let commodity = commodity.trim_matches('"');
+    if commodity.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(Amount {
+        number: if negative { -number } else { number },
+        commodity: commodity.to_string(),
+    })
+}
+
+/// Total cost of an amount bought at a unit price, or at a total price when `total`
+/// is set, signed like the amount.
+fn cost(amount: &Amount, price: Amount, total: bool) -> Amount {
+    let number = if total {
+        price.number.abs()
+    } else {
+        (price.number * amount.number).abs()
+    };
+
+    Amount {
+        number: if amount.number.is_sign_negative() {
+            -number
+        } else {
+            number
+        },
+        ..price
+    }
+}
+
+/// Parse a posting: `[flag] account  amount [{cost}] [@ price | @@ total] [= balance]`.
+///
+/// Returns the posting, and the balance asserted after it if any.
+fn parse_posting(text: &str, decimal_comma: bool) -> Result<(Posting, Option<Amount>), String> {
+    let text = text
+        .strip_prefix(['*', '!'])
+        .map(str::trim_start)
+        .unwrap_or(text);
+
+    // hledger accounts may hold single spaces, so amounts are separated by two
+    // spaces or a tab. Beancount accounts never hold spaces.
+    let (account, rest) = match text.find("  ").or_else(|| text.find('\t')) {
+        Some(index) => (&text[..index], text[index..].trim()),
+        None => match text.split_once(' ') {
+            Some((account, rest)) => (account, rest.trim()),
+            None => (text, ""),
+        },
+    };
+    let account = account.trim().trim_matches(['[', ']']);
+
+    let (rest, assertion) = match rest.split_once('=') {
+        Some((rest, assertion)) => {
+            let assertion = assertion.trim_start_matches('=');
+            (
+                rest.trim(),
+                Some(
+                    parse_amount(assertion, decimal_comma)
+                        .ok_or_else(|| format!("invalid balance assertion: {assertion}"))?,
+                ),
+            )
+        }
+        None => (rest, None),
+    };
+
+    let (rest, price) = match rest.split_once('@') {
+        Some((rest, price)) => (rest.trim(), Some(price)),
+        None => (rest, None),
+    };
+
+    // Cost of a lot in beancount, per unit or as a total between double braces.
+    let (rest, lot) = match rest.split_once('{') {
+        Some((rest, lot)) => (rest.trim(), Some(lot)),
+        None => (rest, None),
+    };
+
+    let amount = if rest.is_empty() {
+        None
+    } else {
+        Some(parse_amount(rest, decimal_comma).ok_or_else(|| format!("invalid amount: {rest}"))?)
+    };
+
+    let cost = match (&amount, price, lot) {
+        (Some(amount), Some(price), _) => {
+            let (total, price) = match price.strip_prefix('@') {
+                Some(price) => (true, price),
+                None => (false, price),
+            };
+            let price = parse_amount(price, decimal_comma)
+                .ok_or_else(|| format!("invalid price: {price}"))?;
+            Some(cost(amount, price, total))
+        }
+        (Some(amount), None, Some(lot)) => {
+            let (total, lot) = match lot.strip_prefix('{') {
+                Some(lot) => (true, lot),
+                None => (false, lot),
+            };
+            let lot = lot.trim_end_matches('}');
+            let price = lot.split(',').next().unwrap_or_default();
+            let price =
+                parse_amount(price, decimal_comma).ok_or_else(|| format!("invalid cost: {lot}"))?;
+            Some(cost(amount, price, total))
+        }
+        _ => None,
+    };
+
+    Ok((
+        Posting {
+            account: account.to_string(),
+            amount,
+            cost,
+            tags: vec![],
+        },
+        assertion,
+    ))
+}
+
+/// Parse the header of a beancount transaction, after its date:
+/// `flag ["payee"] "narration" #tag ^link`.
+fn parse_beancount_header(entry: &mut Entry, header: &str) {
+    let (strings, rest) = strings(header);
+    let mut strings = strings.into_iter();
+
+    match (strings.next(), strings.next()) {
+        (Some(payee), Some(narration)) => {
+            entry.payee = Some(payee).filter(|payee| !payee.is_empty());
+            entry.description = narration;
+        }
+        (Some(narration), None) => entry.description = narration,
+        _ => {}
+    }
+
+    // Links are kept as tags.
+    entry.tags.extend(
+        rest.split_whitespace()
+            .filter_map(|word| word.strip_prefix('#').or_else(|| word.strip_prefix('^')))
+            .map(str::to_string),
+    );
+}
+
+/// Parse the header of an hledger or ledger transaction, after its date:
+/// `[=date] [status] [(code)] payee | note  ; comment`.
+fn parse_ledger_header(entry: &mut Entry, header: &str, comment: Option<&str>) {
+    let mut header = header.trim_start();
+
+    // Cleared transactions of ledger were checked against a statement, pending
+    // ones only appear on it.
+    if let Some(rest) = header.strip_prefix('*') {
+        entry.status = Status::Reconciled;
+        header = rest.trim_start();
+    } else if let Some(rest) = header.strip_prefix('!') {
+        entry.status = Status::Cleared;
+        header = rest.trim_start();
+    }
+
+    if header.starts_with('(') {
+        header = header
+            .split_once(')')
+            .map(|(_, rest)| rest.trim_start())
+            .unwrap_or_default();
+    }
+
+    match header.split_once('|') {
+        Some((payee, note)) => {
+            entry.payee = Some(payee.trim().to_string()).filter(|payee| !payee.is_empty());
+            entry.description = note.trim().to_string();
+        }
+        None => entry.description = header.trim().to_string(),
+    }
+
+    if let Some(comment) = comment {
+        let tags = comment_tags(comment);
+
+        // Ledger writes the note of a transaction as a comment of its payee.
+        if tags.is_empty() && entry.payee.is_none() && !comment.is_empty() {
+            entry.payee = Some(std::mem::take(&mut entry.description));
+            entry.description = comment.to_string();
+        }
+
+        apply_tags(entry, None, tags);
+    }
+}
+
+/// Add tags read from a comment or metadata to a transaction, or to its last posting.
+fn apply_tags(entry: &mut Entry, posting: Option<usize>, tags: Vec<(String, String)>) {
+    for (name, value) in tags {
+        match name.as_str() {
+            "fitid" => entry.fitid = Some(value),
+            "status" => {
+                entry.status = match value.as_str() {
+                    "cleared" => Status::Cleared,
+                    "reconciled" => Status::Reconciled,
+                    _ => Status::Uncleared,
+                }
+            }
+            // Tags of the postings of beancount files exported by this crate.
+            "tags" => {
+                let tags = value.split_whitespace().map(str::to_string);
+                match posting.and_then(|posting| entry.postings.get_mut(posting)) {
+                    Some(posting) => posting.tags.extend(tags),
+                    None => entry.tags.extend(tags),
+                }
+            }
+            _ => match posting.and_then(|posting| entry.postings.get_mut(posting)) {
+                Some(posting) => posting.tags.push(name),
+                None => entry.tags.push(name),
+            },
+        }
+    }
+}
+
+/// Read a beancount metadata line, `key: value`.
+fn metadata(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+
+    // Lower case account names of hledger are not followed by a space.
+    if !value.is_empty() && !value.starts_with(char::is_whitespace)
+        || !key.starts_with(|c: char| c.is_ascii_lowercase())
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = match strings(value) {
+        (strings, "") if strings.len() == 1 => strings[0].clone(),
+        _ => value.to_string(),
+    };
+
+    Some((key.to_string(), value))
+}
+
+fn read(content: &str) -> Journal {
+    let mut journal = Journal::default();
+    let mut decimal_comma = false;
+    let mut entry: Option<Entry> = None;
+    // Indentation of the postings of the current transaction, deeper lines are
+    // metadata of the last posting.
+    let mut indentation = usize::MAX;
+
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim_end_matches('\r');
+        let trimmed = line.trim_start();
+
+        if trimmed.is_empty() {
+            journal.entries.extend(entry.take());
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            let Some(current) = entry.as_mut() else {
+                // Sub-directives of accounts and commodities.
+                continue;
+            };
+            let depth = line.len() - trimmed.len();
+
+            if let Some(comment) = trimmed.strip_prefix(';') {
+                // Comments after a posting belong to it.
+                let posting = current.postings.len().checked_sub(1);
+                apply_tags(current, posting, comment_tags(comment));
+            } else if let Some(metadata) = metadata(trimmed) {
+                let posting = current
+                    .postings
+                    .len()
+                    .checked_sub(1)
+                    .filter(|_| depth > indentation);
+                apply_tags(current, posting, vec![metadata]);
+            } else {
+                indentation = indentation.min(depth);
+                let (text, comment) = split_comment(trimmed);
+
+                match parse_posting(text, decimal_comma) {
+                    // Unbalanced virtual postings of hledger do not move money.
+                    Ok((posting, _)) if posting.account.starts_with('(') => {
+                        journal.skipped.push(format!(
+                            "line {number}: virtual posting to {} ignored",
+                            posting.account
+                        ));
+                    }
+                    Ok((posting, assertion)) => {
+                        if let Some(assertion) = assertion {
+                            journal.balances.push((
+                                number,
+                                current.date,
+                                posting.account.clone(),
+                                assertion,
+                            ));
+                        }
+                        current.postings.push(posting);
+                        if let Some(comment) = comment {
+                            let posting = current.postings.len() - 1;
+                            apply_tags(current, Some(posting), comment_tags(comment));
+                        }
+                    }
+                    Err(error) => {
+                        journal
+                            .skipped
+                            .push(format!("line {number}: {error}, transaction skipped"));
+                        entry = None;
+                    }
+                }
+            }
+            continue;
+        }
+
+        journal.entries.extend(entry.take());
+        indentation = usize::MAX;
+
+        if trimmed.starts_with([';', '#', '*', '%', '|']) {
+            continue;
+        }
+
+        let (text, comment) = split_comment(trimmed);
+        let mut words = text.split_whitespace();
+        let first = words.next().unwrap_or_default();
+
+        match first {
+            "account" => {
+                // hledger declares accounts without currency.
+            }
+            "P" => {
+                let (Some(date), Some(base), Some(price)) = (
+                    words.next().and_then(parse_date),
+                    words.next(),
+                    parse_amount(&words.collect::<Vec<_>>().join(" "), decimal_comma),
+                ) else {
+                    journal
+                        .skipped
+                        .push(format!("line {number}: invalid price directive"));
+                    continue;
+                };
+                journal.prices.push((
+                    date,
+                    base.trim_matches('"').to_string(),
+                    price.number,
+                    price.commodity,
+                ));
+            }
+            "decimal-mark" => decimal_comma = words.next() == Some(","),
+            "commodity" | "option" | "alias" | "payee" | "tag" => {}
+            _ => {
+                let Some(date) = parse_date(first) else {
+                    journal.skipped.push(format!(
+                        "line {number}: {first} directives are not supported"
+                    ));
+                    continue;
+                };
+                let rest = text[first.len()..].trim_start();
+                let directive = rest.split_whitespace().next().unwrap_or_default();
+
+                match directive {
+                    "open" => {
+                        let mut words = rest.split_whitespace().skip(1);
+                        if let (Some(account), Some(currencies)) = (words.next(), words.next()) {
+                            // Accounts holding several commodities are kept with
+                            // the first one.
+                            let currency = currencies.split(',').next().unwrap_or_default();
+                            journal
+                                .currencies
+                                .insert(account.to_string(), currency.to_string());
+                        }
+                    }
+                    "commodity" => {}
+                    "price" => {
+                        let mut words = rest.split_whitespace().skip(1);
+                        let base = words.next();
+                        let price =
+                            parse_amount(&words.collect::<Vec<_>>().join(" "), decimal_comma);
+                        match (base, price) {
+                            (Some(base), Some(price)) => journal.prices.push((
+                                date,
+                                base.to_string(),
+                                price.number,
+                                price.commodity,
+                            )),
+                            _ => journal
+                                .skipped
+                                .push(format!("line {number}: invalid price directive")),
+                        }
+                    }
+                    "balance" => {
+                        let mut words = rest.split_whitespace().skip(1);
+                        let account = words.next();
+                        let amount =
+                            parse_amount(&words.collect::<Vec<_>>().join(" "), decimal_comma);
+                        match (account, amount, date.pred_opt()) {
+                            // Beancount checks balances at the beginning of the day.
+                            (Some(account), Some(amount), Some(date)) => {
+                                journal
+                                    .balances
+                                    .push((number, date, account.to_string(), amount))
+                            }
+                            _ => journal
+                                .skipped
+                                .push(format!("line {number}: invalid balance directive")),
+                        }
+                    }
+                    "*" | "!" | "txn" if rest[directive.len()..].trim_start().starts_with('"') => {
+                        let mut current = Entry {
+                            line: number,
+                            date,
+                            status: Status::Uncleared,
+                            payee: None,
+                            description: String::new(),
+                            tags: vec![],
+                            fitid: None,
+                            postings: vec![],
+                        };
+                        parse_beancount_header(&mut current, &rest[directive.len()..]);
+                        entry = Some(current);
+                    }
+                    "close" | "pad" | "note" | "document" | "event" | "query" | "custom" => {
+                        journal.skipped.push(format!(
+                            "line {number}: {directive} directives are not supported"
+                        ));
+                    }
+                    _ => {
+                        let mut current = Entry {
+                            line: number,
+                            date,
+                            status: Status::Uncleared,
+                            payee: None,
+                            description: String::new(),
+                            tags: vec![],
+                            fitid: None,
+                            postings: vec![],
+                        };
+                        parse_ledger_header(&mut current, &text[first.len()..], comment);
+                        entry = Some(current);
+                    }
+                }
+            }
+        }
+    }
+
+    journal.entries.extend(entry);
+    journal
+}
+
+/// What a transaction of the journal becomes in the store.
+enum Action {
+    Transaction {
+        account: String,
+        currency: String,
+        options: AddTransactionOptions,
+    },
+    Transfer {
+        from: String,
+        to: String,
+        currencies: (String, String),
+        options: AddTransferOptions,
+    },
+}
+
+fn to_tags(labels: impl IntoIterator<Item = String>) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = vec![];
+
+    for label in labels {
+        let tag = Tag { label, color: None };
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+fn to_datetime(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+/// Convert a transaction of the journal, with the currencies of asset accounts
+/// known so far.
+fn convert(mut entry: Entry, currencies: &BTreeMap<String, String>) -> Result<Action, String> {
+    // Fill the amount of the posting written without one.
+    let mut elided = entry
+        .postings
+        .iter()
+        .enumerate()
+        .filter(|(_, posting)| posting.amount.is_none())
+        .map(|(index, _)| index);
+    if let Some(index) = elided.next() {
+        if elided.next().is_some() {
+            return Err("several postings without amount".to_string());
+        }
+
+        let mut sums: BTreeMap<&str, Decimal> = BTreeMap::new();
+        for weight in entry.postings.iter().filter_map(Posting::weight) {
+            *sums.entry(&weight.commodity).or_default() += weight.number;
+        }
+        sums.retain(|_, sum| !sum.is_zero());
+
+        let [(commodity, sum)] = sums.into_iter().collect::<Vec<_>>()[..] else {
+            return Err("the amount of a posting cannot be inferred".to_string());
+        };
+        entry.postings[index].amount = Some(Amount {
+            number: -sum,
+            commodity: commodity.to_string(),
+        });
+    }
+
+    let mut assets = vec![];
+    let mut others = vec![];
+    for posting in entry.postings {
+        match kind(&posting.account) {
+            Kind::Asset => assets.push(posting),
+            Kind::Category | Kind::Equity => others.push(posting),
+            Kind::Unknown => {
+                return Err(format!(
+                    "{} is not an asset, liability, income, expense or equity account",
+                    posting.account
+                ))
+            }
+        }
+    }
+
+    // Currencies of the asset accounts, from their `open` directive or from the
+    // first commodity posted to them.
+    let mut asset_currencies = vec![];
+    for posting in &assets {
+        let amount = posting.amount.as_ref().expect("amounts are filled");
+        let currency = currencies
+            .get(&posting.account)
+            .cloned()
+            .unwrap_or_else(|| amount.commodity.clone());
+        if !amount.commodity.is_empty() && currency != amount.commodity {
+            return Err(format!(
+                "{} is in {currency}, not in {}",
+                posting.account, amount.commodity
+            ));
+        }
+        asset_currencies.push(currency);
+    }
+
+    let date = Some(to_datetime(entry.date));
+
+    match (&assets[..], &others[..]) {
+        ([], _) => Err("no asset or liability account".to_string()),
+        ([asset], others) => {
+            let amount = asset.amount.clone().expect("amounts are filled");
+            let currency = asset_currencies.swap_remove(0);
+
+            let mut splits = vec![];
+            for posting in others {
+                let weight = posting.weight().expect("amounts are filled");
+                if !weight.commodity.is_empty()
+                    && !amount.commodity.is_empty()
+                    && weight.commodity != amount.commodity
+                {
+                    return Err(format!(
+                        "{} is posted in {}, not in {}",
+                        posting.account, weight.commodity, amount.commodity
+                    ));
+                }
+
+                splits.push(Split {
+                    amount: -weight.number,
+                    tags: to_tags(
+                        category(&posting.account)
+                            .into_iter()
+                            .chain(posting.tags.iter().chain(&asset.tags).cloned()),
+                    ),
+                    memo: String::new(),
+                });
+            }
+
+            let total: Decimal = splits.iter().map(|split| split.amount).sum();
+            if !others.is_empty() && total != amount.number {
+                return Err(format!(
+                    "the postings sum to {total} instead of {}",
+                    amount.number
+                ));
+            }
+
+            // Transactions posted to a single category are tagged with it.
+            let (tags, splits) = match &splits[..] {
+                [split] => (split.tags.clone(), vec![]),
+                _ => (vec![], splits),
+            };
+
+            Ok(Action::Transaction {
+                account: asset.account.clone(),
+                currency,
+                options: AddTransactionOptions {
+                    amount: amount.number,
+                    description: entry.description,
+                    tags: to_tags(
+                        entry
+                            .tags
+                            .into_iter()
+                            .chain(tags.into_iter().map(|tag| tag.label)),
+                    ),
+                    splits,
+                    date,
+                    fitid: entry.fitid,
+                    payee: entry.payee,
+                    status: entry.status,
+                    ..Default::default()
+                },
+            })
+        }
+        ([first, second], []) => {
+            let [first_currency, second_currency] = &asset_currencies[..] else {
+                unreachable!("there is one currency per asset posting");
+            };
+            let ((from, from_currency), (to, to_currency)) = if first
+                .amount
+                .as_ref()
+                .is_some_and(|amount| amount.number.is_sign_negative())
+            {
+                ((first, first_currency), (second, second_currency))
+            } else {
+                ((second, second_currency), (first, first_currency))
+            };
+            let withdrawn = from.amount.as_ref().expect("amounts are filled").number;
+            let deposited = to.amount.as_ref().expect("amounts are filled").number;
+
+            if !withdrawn.is_sign_negative() || deposited.is_sign_negative() {
+                return Err("postings to two asset accounts that are not a transfer".to_string());
+            }
+
+            let currencies = (from_currency.clone(), to_currency.clone());
+            let rate = if currencies.0 == currencies.1 {
+                if withdrawn.abs() != deposited {
+                    return Err("the amounts of the transfer differ".to_string());
+                }
+                None
+            } else {
+                Some(deposited / withdrawn.abs())
+            };
+
+            Ok(Action::Transfer {
+                from: from.account.clone(),
+                to: to.account.clone(),
+                currencies,
+                options: AddTransferOptions {
+                    // Accounts are resolved once created.
+                    from: RecordId::from(("account", "")),
+                    to: RecordId::from(("account", "")),
+                    amount: withdrawn.abs(),
+                    rate,
+                    description: entry.description,
+                    tags: to_tags(entry.tags),
+                    date,
+                    fitid: entry.fitid,
+                },
+            })
+        }
+        _ => Err("postings to several asset or liability accounts with other accounts".to_string()),
+    }
+}
+
+/// Summary of what a journal import added to the database.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct JournalImport {
+    /// One report per asset or liability account of the journal.
+    pub accounts: Vec<ImportReport>,
+    pub transfers_imported: usize,
+    pub rates_imported: usize,
+    /// Balance assertions that were not already recorded.
+    pub checkpoints: Vec<Checkpoint>,
+    /// Labels of the tags that did not exist before the import.
+    pub tags_created: Vec<String>,
+    /// Directives and transactions that could not be mapped, with the reason why.
+    pub skipped: Vec<String>,
+}
+
+/// An account of the store an asset account of the journal is imported in.
+struct Target {
+    id: RecordId,
+    report: ImportReport,
+    fitids: Vec<String>,
+}
+
+/// Get the account an asset account of the journal is imported in, creating it if needed.
+async fn target<'a>(
+    db: &Surreal<Db>,
+    targets: &'a mut HashMap<String, Target>,
+    account: &str,
+    currency: &str,
+) -> Result<&'a mut Target, Error> {
+    if !targets.contains_key(account) {
+        let name = account_name(account);
+        let (id, created) = super::find_or_create_account(db, name, currency).await?;
+
+        targets.insert(
+            account.to_string(),
+            Target {
+                report: ImportReport::new(name.to_string(), id.clone(), created),
+                fitids: super::fitids(db, id.clone()).await?,
+                id,
+            },
+        );
+    }
+
+    Ok(targets
+        .get_mut(account)
+        .expect("the target was just inserted"))
+}
+
+/// Reference of an entry without `fitid`, from the file, line, date and amount of
+/// the entry, so that importing the same file again does not duplicate it.
+fn reference(source: &str, line: usize, date: chrono::NaiveDate, amount: Decimal) -> String {
+    format!("{source}:{line}:{date}:{amount}")
+}
+
+/// Import a beancount or hledger journal, creating the accounts it holds. Transactions
+/// and transfers whose `fitid` is already used in their account are skipped, entries
+/// without `fitid` are given a reference to be recognized when imported again.
+pub async fn import_file(db: &Surreal<Db>, path: &std::path::Path) -> Result<JournalImport, Error> {
+    let content = std::fs::read(path)?;
+    let journal = read(&String::from_utf8_lossy(&content));
+    let source = super::source_name(path);
+
+    let mut skipped = journal.skipped;
+    let mut currencies = journal.currencies;
+    let mut actions = vec![];
+
+    for entry in journal.entries {
+        let (line, date) = (entry.line, entry.date);
+        match convert(entry, &currencies) {
+            Ok(mut action) => {
+                let (fitid, amount) = match &mut action {
+                    Action::Transaction { options, .. } => (&mut options.fitid, options.amount),
+                    Action::Transfer { options, .. } => (&mut options.fitid, options.amount),
+                };
+                fitid.get_or_insert_with(|| reference(&source, line, date, amount));
+
+                match &action {
+                    Action::Transaction {
+                        account, currency, ..
+                    } => {
+                        currencies
+                            .entry(account.clone())
+                            .or_insert_with(|| currency.clone());
+                    }
+                    Action::Transfer {
+                        from,
+                        to,
+                        currencies: (from_currency, to_currency),
+                        ..
+                    } => {
+                        currencies
+                            .entry(from.clone())
+                            .or_insert_with(|| from_currency.clone());
+                        currencies
+                            .entry(to.clone())
+                            .or_insert_with(|| to_currency.clone());
+                    }
+                }
+                actions.push(action);
+            }
+            Err(error) => skipped.push(format!("line {line}: {error}, transaction skipped")),
+        }
+    }
+
+    let tags_created = super::create_missing_tags(
+        db,
+        actions.iter().flat_map(|action| {
+            let (tags, splits) = match action {
+                Action::Transaction { options, .. } => (&options.tags, &options.splits[..]),
+                Action::Transfer { options, .. } => (&options.tags, &[][..]),
+            };
+            tags.iter()
+                .chain(splits.iter().flat_map(|split| split.tags.iter()))
+                .map(|tag| tag.label.clone())
+                .collect::<Vec<_>>()
+        }),
+    )
+    .await?;
+
+    let mut targets: HashMap<String, Target> = HashMap::new();
+
+    // Accounts opened without transactions are created too.
+    for (account, currency) in &currencies {
+        if matches!(kind(account), Kind::Asset) {
+            target(db, &mut targets, account, currency).await?;
+        }
+    }
+
+    let rules = crate::rule::enabled_rules(db).await?;
+    let mut transfers_imported = 0;
+    for action in actions {
+        match action {
+            Action::Transaction {
+                account,
+                currency,
+                options,
+            } => {
+                let target = target(db, &mut targets, &account, &currency).await?;
+
+                if let Some(fitid) = &options.fitid {
+                    if target.fitids.contains(fitid) {
+                        target
+                            .report
+                            .skipped
+                            .push(format!("transaction {fitid}: already imported"));
+                        continue;
+                    }
+                    target.fitids.push(fitid.clone());
+                }
+
+                crate::add_transaction_with_rules(db, &rules, target.id.clone(), options).await?;
+                target.report.transactions_imported += 1;
+            }
+            Action::Transfer {
+                from,
+                to,
+                currencies,
+                mut options,
+            } => {
+                let fitid = options.fitid.clone().unwrap_or_default();
+                let from = target(db, &mut targets, &from, &currencies.0).await?;
+                if from.fitids.contains(&fitid) {
+                    skipped.push(format!("transfer {fitid}: already imported"));
+                    continue;
+                }
+                from.fitids.push(fitid.clone());
+                options.from = from.id.clone();
+
+                let to = target(db, &mut targets, &to, &currencies.1).await?;
+                to.fitids.push(fitid);
+                options.to = to.id.clone();
+
+                crate::add_transfer(db, options).await?;
+                transfers_imported += 1;
+            }
+        }
+    }
+
+    let existing = crate::exchange::list_exchange_rates(db).await?;
+    let mut rates_imported = 0;
+    for (date, base, rate, quote) in journal.prices {
+        let date = to_datetime(date);
+        if existing.iter().any(|existing| {
+            existing.base.eq_ignore_ascii_case(&base)
+                && existing.quote.eq_ignore_ascii_case(&quote)
+                && existing.date == date
+                && existing.rate == rate
+        }) {
+            continue;
+        }
+
+        match crate::exchange::add_exchange_rate(
+            db,
+            AddExchangeRateOptions {
+                base: base.clone(),
+                quote: quote.clone(),
+                date,
+                rate,
+                source: source.clone(),
+            },
+        )
+        .await
+        {
+            Ok(_) => rates_imported += 1,
+            Err(crate::Error::Invalid(reason)) => {
+                skipped.push(format!("price of {base} in {quote}: {reason}"))
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let mut balances: BTreeMap<String, Vec<(Decimal, chrono::DateTime<chrono::Utc>)>> =
+        BTreeMap::new();
+    for (line, date, account, amount) in journal.balances {
+        let currency = match (kind(&account), currencies.get(&account)) {
+            (Kind::Asset, Some(currency)) => currency,
+            _ => {
+                skipped.push(format!(
+                    "line {line}: balance of {account} is not the balance of a known asset account"
+                ));
+                continue;
+            }
+        };
+        if !amount.commodity.is_empty() && amount.commodity != *currency {
+            skipped.push(format!(
+                "line {line}: balance of {account} is in {}, not in {currency}",
+                amount.commodity
+            ));
+            continue;
+        }
+
+        balances
+            .entry(account)
+            .or_default()
+            .push((amount.number, to_datetime(date)));
+    }
+
+    let mut checkpoints = vec![];
+    for (account, balances) in balances {
+        let currency = currencies[&account].clone();
+        let id = target(db, &mut targets, &account, &currency)
+            .await?
+            .id
+            .clone();
+        checkpoints.extend(super::record_checkpoints(db, id, balances, &source).await?);
+    }
+
+    let mut accounts: Vec<ImportReport> =
+        targets.into_values().map(|target| target.report).collect();
+    accounts.sort_by(|a, b| a.account_name.cmp(&b.account_name));
+
+    Ok(JournalImport {
+        accounts,
+        transfers_imported,
+        rates_imported,
+        checkpoints,
+        tags_created,
+        skipped,
+    })
+}
@@ -0,0 +1,129 @@
+//! Import of the JSON account files written by the first versions of the cli,
+//! where each file holds the transactions of a single account.
+
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use surrealdb::{engine::local::Db, Surreal};
+
+use super::{Error, ImportReport};
+use crate::{transaction::Tag, AddTransactionOptions};
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub enum Operation {
+    #[serde(rename = "i")]
+    Income,
+    #[serde(rename = "s")]
+    Spending,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct LegacyTransaction {
+    pub operation: Operation,
+    /// Year and ordinal day of the year.
+    pub date: (i32, u32),
+    /// Always positive, the sign is given by `operation`.
+    pub amount: f64,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct LegacyAccount {
+    pub transactions: Vec<LegacyTransaction>,
+    pub currency: String,
+}
+
+impl LegacyTransaction {
+    fn into_options(self) -> Result<AddTransactionOptions, String> {
+        let (year, ordinal) = self.date;
+        let date = chrono::NaiveDate::from_yo_opt(year, ordinal)
+            .ok_or_else(|| format!("invalid date: day {ordinal} of year {year}"))?;
+
+        let amount = Decimal::from_f64(self.amount.abs())
+            .ok_or_else(|| format!("invalid amount: {}", self.amount))?;
+
+        Ok(AddTransactionOptions {
+            amount: match self.operation {
+                Operation::Income => amount,
+                Operation::Spending => -amount,
+            },
+            description: self.description,
+            tags: self
+                .tags
+                .into_iter()
+                .filter(|label| !label.is_empty())
+                .map(|label| Tag { label, color: None })
+                .collect(),
+            splits: vec![],
+            date: Some(date.and_time(chrono::NaiveTime::MIN).and_utc()),
+            ..Default::default()
+        })
+    }
+}
+
+/// Import a legacy account file. The account is named after the file name.
+pub async fn import_file(db: &Surreal<Db>, path: &std::path::Path) -> Result<ImportReport, Error> {
+    let name = path
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| Error::Invalid(format!("invalid file name: {}", path.display())))?;
+    let account: LegacyAccount =
+        serde_json::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))?;
+
+    import_account(db, name, account).await
+}
+
+/// Import the transactions of a legacy account in the account named `name`,
+/// which is created if it does not exist.
+///
+/// Each transaction is given a reference from its position, date and amount in the
+/// file, so that transactions imported before are skipped.
+pub async fn import_account(
+    db: &Surreal<Db>,
+    name: &str,
+    account: LegacyAccount,
+) -> Result<ImportReport, Error> {
+    let (account_id, account_created) =
+        super::find_or_create_account(db, name, &account.currency).await?;
+    let mut report = ImportReport::new(name.to_string(), account_id.clone(), account_created);
+
+    let mut fitids = super::fitids(db, account_id.clone()).await?;
+    let mut transactions = vec![];
+    for (index, transaction) in account.transactions.into_iter().enumerate() {
+        match transaction.into_options() {
+            Ok(mut options) => {
+                let date = options.date.unwrap_or_default().date_naive();
+                let reference = format!("{name}:{index}:{date}:{}", options.amount);
+                if fitids.contains(&reference) {
+                    report
+                        .skipped
+                        .push(format!("transaction #{index}: already imported"));
+                    continue;
+                }
+
+                fitids.push(reference.clone());
+                options.fitid = Some(reference);
+                transactions.push(options);
+            }
+            Err(error) => report
+                .skipped
+                .push(format!("transaction #{index}: {error}")),
+        }
+    }
+
+    report.tags_created = super::create_missing_tags(
+        db,
+        transactions
+            .iter()
+            .flat_map(|transaction| transaction.tags.iter().map(|tag| tag.label.clone())),
+    )
+    .await?;
+
+    let rules = crate::rule::enabled_rules(db).await?;
+    for options in transactions {
+        crate::add_transaction_with_rules(db, &rules, account_id.clone(), options).await?;
+        report.transactions_imported += 1;
+    }
+
+    Ok(report)
+}
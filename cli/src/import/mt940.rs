@@ -0,0 +1,285 @@
+//! Import of SWIFT MT940 customer statements.
+//!
+//! Transactions are read from the `:61:` statement lines and the `:86:` information
+//! that follows them, either free text or structured with `?NN` subfields (German
+//! banks) or `/CODE/` fields (Dutch banks).
+
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, Statement, StatementImport};
+use crate::AddTransactionOptions;
+
+/// Split a message into its `:TAG:` fields, joining continuation lines.
+fn fields(content: &str) -> Vec<(&str, String)> {
+    let mut fields: Vec<(&str, String)> = vec![];
+
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+
+        if let Some((tag, value)) = line
+            .strip_prefix(':')
+            .and_then(|line| line.split_once(':'))
+            .filter(|(tag, _)| {
+                (2..=3).contains(&tag.len()) && tag.starts_with(|c: char| c.is_ascii_digit())
+            })
+        {
+            fields.push((tag, value.to_string()));
+        } else if line.starts_with('-') || line.starts_with('{') {
+            // End of a message, or the headers of a SWIFT envelope.
+            continue;
+        } else if let Some((_, value)) = fields.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+
+    fields
+}
+
+fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(value.get(..6)?, "%y%m%d").ok()
+}
+
+fn to_datetime(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+/// Parse an amount written with a comma as decimal separator.
+fn parse_amount(value: &str) -> Option<Decimal> {
+    value.replace(',', ".").trim_end_matches('.').parse().ok()
+}
+
+/// Parse a balance field (`:60F:`, `:62F:`...): `C` or `D`, date, currency and amount.
+fn parse_balance(value: &str) -> Option<(Decimal, chrono::NaiveDate, &str)> {
+    let sign = value.get(..1)?;
+    let date = parse_date(value.get(1..7)?)?;
+    let currency = value.get(7..10)?;
+    let amount = parse_amount(value.get(10..)?.trim())?;
+
+    Some((if sign == "D" { -amount } else { amount }, date, currency))
+}
+
+/// A statement line, the `:61:` field.
+struct StatementLine {
+    value_date: chrono::NaiveDate,
+    booking_date: chrono::NaiveDate,
+    amount: Decimal,
+    reference: Option<String>,
+    supplementary: Option<String>,
+}
+
+/// Parse a statement line: `YYMMDD[MMDD](C|D|RC|RD)[funds code]amount` followed by
+/// the transaction type, the customer reference and the bank reference after `//`.
+fn parse_statement_line(value: &str) -> Result<StatementLine, String> {
+    let (line, supplementary) = match value.split_once('\n') {
+        Some((line, supplementary)) => (line, Some(supplementary.trim().to_string())),
+        None => (value, None),
+    };
+
+    let value_date = parse_date(line)
+        .ok_or_else(|| format!("invalid value date: {}", line.get(..6).unwrap_or(line)))?;
+    let mut rest = &line[6..];
+
+    // The booking date has no year, it is the one closest to the value date.
+    let booking_date = match rest
+        .get(..4)
+        .filter(|date| date.chars().all(|c| c.is_ascii_digit()))
+    {
+        Some(date) => {
+            rest = &rest[4..];
+            [0, -1, 1]
+                .into_iter()
+                .filter_map(|offset| {
+                    chrono::NaiveDate::parse_from_str(
+                        &format!("{}{date}", chrono::Datelike::year(&value_date) + offset),
+                        "%Y%m%d",
+                    )
+                    .ok()
+                })
+                .min_by_key(|date| (*date - value_date).num_days().abs())
+                .unwrap_or(value_date)
+        }
+        None => value_date,
+    };
+
+    let (debit, mark) = if rest.starts_with("RC") {
+        (true, 2)
+    } else if rest.starts_with("RD") {
+        (false, 2)
+    } else if rest.starts_with('C') {
+        (false, 1)
+    } else if rest.starts_with('D') {
+        (true, 1)
+    } else {
+        return Err(format!("invalid debit or credit mark: {rest}"));
+    };
+    rest = &rest[mark..];
+
+    // Optional third letter of the currency code.
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let amount = parse_amount(&rest[..end]).ok_or_else(|| format!("invalid amount: {rest}"))?;
+    rest = &rest[end..];
+
+    // Transaction type (a letter and three characters), then the reference of the
+    // customer, which is not unique, and the reference of the bank after `//`.
+    let reference = rest
+        .get(4..)
+        .and_then(|references| references.split_once("//"))
+        .map(|(_, bank)| bank.trim())
+        .filter(|bank| !bank.is_empty())
+        .map(str::to_string);
+
+    Ok(StatementLine {
+        value_date,
+        booking_date,
+        amount: if debit { -amount } else { amount },
+        reference,
+        supplementary,
+    })
+}
+
+/// Information of a transaction, the `:86:` field.
+#[derive(Default)]
+struct Information {
+    remittance: String,
+    payee: Option<String>,
+    payee_iban: Option<String>,
+}
+
+fn parse_information(value: &str) -> Information {
+    let value = value.replace('\n', "");
+
+    // German banks separate subfields with `?` followed by two digits.
+    if value.get(3..4) == Some("?") {
+        let mut information = Information::default();
+        let mut remittance = vec![];
+        let mut names = vec![];
+
+        for subfield in value[3..].split('?').skip(1) {
+            let text = subfield.get(2..).unwrap_or_default();
+            match subfield.get(..2).unwrap_or_default() {
+                "20" | "21" | "22" | "23" | "24" | "25" | "26" | "27" | "28" | "29" | "60"
+                | "61" | "62" | "63" => remittance.push(text.trim()),
+                "31" => information.payee_iban = Some(text.trim().to_string()),
+                "32" | "33" => names.push(text.trim()),
+                _ => {}
+            }
+        }
+
+        information.remittance = remittance.concat();
+        information.payee = Some(names.concat()).filter(|name| !name.is_empty());
+
+        return information;
+    }
+
+    // Dutch banks use `/CODE/value` pairs.
+    if let Some(pairs) = value.strip_prefix('/') {
+        let mut information = Information::default();
+        let mut parts = pairs.split('/');
+
+        while let Some(code) = parts.next() {
+            let text = parts.next().unwrap_or_default().trim().to_string();
+            match code {
+                "REMI" => information.remittance = text,
+                "NAME" => information.payee = Some(text),
+                "IBAN" => information.payee_iban = Some(text),
+                _ => {}
+            }
+        }
+
+        return information;
+    }
+
+    Information {
+        remittance: value.trim().to_string(),
+        ..Default::default()
+    }
+}
+
+/// Read the statements of an MT940 file. Files holding the statements of several
+/// accounts are rejected.
+pub fn read(content: &[u8]) -> Result<Statement, String> {
+    // Statements are written in a subset of ASCII, but some banks use latin-1.
+    let content = match std::str::from_utf8(content) {
+        Ok(content) => std::borrow::Cow::Borrowed(content),
+        Err(_) => encoding_rs::WINDOWS_1252.decode(content).0,
+    };
+
+    let mut statement = Statement::default();
+    let mut account: Option<String> = None;
+    let mut lines: Vec<(StatementLine, Information)> = vec![];
+
+    for (tag, value) in fields(&content) {
+        match tag {
+            "25" => {
+                let value = value.trim().to_string();
+                if account.as_ref().is_some_and(|account| *account != value) {
+                    return Err(
+                        "the file holds the statements of several accounts, import them separately"
+                            .to_string(),
+                    );
+                }
+                account = Some(value);
+            }
+            "60F" | "60M" => {
+                if let Some((_, _, currency)) = parse_balance(&value) {
+                    statement.currency = Some(currency.to_string());
+                }
+            }
+            "61" => match parse_statement_line(&value) {
+                Ok(line) => lines.push((line, Information::default())),
+                Err(error) => statement.skipped.push(format!("line {value}: {error}")),
+            },
+            "86" => {
+                if let Some((_, information)) = lines.last_mut() {
+                    *information = parse_information(&value);
+                }
+            }
+            "62F" => {
+                if let Some((balance, date, currency)) = parse_balance(&value) {
+                    statement.currency = Some(currency.to_string());
+                    statement.balances.push((balance, to_datetime(date)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    statement.transactions = lines
+        .into_iter()
+        .map(|(line, information)| AddTransactionOptions {
+            amount: line.amount,
+            description: Some(information.remittance)
+                .filter(|remittance| !remittance.is_empty())
+                .or_else(|| information.payee.clone())
+                .or(line.supplementary)
+                .unwrap_or_default(),
+            date: Some(to_datetime(line.booking_date)),
+            value_date: Some(to_datetime(line.value_date))
+                .filter(|_| line.value_date != line.booking_date),
+            fitid: line.reference,
+            payee: information.payee,
+            payee_iban: information.payee_iban,
+            ..Default::default()
+        })
+        .collect();
+
+    Ok(statement)
+}
+
+/// Import an MT940 statement in an existing account. Transactions whose bank
+/// reference is already used in the account are skipped.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+) -> Result<StatementImport, Error> {
+    super::import_statement_file(db, account_id, path, read).await
+}
@@ -0,0 +1,277 @@
+//! Import of OFX and QFX statements, in both the SGML (1.x) and XML (2.x) versions.
+//!
+//! Transactions keep the FITID given by the bank, which is used to skip the
+//! transactions that were already imported when a statement is imported again.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, Statement, StatementImport};
+use crate::AddTransactionOptions;
+
+/// An element of an OFX document, holding either a value or other elements.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    value: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|child| child.value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Every element named `name` under this one, at any depth.
+    fn descendants<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            } else {
+                child.descendants(name, found);
+            }
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Parse the body of an OFX document. SGML documents do not close elements holding
+/// a value, so an element followed by text is a value, and an element followed by
+/// another tag holds other elements if it is closed later, or is an empty value.
+fn parse(content: &str) -> Result<Element, String> {
+    let start = content
+        .find("<OFX>")
+        .ok_or("not an OFX file, the <OFX> element is missing")?;
+    let document = &content[start..];
+
+    // Position of the last closing tag of each element.
+    let mut closing_tags: HashMap<&str, usize> = HashMap::new();
+    let mut position = 0;
+    while let Some(open) = document[position..].find("</") {
+        let open = position + open;
+        let Some(close) = document[open..].find('>') else {
+            break;
+        };
+        closing_tags.insert(document[open + 2..open + close].trim(), open);
+        position = open + close;
+    }
+
+    let mut stack = vec![Element::default()];
+    let mut rest = document;
+
+    while let Some(open) = rest.find('<') {
+        let close = rest[open..]
+            .find('>')
+            .map(|close| open + close)
+            .ok_or("unterminated tag")?;
+        let tag = rest[open + 1..close].trim();
+        rest = &rest[close + 1..];
+
+        // Processing instructions and comments of XML documents.
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+
+            // Closing tags of values, only written in XML documents.
+            if stack.last().is_some_and(|last| {
+                last.children
+                    .last()
+                    .is_some_and(|child| child.name == name && !child.value.is_empty())
+            }) {
+                continue;
+            }
+
+            // Close every element up to the matching one.
+            if stack.iter().skip(1).any(|element| element.name == name) {
+                while let Some(element) = stack.pop() {
+                    let done = element.name == name;
+                    stack
+                        .last_mut()
+                        .expect("the root is never closed")
+                        .children
+                        .push(element);
+                    if done {
+                        break;
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        let (name, self_closing) = match tag.strip_suffix('/') {
+            Some(name) => (name.trim(), true),
+            None => (tag, false),
+        };
+        let name = name
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let text = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+        let position = document.len() - rest.len();
+        let is_closed_later = closing_tags
+            .get(name.as_str())
+            .is_some_and(|closing| *closing >= position);
+
+        if self_closing || !text.is_empty() || !is_closed_later {
+            stack
+                .last_mut()
+                .expect("the root is never closed")
+                .children
+                .push(Element {
+                    name,
+                    value: unescape(text),
+                    children: vec![],
+                });
+        } else {
+            stack.push(Element {
+                name,
+                ..Default::default()
+            });
+        }
+    }
+
+    // Elements left open by a truncated document.
+    while stack.len() > 1 {
+        let element = stack.pop().expect("the stack is not empty");
+        stack
+            .last_mut()
+            .expect("the root is never closed")
+            .children
+            .push(element);
+    }
+
+    stack.pop().ok_or_else(|| "empty document".to_string())
+}
+
+/// Parse an OFX date (`YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`), keeping the day only.
+fn parse_date(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d")
+        .ok()
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+fn parse_amount(value: &str) -> Option<Decimal> {
+    value.trim().replace(',', ".").parse().ok()
+}
+
+/// Read the statement of an OFX document. Documents holding the statements of
+/// several accounts are rejected.
+pub fn read(content: &[u8]) -> Result<Statement, String> {
+    // SGML documents are often encoded in windows-1252, as told by their header.
+    let content = match std::str::from_utf8(content) {
+        Ok(content) => std::borrow::Cow::Borrowed(content),
+        Err(_) => encoding_rs::WINDOWS_1252.decode(content).0,
+    };
+    let document = parse(&content)?;
+
+    let mut statements = vec![];
+    document.descendants("STMTRS", &mut statements);
+    document.descendants("CCSTMTRS", &mut statements);
+
+    let statement = match statements[..] {
+        [statement] => statement,
+        [] => return Err("the file does not hold any statement".to_string()),
+        _ => {
+            return Err(format!(
+                "the file holds the statements of {} accounts, import them separately",
+                statements.len()
+            ))
+        }
+    };
+
+    let mut entries = vec![];
+    statement.descendants("STMTTRN", &mut entries);
+
+    let mut transactions = vec![];
+    let mut skipped = vec![];
+    for (index, entry) in entries.into_iter().enumerate() {
+        match read_transaction(entry) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped.push(format!(
+                "transaction {}: {error}",
+                entry
+                    .value("FITID")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("#{index}"))
+            )),
+        }
+    }
+
+    let ledger_balance = statement.child("LEDGERBAL").and_then(|balance| {
+        Some((
+            parse_amount(balance.value("BALAMT")?)?,
+            parse_date(balance.value("DTASOF")?)?,
+        ))
+    });
+
+    Ok(Statement {
+        currency: statement.value("CURDEF").map(str::to_string),
+        transactions,
+        balances: ledger_balance.into_iter().collect(),
+        skipped,
+    })
+}
+
+fn read_transaction(entry: &Element) -> Result<AddTransactionOptions, String> {
+    let amount = entry.value("TRNAMT").ok_or("missing amount")?;
+    let amount = parse_amount(amount).ok_or_else(|| format!("invalid amount: {amount}"))?;
+    let date = entry
+        .value("DTPOSTED")
+        .or_else(|| entry.value("DTUSER"))
+        .ok_or("missing date")?;
+    let date = parse_date(date).ok_or_else(|| format!("invalid date: {date}"))?;
+
+    let name = entry
+        .value("NAME")
+        .or_else(|| entry.child("PAYEE").and_then(|payee| payee.value("NAME")));
+    let memo = entry.value("MEMO");
+    let description = match (name, memo) {
+        (Some(name), Some(memo)) if !name.contains(memo) => format!("{name} {memo}"),
+        (Some(name), _) => name.to_string(),
+        (None, Some(memo)) => memo.to_string(),
+        (None, None) => entry.value("TRNTYPE").unwrap_or_default().to_string(),
+    };
+
+    Ok(AddTransactionOptions {
+        amount,
+        description,
+        tags: vec![],
+        splits: vec![],
+        date: Some(date),
+        fitid: entry.value("FITID").map(str::to_string),
+        payee: name.map(str::to_string),
+        ..Default::default()
+    })
+}
+
+/// Import an OFX or QFX statement in an existing account. Transactions whose FITID
+/// is already used in the account are skipped.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+) -> Result<StatementImport, Error> {
+    super::import_statement_file(db, account_id, path, read).await
+}
@@ -0,0 +1,288 @@
+//! Import of QIF files, as written by Quicken, Homebank or GnuCash.
+//!
+//! Only the `Bank`, `CCard` and `Cash` sections are read. Categories and classes
+//! become tags, and split lines become the lines of split transactions.
+
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use super::{Error, Statement, StatementImport};
+use crate::{
+    transaction::{Split, Status, Tag},
+    AddTransactionOptions,
+};
+
+/// Transaction types that hold transactions of a cash account.
+const TYPES: &[&str] = &["Bank", "CCard", "Cash", "Oth A", "Oth L"];
+
+/// Order of the day and the month in the dates of a file.
+#[derive(Clone, Copy)]
+enum DateOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+/// Split a date in its three numbers, whatever the separators. Quicken writes
+/// years after 2000 with a `'` separator and two digits.
+fn date_parts(value: &str) -> Option<[u32; 3]> {
+    let parts: Vec<u32> = value
+        .trim()
+        .split(['/', '\'', '-', '.'])
+        .map(|part| part.trim().parse().ok())
+        .collect::<Option<_>>()?;
+
+    parts.try_into().ok()
+}
+
+/// Guess the order of days and months from all the dates of the file. QIF files
+/// usually put the month first, but some tools write dates in the order of the locale.
+fn date_order(content: &str) -> DateOrder {
+    for line in content.lines() {
+        let Some([first, second, _]) = line.strip_prefix('D').and_then(date_parts) else {
+            continue;
+        };
+
+        if first > 12 && first < 32 {
+            return DateOrder::DayFirst;
+        }
+
+        if second > 12 {
+            return DateOrder::MonthFirst;
+        }
+    }
+
+    DateOrder::MonthFirst
+}
+
+fn parse_date(value: &str, order: DateOrder) -> Option<chrono::DateTime<chrono::Utc>> {
+    let [first, second, third] = date_parts(value)?;
+
+    let (year, month, day) = if first > 31 {
+        (first, second, third)
+    } else {
+        let year = match third {
+            0..=69 => third + 2000,
+            70..=99 => third + 1900,
+            year => year,
+        };
+        match order {
+            DateOrder::MonthFirst => (year, first, second),
+            DateOrder::DayFirst => (year, second, first),
+        }
+    };
+
+    chrono::NaiveDate::from_ymd_opt(year as i32, month, day)
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// Guess whether amounts use a decimal comma, from the first amount of the file
+/// that holds both a comma and a dot, like `1.234,56`. Amounts with a single
+/// comma, like `12,50`, are told apart from thousands when they are parsed.
+fn decimal_comma(content: &str) -> bool {
+    for line in content.lines() {
+        let Some(value) = line.strip_prefix(['T', 'U', '$']) else {
+            continue;
+        };
+
+        if let (Some(comma), Some(dot)) = (value.rfind(','), value.rfind('.')) {
+            return comma > dot;
+        }
+    }
+
+    false
+}
+
+fn parse_amount(value: &str, decimal_comma: bool) -> Option<Decimal> {
+    super::parse_number(value, decimal_comma)
+}
+
+/// Tags of a category field, `Category[:Subcategory][/Class]`. Transfers to other
+/// accounts, written between brackets, are not categories.
+fn parse_category(value: &str) -> Vec<Tag> {
+    value
+        .split('/')
+        .map(str::trim)
+        .filter(|label| !label.is_empty() && !label.starts_with('['))
+        .map(|label| Tag {
+            label: label.to_string(),
+            color: None,
+        })
+        .collect()
+}
+
+/// A transaction being read, field by field.
+#[derive(Default)]
+struct Record {
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    status: Status,
+    tags: Vec<Tag>,
+    splits: Vec<Split>,
+    /// First invalid field of the record.
+    error: Option<String>,
+}
+
+impl Record {
+    fn into_options(
+        self,
+        order: DateOrder,
+        decimal_comma: bool,
+    ) -> Result<AddTransactionOptions, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let date = self.date.ok_or("missing date")?;
+        let date = parse_date(&date, order).ok_or_else(|| format!("invalid date: {date}"))?;
+        let amount = self.amount.ok_or("missing amount")?;
+        let amount = parse_amount(&amount, decimal_comma)
+            .ok_or_else(|| format!("invalid amount: {amount}"))?;
+
+        if !self.splits.is_empty() {
+            let total: Decimal = self.splits.iter().map(|split| split.amount).sum();
+            if total != amount {
+                return Err(format!("the lines sum to {total} instead of {amount}"));
+            }
+        }
+
+        Ok(AddTransactionOptions {
+            amount,
+            description: self
+                .memo
+                .clone()
+                .or_else(|| self.payee.clone())
+                .unwrap_or_default(),
+            tags: self.tags,
+            splits: self.splits,
+            date: Some(date),
+            payee: self.payee,
+            status: self.status,
+            ..Default::default()
+        })
+    }
+}
+
+/// Read the transactions of a QIF file. Files holding the transactions of
+/// several accounts are rejected.
+pub fn read(content: &[u8]) -> Result<Statement, String> {
+    let content = match std::str::from_utf8(content) {
+        Ok(content) => std::borrow::Cow::Borrowed(content),
+        Err(_) => encoding_rs::WINDOWS_1252.decode(content).0,
+    };
+    let order = date_order(&content);
+    let decimal_comma = decimal_comma(&content);
+
+    let mut statement = Statement::default();
+    let mut accounts = 0;
+    let mut in_account = false;
+    let mut section: Option<String> = None;
+    let mut record = Record::default();
+    let mut index = 0;
+
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        let Some(code) = line.chars().next() else {
+            continue;
+        };
+        let value = line[code.len_utf8()..].trim();
+
+        if code == '!' {
+            match value {
+                "Account" => in_account = true,
+                "Option:AutoSwitch" | "Clear:AutoSwitch" => {}
+                _ => {
+                    section = value
+                        .strip_prefix("Type:")
+                        .or_else(|| value.strip_prefix("Type "))
+                        .map(str::to_string);
+                }
+            }
+            continue;
+        }
+
+        if in_account {
+            // Fields of an account, until the end of the record.
+            if code == '^' {
+                in_account = false;
+                accounts += 1;
+            }
+            continue;
+        }
+
+        let Some(section) = section.as_deref() else {
+            continue;
+        };
+
+        if !TYPES.contains(&section) {
+            if code == '^' {
+                statement
+                    .skipped
+                    .push(format!("record of type {section}: not a cash account"));
+            }
+            continue;
+        }
+
+        match code {
+            'D' => record.date = Some(value.to_string()),
+            'T' | 'U' => record.amount = Some(value.to_string()),
+            'P' => record.payee = Some(value.to_string()).filter(|payee| !payee.is_empty()),
+            'M' => record.memo = Some(value.to_string()).filter(|memo| !memo.is_empty()),
+            'C' => {
+                record.status = match value {
+                    "*" | "c" => Status::Cleared,
+                    "X" | "R" => Status::Reconciled,
+                    _ => Status::Uncleared,
+                }
+            }
+            'L' => record.tags = parse_category(value),
+            'S' => record.splits.push(Split {
+                amount: Decimal::ZERO,
+                tags: parse_category(value),
+                memo: String::new(),
+            }),
+            'E' => {
+                if let Some(split) = record.splits.last_mut() {
+                    split.memo = value.to_string();
+                }
+            }
+            '$' => match (record.splits.last_mut(), parse_amount(value, decimal_comma)) {
+                (Some(split), Some(amount)) => split.amount = amount,
+                _ => {
+                    record
+                        .error
+                        .get_or_insert(format!("invalid line amount: {value}"));
+                }
+            },
+            '^' => {
+                let name = record.date.clone().unwrap_or_default();
+                match std::mem::take(&mut record).into_options(order, decimal_comma) {
+                    Ok(options) => statement.transactions.push(options),
+                    Err(error) => statement
+                        .skipped
+                        .push(format!("transaction #{index} ({name}): {error}")),
+                }
+                index += 1;
+            }
+            _ => {}
+        }
+    }
+
+    if accounts > 1 {
+        return Err(format!(
+            "the file holds the transactions of {accounts} accounts, import them separately"
+        ));
+    }
+
+    Ok(statement)
+}
+
+/// Import a QIF file in an existing account.
+pub async fn import_file(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    path: &std::path::Path,
+) -> Result<StatementImport, Error> {
+    super::import_statement_file(db, account_id, path, read).await
+}
@@ -1,8 +1,23 @@
 use account::Account;
-use surrealdb::{engine::local::Db, RecordId, Surreal};
-use transaction::{Tag, TransactionWithId};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, method::Query, RecordId, Surreal};
+use transaction::{Split, Status, Tag, TransactionWithId, Transfer, TrashedTransaction};
 
 pub mod account;
+pub mod archive;
+pub mod budget;
+pub mod checkpoint;
+pub mod duplicate;
+pub mod exchange;
+pub mod export;
+pub mod filter;
+pub mod import;
+pub mod migration;
+pub mod money;
+pub mod page;
+pub mod report;
+pub mod rule;
+pub mod schedule;
 pub mod script;
 pub mod settings;
 pub mod transaction;
@@ -20,6 +35,8 @@ pub const TIME_FORMAT_DAY: &[time::format_description::FormatItem<'_>] =
 pub enum Error {
     Database(surrealdb::Error),
     RecordNotFound,
+    /// The request cannot be applied, with the reason why.
+    Invalid(String),
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -36,11 +53,14 @@ impl std::fmt::Display for Error {
             match self {
                 Error::Database(error) => error.to_string(),
                 Error::RecordNotFound => "Record not found".to_string(),
+                Error::Invalid(reason) => reason.clone(),
             }
         )
     }
 }
 
+impl std::error::Error for Error {}
+
 impl From<surrealdb::Error> for Error {
     fn from(value: surrealdb::Error) -> Self {
         Self::Database(value)
@@ -51,25 +71,46 @@ impl From<surrealdb::Error> for Error {
 #[ts(export)]
 #[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BalanceOptions {
+    /// Included.
     #[ts(as = "Option<String>", optional)]
     pub period_start: Option<surrealdb::Datetime>,
+    /// Excluded.
     #[ts(as = "Option<String>", optional)]
     pub period_end: Option<surrealdb::Datetime>,
+    /// Only count amounts tagged with this label. For split transactions, only the
+    /// lines with the tag are counted.
     #[ts(optional)]
     pub tag: Option<String>,
+    /// Ignore transfers between accounts.
+    #[ts(optional)]
+    pub exclude_transfers: Option<bool>,
+    /// Only count transactions matching this filter, see [`filter`] for the syntax.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
 }
 
+/// Part of the amount of a transaction tagged with `$tag_label`: the sum of the tagged
+/// lines for split transactions, the whole amount otherwise.
+const TAGGED_AMOUNT: &str =
+    "IF splits THEN math::sum(splits[WHERE tags.label CONTAINS $tag_label].amount) ELSE amount END";
+
 pub async fn balance(
     db: &Surreal<Db>,
     account_id: RecordId,
     options: BalanceOptions,
-) -> Result<f64, Error> {
-    let mut query =
-        r#"RETURN (SELECT math::sum(amount) AS sum FROM transaction WHERE account = $account_id"#
-            .to_string();
+) -> Result<Decimal, Error> {
+    let amount = if options.tag.is_some() {
+        TAGGED_AMOUNT
+    } else {
+        "amount"
+    };
+    let mut query = format!(
+        "RETURN (SELECT math::sum({amount}) AS sum FROM transaction WHERE account = $account_id"
+    );
 
     if options.period_start.is_some() {
-        query.push_str(" AND date > $start");
+        query.push_str(" AND date >= $start");
     }
 
     if options.period_end.is_some() {
@@ -77,13 +118,21 @@ pub async fn balance(
     }
 
     if options.tag.is_some() {
-        query.push_str(" AND tags.find(|$tag| $tag.label = '$tag_label')");
+        query.push_str(
+            " AND (tags.label CONTAINS $tag_label OR (splits ?? []).tags.label.flatten() CONTAINS $tag_label)",
+        );
+    }
+
+    if options.exclude_transfers.unwrap_or_default() {
+        query.push_str(" AND transfer = NONE");
     }
 
+    let filter = filter::parse(options.filter.as_deref())?;
+    let params = filter::Filter::push_condition(filter.as_ref(), &mut query);
+
     query.push_str(" GROUP ALL).sum");
 
-    let sum: Option<f64> = db
-        .query(query)
+    let sum: Option<Decimal> = filter::bind(db.query(query), params)
         .bind(("account_id", account_id))
         .bind(("start", options.period_start.unwrap_or_default()))
         .bind(("end", options.period_end.unwrap_or_default()))
@@ -157,7 +206,11 @@ pub async fn delete_account(
     db.query(
         r#"
     DELETE account WHERE id = $account_id;
-    DELETE transaction WHERE account = $account_id;"#,
+    DELETE transaction WHERE account = $account_id;
+    DELETE schedule WHERE account = $account_id;
+    DELETE checkpoint WHERE account = $account_id;
+    UPDATE budget SET accounts -= $account_id WHERE accounts CONTAINS $account_id;
+    DELETE trash WHERE transaction.account = $account_id;"#,
     )
     .bind(("account_id", account_id))
     .await
@@ -188,48 +241,448 @@ pub async fn get_currency(db: &Surreal<Db>, account_id: RecordId) -> Result<Stri
 
 #[derive(ts_rs::TS)]
 #[ts(export)]
-#[derive(Default, Debug, serde::Deserialize)]
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AddTransactionOptions {
-    pub amount: f64,
+    /// Rounded to the minor units of the account currency.
+    #[ts(type = "string")]
+    pub amount: Decimal,
     pub description: String,
     pub tags: Vec<Tag>,
+    /// Lines to split the amount into, they must sum to `amount`.
+    #[ts(as = "Option<Vec<Split>>", optional)]
+    #[serde(default)]
+    pub splits: Vec<Split>,
     #[ts(as = "Option<String>", optional)]
     pub date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Identifier given to the transaction by the bank, like the FITID of OFX statements.
+    #[ts(optional)]
+    #[serde(default)]
+    pub fitid: Option<String>,
+    /// Name of the other party of the transaction.
+    #[ts(optional)]
+    #[serde(default)]
+    pub payee: Option<String>,
+    #[ts(optional)]
+    #[serde(default)]
+    pub payee_iban: Option<String>,
+    /// Date the amount is taken into account by the bank for interests, when it
+    /// differs from the booking date.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub value_date: Option<chrono::DateTime<chrono::Utc>>,
+    #[ts(as = "Option<Status>", optional)]
+    #[serde(default)]
+    pub status: Status,
+}
+
+/// Round the lines of a split transaction to the minor units of the account currency
+/// and check that they sum to the amount of the transaction.
+fn round_splits(amount: Decimal, splits: &mut [Split], currency: &str) -> Result<(), String> {
+    if splits.is_empty() {
+        return Ok(());
+    }
+
+    for split in splits.iter_mut() {
+        split.amount = money::round(split.amount, currency);
+    }
+
+    let total: Decimal = splits.iter().map(|split| split.amount).sum();
+    if total != amount {
+        return Err(format!(
+            "the lines of the transaction sum to {total} instead of {amount}"
+        ));
+    }
+
+    Ok(())
 }
 
 pub async fn add_transaction(
     db: &Surreal<Db>,
     account_id: RecordId,
     options: AddTransactionOptions,
-) -> Result<(), surrealdb::Error> {
-    let query = r#"
+) -> Result<(), Error> {
+    let rules = rule::enabled_rules(db).await?;
+
+    add_transaction_with_rules(db, &rules, account_id, options).await
+}
+
+/// Add a transaction with rules loaded by the caller, once for all the transactions
+/// of an import.
+pub(crate) async fn add_transaction_with_rules(
+    db: &Surreal<Db>,
+    rules: &[rule::CompiledRule],
+    account_id: RecordId,
+    mut options: AddTransactionOptions,
+) -> Result<(), Error> {
+    prepare_transaction(db, rules, &account_id, &mut options).await?;
+    bind_transaction(db.query(CREATE_TRANSACTION), account_id, options).await?;
+
+    Ok(())
+}
+
+/// Statement creating a transaction, with the parameters bound by [`bind_transaction`].
+pub(crate) const CREATE_TRANSACTION: &str = r#"
     CREATE transaction SET
         date = $date,
         amount = $amount,
         description = $description,
         tags = $tags,
-        account = $account_id"#;
+        splits = $splits,
+        fitid = $fitid,
+        payee = $payee,
+        payee_iban = $payee_iban,
+        value_date = $value_date,
+        status = $status,
+        account = $account_id;"#;
+
+/// Round the amounts of a new transaction to the currency of its account, and apply
+/// the rules.
+pub(crate) async fn prepare_transaction(
+    db: &Surreal<Db>,
+    rules: &[rule::CompiledRule],
+    account_id: &RecordId,
+    options: &mut AddTransactionOptions,
+) -> Result<(), Error> {
+    let currency = get_currency(db, account_id.clone()).await?;
+    options.amount = money::round(options.amount, &currency);
+    round_splits(options.amount, &mut options.splits, &currency).map_err(Error::Invalid)?;
+    rule::apply_on_insert(db, rules, account_id, options).await
+}
 
-    db.query(query)
+pub(crate) fn bind_transaction<'a>(
+    query: Query<'a, Db>,
+    account_id: RecordId,
+    options: AddTransactionOptions,
+) -> Query<'a, Db> {
+    query
         .bind(("date", options.date.unwrap_or_else(chrono::Utc::now)))
         .bind(("amount", options.amount))
         .bind(("description", options.description))
         .bind(("tags", serde_json::json!(options.tags)))
+        .bind(("splits", serde_json::json!(options.splits)))
+        .bind(("fitid", options.fitid))
+        .bind(("payee", options.payee))
+        .bind(("payee_iban", options.payee_iban))
+        .bind((
+            "value_date",
+            options.value_date.map(surrealdb::Datetime::from),
+        ))
+        .bind(("status", options.status))
         .bind(("account_id", account_id))
-        .await?;
+}
 
-    Ok(())
+/// Reconciled transactions cannot be deleted, and their amount and date cannot change.
+fn check_unlocked(
+    previous: &TransactionWithId,
+    transaction: Option<&TransactionWithId>,
+) -> Result<(), String> {
+    if previous.inner.status != transaction::Status::Reconciled {
+        return Ok(());
+    }
+
+    if transaction.is_some_and(|transaction| {
+        transaction.inner.amount == previous.inner.amount
+            && transaction.inner.date == previous.inner.date
+            && transaction.account == previous.account
+    }) {
+        return Ok(());
+    }
+
+    Err(format!(
+        "transaction {} is reconciled, set its status back to cleared to change its amount or date, or to delete it",
+        previous.id
+    ))
 }
 
+/// Update a transaction. If the transaction is a leg of a transfer, the other leg
+/// is updated with the same date, description and tags, and an amount converted
+/// with the rate of the transfer, keeping its own status.
+///
+/// The amount and date of reconciled transactions cannot change.
 pub async fn update_transaction(
     db: &Surreal<Db>,
-    transaction: TransactionWithId,
+    mut transaction: TransactionWithId,
+) -> Result<(), Error> {
+    let currency = get_currency(db, transaction.account.clone()).await?;
+    transaction.inner.amount = money::round(transaction.inner.amount, &currency);
+    round_splits(
+        transaction.inner.amount,
+        &mut transaction.inner.splits,
+        &currency,
+    )
+    .map_err(Error::Invalid)?;
+
+    let previous: Option<TransactionWithId> = db.select(transaction.id.clone()).await?;
+    let previous = previous.ok_or(Error::RecordNotFound)?;
+    check_unlocked(&previous, Some(&transaction)).map_err(Error::Invalid)?;
+
+    let Some(transfer_id) = transaction.transfer.clone() else {
+        let _: Option<Record> = db
+            .update(("transaction", transaction.id.key().clone()))
+            .merge(transaction)
+            .await?;
+
+        return Ok(());
+    };
+
+    if !transaction.inner.splits.is_empty() {
+        return Err(Error::Invalid("transfers cannot be split".to_string()));
+    }
+
+    let other: Option<TransactionWithId> = db.select(transfer_id).await?;
+    let mut other = other.ok_or(Error::RecordNotFound)?;
+
+    let other_currency = get_currency(db, other.account.clone()).await?;
+    let amount = if previous.inner.amount.is_zero() {
+        -transaction.inner.amount
+    } else {
+        transaction.inner.amount * other.inner.amount / previous.inner.amount
+    };
+
+    let previous_other = other.clone();
+    other.inner = transaction::Transaction {
+        amount: money::round(amount, &other_currency),
+        fitid: other.inner.fitid.clone(),
+        // Each leg appears on the statement of its own account.
+        status: other.inner.status,
+        ..transaction.inner.clone()
+    };
+    check_unlocked(&previous_other, Some(&other)).map_err(Error::Invalid)?;
+
+    db.query(
+        r#"
+    BEGIN TRANSACTION;
+    UPDATE $transaction.id MERGE $transaction;
+    UPDATE $other.id MERGE $other;
+    COMMIT TRANSACTION;"#,
+    )
+    .bind(("transaction", transaction))
+    .bind(("other", other))
+    .await?
+    .check()?;
+
+    Ok(())
+}
+
+/// Set the status of transactions, like marking the transactions seen on a statement
+/// as cleared. Setting the status of reconciled transactions back to cleared unlocks them.
+pub async fn set_transactions_status(
+    db: &Surreal<Db>,
+    transaction_ids: Vec<RecordId>,
+    status: transaction::Status,
 ) -> Result<(), surrealdb::Error> {
-    let _: Option<Record> = db
-        .update(("transaction", transaction.id.key().clone()))
-        .merge(transaction)
+    db.query("UPDATE transaction SET status = $status WHERE id IN $transaction_ids")
+        .bind(("transaction_ids", transaction_ids))
+        .bind(("status", status))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddTransferOptions {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub from: RecordId,
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub to: RecordId,
+    /// Amount withdrawn from the `from` account, in its currency.
+    #[ts(type = "string")]
+    pub amount: Decimal,
+    /// Amount of the `to` account currency for one unit of the `from` account currency.
+    /// Required when both accounts have different currencies.
+    #[ts(type = "string | null", optional)]
+    pub rate: Option<Decimal>,
+    pub description: String,
+    pub tags: Vec<Tag>,
+    #[ts(as = "Option<String>", optional)]
+    pub date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Identifier of the transfer in the file it was imported from, given to both transactions.
+    #[ts(optional)]
+    #[serde(default)]
+    pub fitid: Option<String>,
+}
+
+/// Move money between two accounts, creating a transaction in both accounts
+/// linked to each other.
+pub async fn add_transfer(
+    db: &Surreal<Db>,
+    options: AddTransferOptions,
+) -> Result<Transfer, Error> {
+    if options.from == options.to {
+        return Err(Error::Invalid(
+            "cannot transfer money to the same account".to_string(),
+        ));
+    }
+
+    if options.amount <= Decimal::ZERO {
+        return Err(Error::Invalid(
+            "the amount of a transfer must be positive".to_string(),
+        ));
+    }
+
+    let from_currency = get_currency(db, options.from.clone()).await?;
+    let to_currency = get_currency(db, options.to.clone()).await?;
+    let rate = match options.rate {
+        Some(rate) if rate > Decimal::ZERO => rate,
+        Some(_) => {
+            return Err(Error::Invalid(
+                "the rate of a transfer must be positive".to_string(),
+            ))
+        }
+        None if from_currency == to_currency => Decimal::ONE,
+        None => {
+            return Err(Error::Invalid(format!(
+                "a rate is required to transfer money from {from_currency} to {to_currency}"
+            )))
+        }
+    };
+
+    let query = r#"
+    BEGIN TRANSACTION;
+    LET $from = (CREATE ONLY transaction SET
+        date = $date,
+        amount = $from_amount,
+        description = $description,
+        tags = $tags,
+        fitid = $fitid,
+        account = $from_account_id);
+    LET $to = (CREATE ONLY transaction SET
+        date = $date,
+        amount = $to_amount,
+        description = $description,
+        tags = $tags,
+        fitid = $fitid,
+        account = $to_account_id,
+        transfer = $from.id);
+    UPDATE $from.id SET transfer = $to.id;
+    COMMIT TRANSACTION;
+    SELECT * FROM ONLY $from.id;
+    SELECT * FROM ONLY $to.id;"#;
+
+    let mut response = db
+        .query(query)
+        .bind(("date", options.date.unwrap_or_else(chrono::Utc::now)))
+        .bind(("from_amount", -money::round(options.amount, &from_currency)))
+        .bind((
+            "to_amount",
+            money::round(options.amount * rate, &to_currency),
+        ))
+        .bind(("description", options.description))
+        .bind(("tags", serde_json::json!(options.tags)))
+        .bind(("fitid", options.fitid))
+        .bind(("from_account_id", options.from))
+        .bind(("to_account_id", options.to))
         .await?;
 
+    let from: Option<TransactionWithId> = response.take(3)?;
+    let to: Option<TransactionWithId> = response.take(4)?;
+
+    from.zip(to)
+        .map(|(from, to)| Transfer { from, to })
+        .ok_or(Error::RecordNotFound)
+}
+
+/// Move a transaction to the trash.
+pub async fn delete_transaction(db: &Surreal<Db>, transaction_id: RecordId) -> Result<(), Error> {
+    delete_transactions(db, vec![transaction_id]).await
+}
+
+/// Move transactions to the trash, where they can be restored until the trash
+/// retention period expires. Both legs of transfers are deleted.
+///
+/// Nothing is deleted if one of the transactions is reconciled.
+pub async fn delete_transactions(
+    db: &Surreal<Db>,
+    transaction_ids: Vec<RecordId>,
+) -> Result<(), Error> {
+    let reconciled: Option<TransactionWithId> = db
+        .query("SELECT * FROM ONLY transaction WHERE (id IN $transaction_ids OR transfer IN $transaction_ids) AND status = 'reconciled' LIMIT 1")
+        .bind(("transaction_ids", transaction_ids.clone()))
+        .await?
+        .take(0)?;
+    if let Some(reconciled) = reconciled {
+        check_unlocked(&reconciled, None).map_err(Error::Invalid)?;
+    }
+
+    let query = r#"
+    BEGIN TRANSACTION;
+    LET $deleted = (
+        DELETE transaction WHERE id IN $transaction_ids OR transfer IN $transaction_ids
+        RETURN BEFORE
+    );
+    INSERT INTO trash (
+        SELECT record::id(id) AS id, $this AS transaction, time::now() AS deleted_at FROM $deleted
+    );
+    COMMIT TRANSACTION;"#;
+
+    db.query(query)
+        .bind(("transaction_ids", transaction_ids))
+        .await?
+        .check()?;
+
+    purge_trash(db).await?;
+
+    Ok(())
+}
+
+pub async fn get_trash(db: &Surreal<Db>) -> Result<Vec<TrashedTransaction>, surrealdb::Error> {
+    db.query("SELECT * FROM trash ORDER BY deleted_at DESC")
+        .await?
+        .take(0)
+}
+
+/// Restore transactions from the trash, with the other leg of transfers.
+///
+/// Transactions of accounts that have been deleted in the meantime stay in the trash.
+pub async fn restore_transactions(
+    db: &Surreal<Db>,
+    transaction_ids: Vec<RecordId>,
+) -> Result<Vec<TransactionWithId>, surrealdb::Error> {
+    let query = r#"
+    BEGIN TRANSACTION;
+    LET $restored = (
+        DELETE trash
+        WHERE (transaction.id IN $transaction_ids OR transaction.transfer IN $transaction_ids)
+            AND record::exists(transaction.account)
+        RETURN BEFORE
+    );
+    INSERT INTO transaction $restored.transaction;
+    COMMIT TRANSACTION;"#;
+
+    db.query(query)
+        .bind(("transaction_ids", transaction_ids))
+        .await?
+        .take(1)
+}
+
+/// Permanently delete transactions that stayed in the trash longer than the retention
+/// period of the settings.
+pub async fn purge_trash(db: &Surreal<Db>) -> Result<(), surrealdb::Error> {
+    let settings: Option<settings::Settings> = db.select(("settings", "main")).await?;
+    let retention_days = settings
+        .map(|settings| settings.trash_retention_days)
+        .unwrap_or(30);
+
+    db.query("DELETE trash WHERE deleted_at < $limit")
+        .bind((
+            "limit",
+            surrealdb::Datetime::from(
+                chrono::Utc::now() - chrono::Duration::days(retention_days.into()),
+            ),
+        ))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Permanently delete every transaction in the trash.
+pub async fn empty_trash(db: &Surreal<Db>) -> Result<(), surrealdb::Error> {
+    db.query("DELETE trash").await?.check()?;
+
     Ok(())
 }
 
@@ -245,31 +698,97 @@ pub struct GetTransactionOptions {
     /// Cannot be combined with `start` and `end` options.
     #[ts(optional)]
     pub last_x_days: Option<usize>,
+    /// Only get transactions matching this filter, see [`filter`] for the syntax.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Columns to sort by, in order. Transactions are sorted by date by default.
+    #[ts(as = "Option<Vec<page::Sort>>", optional)]
+    #[serde(default)]
+    pub sort: Vec<page::Sort>,
+    /// Maximum number of transactions to get.
+    #[ts(optional)]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Get the transactions after this cursor, returned with the previous page.
+    /// The sort must be the same as for the previous page.
+    #[ts(optional)]
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Also get the balance of the account after each transaction, counting all the
+    /// transactions of the account in date order, whatever the filter and the sort.
+    #[ts(optional)]
+    #[serde(default)]
+    pub running_balance: Option<bool>,
 }
 
 pub async fn get_transactions(
     db: &Surreal<Db>,
     account_id: RecordId,
     options: GetTransactionOptions,
-) -> Result<Vec<TransactionWithId>, surrealdb::Error> {
-    let mut query = "SELECT * FROM transaction WHERE account = $account_id".to_string();
+) -> Result<Vec<TransactionWithId>, Error> {
+    Ok(select_transactions(db, account_id, options, false)
+        .await?
+        .transactions)
+}
+
+/// Get a page of transactions, with the total number of transactions matching the
+/// options and the cursor of the next page.
+pub async fn get_transaction_page(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    options: GetTransactionOptions,
+) -> Result<page::TransactionPage, Error> {
+    select_transactions(db, account_id, options, true).await
+}
+
+async fn select_transactions(
+    db: &Surreal<Db>,
+    account_id: RecordId,
+    options: GetTransactionOptions,
+    count: bool,
+) -> Result<page::TransactionPage, Error> {
+    if options.limit == Some(0) {
+        return Err(Error::Invalid("the limit must be positive".to_string()));
+    }
+
+    let mut conditions = "account = $account_id".to_string();
 
     if options.last_x_days.is_some() {
-        query.push_str(" AND date >= time::now() - $last_x_days AND date <= time::now()");
+        conditions.push_str(" AND date >= time::now() - $last_x_days AND date <= time::now()");
     } else {
         if options.start.is_some() {
-            query.push_str(" AND date >= $start");
+            conditions.push_str(" AND date >= $start");
         }
 
         if options.end.is_some() {
-            query.push_str(" AND date <= $end");
+            conditions.push_str(" AND date <= $end");
         }
     }
 
-    query.push_str(" ORDER BY date");
+    let filter = filter::parse(options.filter.as_deref())?;
+    let mut params = filter::Filter::push_condition(filter.as_ref(), &mut conditions);
+
+    let sorting =
+        page::Sorting::new(&options.sort, options.cursor.as_deref()).map_err(Error::Invalid)?;
+    let mut query = format!(
+        "SELECT *{} FROM transaction WHERE {conditions}",
+        sorting.projection()
+    );
+    params.extend(sorting.push_condition(&mut query));
+    query.push_str(&sorting.order());
+    if options.limit.is_some() {
+        // One more transaction tells whether there is a next page.
+        query.push_str(" LIMIT $limit");
+    }
+    query.push(';');
+    if count {
+        query.push_str(&format!(
+            "SELECT count() AS total FROM transaction WHERE {conditions} GROUP ALL;"
+        ));
+    }
 
-    let transactions: Vec<TransactionWithId> = db
-        .query(query)
+    let mut response = filter::bind(db.query(query), params)
         .bind((
             "last_x_days",
             options
@@ -280,10 +799,104 @@ pub async fn get_transactions(
         .bind(("start", options.start.unwrap_or_default()))
         .bind(("end", options.end.unwrap_or_default()))
         .bind(("account_id", account_id))
-        .await?
-        .take(0)?;
+        .bind((
+            "limit",
+            // SurrealDB limits are 32 bits.
+            options
+                .limit
+                .unwrap_or_default()
+                .saturating_add(1)
+                .min(u32::MAX as usize),
+        ))
+        .await?;
+
+    let mut transactions: Vec<TransactionWithId> = response.take(0)?;
+    let total: Option<usize> = if count {
+        response.take((1, "total"))?
+    } else {
+        None
+    };
+
+    let next_cursor = match options.limit {
+        Some(limit) if transactions.len() > limit => {
+            transactions.truncate(limit);
+            transactions.last().map(|last| sorting.cursor(last))
+        }
+        _ => None,
+    };
+
+    let running_balances = if options.running_balance.unwrap_or_default() {
+        running_balances(db, &transactions).await?
+    } else {
+        vec![]
+    };
+
+    Ok(page::TransactionPage {
+        total: total.unwrap_or(transactions.len()),
+        transactions,
+        next_cursor,
+        running_balances,
+    })
+}
 
-    Ok(transactions)
+/// Balance of the account after each transaction, summing the transactions in date
+/// order, then by id for transactions with the same date.
+async fn running_balances(
+    db: &Surreal<Db>,
+    transactions: &[TransactionWithId],
+) -> Result<Vec<Decimal>, Error> {
+    #[derive(serde::Deserialize)]
+    struct Amount {
+        id: RecordId,
+        amount: Decimal,
+    }
+
+    let (Some(first), Some(earliest), Some(latest)) = (
+        transactions.first(),
+        transactions
+            .iter()
+            .map(|transaction| transaction.inner.date)
+            .min(),
+        transactions
+            .iter()
+            .map(|transaction| transaction.inner.date)
+            .max(),
+    ) else {
+        return Ok(vec![]);
+    };
+
+    // Only the transactions of the dates of the page are read, those before are summed.
+    let query = r#"
+    RETURN (SELECT math::sum(amount) AS sum FROM transaction WHERE account = $account_id AND date < $earliest GROUP ALL).sum;
+    SELECT id, date, amount FROM transaction WHERE account = $account_id AND date >= $earliest AND date <= $latest ORDER BY date, id;"#;
+
+    let mut response = db
+        .query(query)
+        .bind(("account_id", first.account.clone()))
+        .bind(("earliest", surrealdb::Datetime::from(earliest)))
+        .bind(("latest", surrealdb::Datetime::from(latest)))
+        .await?;
+    let opening: Option<Decimal> = response.take(0)?;
+    let amounts: Vec<Amount> = response.take(1)?;
+
+    let mut balance = opening.unwrap_or_default();
+    let balances: std::collections::HashMap<String, Decimal> = amounts
+        .into_iter()
+        .map(|amount| {
+            balance += amount.amount;
+            (amount.id.to_string(), balance)
+        })
+        .collect();
+
+    Ok(transactions
+        .iter()
+        .map(|transaction| {
+            balances
+                .get(&transaction.id.to_string())
+                .copied()
+                .unwrap_or_default()
+        })
+        .collect())
 }
 
 #[derive(ts_rs::TS)]
@@ -291,7 +904,14 @@ pub async fn get_transactions(
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct AccountWithBalance {
     pub account: Account,
-    pub balance: f64,
+    #[ts(type = "string")]
+    pub balance: Decimal,
+    /// Sum of incoming transactions, transfers excluded.
+    #[ts(type = "string")]
+    pub income: Decimal,
+    /// Sum of outgoing transactions, transfers excluded.
+    #[ts(type = "string")]
+    pub expenses: Decimal,
 }
 
 #[derive(ts_rs::TS)]
@@ -299,7 +919,12 @@ pub struct AccountWithBalance {
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct CurrencyBalance {
     pub currency: String,
-    pub total_balance: f64,
+    #[ts(type = "string")]
+    pub total_balance: Decimal,
+    #[ts(type = "string")]
+    pub total_income: Decimal,
+    #[ts(type = "string")]
+    pub total_expenses: Decimal,
     pub accounts: Vec<AccountWithBalance>,
 }
 
@@ -309,11 +934,15 @@ pub async fn balances_by_currency(
     let query = r#"
         SELECT 
             math::sum(balance) as total_balance,
-            array::group([{account: account, balance: balance}]) as accounts,
+            math::sum(income) as total_income,
+            math::sum(expenses) as total_expenses,
+            array::group([{account: account, balance: balance, income: income, expenses: expenses}]) as accounts,
             account.currency as currency
         FROM (
             SELECT
                 math::sum(amount) as balance,
+                math::sum(IF transfer = NONE AND amount > 0 THEN amount ELSE 0dec END) as income,
+                math::sum(IF transfer = NONE AND amount < 0 THEN amount ELSE 0dec END) as expenses,
                 account
             FROM transaction
             GROUP BY account
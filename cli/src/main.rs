@@ -0,0 +1,2414 @@
+use std::path::PathBuf;
+
+use chrono::Datelike;
+use clap::{Args, Parser, Subcommand};
+use surrealdb::{
+    engine::local::{Db, RocksDb},
+    RecordId, Surreal,
+};
+use thunes_cli::{
+    archive::ArchiveFormat,
+    budget::{AddBudgetOptions, Allocation},
+    checkpoint::AddCheckpointOptions,
+    duplicate::{FindDuplicatesOptions, DEFAULT_DAYS, DEFAULT_MIN_SCORE},
+    exchange::{AddExchangeRateOptions, ConversionOptions},
+    export::{JournalFormat, JournalOptions},
+    import::ImportReport,
+    page::{Sort, SortDirection, SortField},
+    report::{BalanceHistoryOptions, Baseline, CompareOptions, Delta, ReportOptions},
+    rule::{Actions, AddRuleOptions, Conditions, Rewrite, RunRulesOptions},
+    schedule::{AddScheduleOptions, Frequency, NthWeekday, Recurrence},
+    script::ScriptAccountBalance,
+    settings::Settings,
+    transaction::{Split, Status, Tag, TransactionRhai},
+    AddAccountOptions, AddTransactionOptions, AddTransferOptions, BalanceOptions, Error,
+    GetTransactionOptions, Record,
+};
+
+/// Identifier of the desktop app, used to share the same store.
+const APP_IDENTIFIER: &str = "com.thunes.app";
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Register and analyze monetary transactions.
+#[derive(Parser)]
+#[command(name = "thunes", version, about)]
+struct Cli {
+    /// Path to the database store. Defaults to the store of the desktop app.
+    #[arg(long, global = true)]
+    store: Option<PathBuf>,
+    /// Print results as JSON instead of plain text.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage accounts.
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// Manage transactions of an account.
+    #[command(subcommand)]
+    Transaction(TransactionCommand),
+    /// Manage tags.
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Manage deleted transactions.
+    #[command(subcommand)]
+    Trash(TrashCommand),
+    /// Manage budgets.
+    #[command(subcommand)]
+    Budget(BudgetCommand),
+    /// Manage recurring transactions.
+    #[command(subcommand)]
+    Schedule(ScheduleCommand),
+    /// Manage balances given by the bank and reconcile accounts with them.
+    #[command(subcommand)]
+    Checkpoint(CheckpointCommand),
+    /// Manage rules tagging and cleaning up transactions.
+    #[command(subcommand)]
+    Rule(RuleCommand),
+    /// Find and merge transactions recorded twice.
+    #[command(subcommand)]
+    Duplicate(DuplicateCommand),
+    /// Move money between two accounts.
+    Transfer(TransferArgs),
+    /// Display the balance of an account, or of all accounts grouped by currency.
+    Balance(BalanceArgs),
+    /// Display the balances of all accounts converted into a single currency.
+    NetWorth {
+        /// Currency to convert to, defaults to the base currency of the settings.
+        #[arg(short, long)]
+        currency: Option<String>,
+        /// Net worth at the start of this date (YYYY-MM-DD), with the exchange rates valid
+        /// at this date. Defaults to now.
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        /// Convert each transaction with the exchange rate valid at its own date.
+        #[arg(long, conflicts_with = "date")]
+        at_transaction_date: bool,
+        /// Only count transactions matching this filter, like 'not tag:savings'.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Report on transactions over periods of time.
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// Manage exchange rates between currencies.
+    #[command(subcommand)]
+    Rate(RateCommand),
+    /// Export or import backups of the store.
+    #[command(subcommand)]
+    Backup(BackupCommand),
+    /// Import transactions from other formats.
+    #[command(subcommand)]
+    Import(ImportCommand),
+    /// Export transactions to other formats.
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// Run a Rhai script on every account, calling the `on_<account name>` functions.
+    Script {
+        /// Path to the script.
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AccountCommand {
+    /// List all accounts.
+    List,
+    /// Display an account.
+    Show {
+        /// Name or record id of the account.
+        account: String,
+    },
+    /// Create a new account.
+    Add {
+        /// Name of the account.
+        name: String,
+        /// Currency of the account.
+        #[arg(short, long)]
+        currency: String,
+    },
+    /// Delete an account and all of its transactions.
+    Delete {
+        /// Name or record id of the account.
+        account: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TransactionCommand {
+    /// List transactions of an account.
+    List {
+        /// Name or record id of the account.
+        account: String,
+        #[command(flatten)]
+        period: PeriodArgs,
+        /// Get transactions dated in the last `last_x_days` days before today.
+        #[arg(long, conflicts_with_all = ["start", "end"])]
+        last_x_days: Option<usize>,
+        /// Only list transactions matching this filter, like 'tag:food and amount < -50'.
+        #[arg(long)]
+        filter: Option<String>,
+        /// Column to sort by (date, description, amount, tags, payee or status), with
+        /// an optional direction like 'amount:desc', can be repeated.
+        #[arg(long, value_parser = parse_sort)]
+        sort: Vec<Sort>,
+        /// Maximum number of transactions to list.
+        #[arg(long)]
+        limit: Option<usize>,
+        /// List the transactions after this cursor, printed with the previous page.
+        #[arg(long, requires = "limit")]
+        cursor: Option<String>,
+        /// Display the balance of the account after each transaction.
+        #[arg(long)]
+        running_balance: bool,
+    },
+    /// Add a transaction to an account.
+    Add {
+        /// Name or record id of the account.
+        account: String,
+        /// Amount of the transaction, negative for spendings.
+        #[arg(short, long, allow_negative_numbers = true)]
+        amount: rust_decimal::Decimal,
+        /// Description of the transaction.
+        #[arg(short, long, default_value = "")]
+        description: String,
+        /// Tags of the transaction, can be repeated.
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        /// Line of a split transaction (AMOUNT[:TAG,TAG...[:MEMO]]), can be repeated.
+        /// Amounts of the lines must sum to the amount of the transaction.
+        #[arg(short, long = "split", value_parser = parse_split, allow_negative_numbers = true)]
+        splits: Vec<Split>,
+        /// Date of the transaction (YYYY-MM-DD), defaults to now.
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+    },
+    /// Move transactions to the trash. Reconciled transactions cannot be deleted.
+    Delete {
+        /// Record ids of the transactions.
+        #[arg(required = true)]
+        transactions: Vec<String>,
+    },
+    /// Set the status of transactions. Setting reconciled transactions back to cleared
+    /// allows to change their amount and date again.
+    Status {
+        /// Status of the transactions (uncleared, cleared or reconciled).
+        #[arg(value_parser = parse_status)]
+        status: Status,
+        /// Record ids of the transactions.
+        #[arg(required = true)]
+        transactions: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommand {
+    /// List deleted transactions.
+    List,
+    /// Restore deleted transactions.
+    Restore {
+        /// Record ids of the transactions.
+        #[arg(required = true)]
+        transactions: Vec<String>,
+    },
+    /// Permanently delete every transaction in the trash.
+    Empty,
+}
+
+#[derive(Subcommand)]
+enum RateCommand {
+    /// List all exchange rates.
+    List,
+    /// Add an exchange rate.
+    Add {
+        /// Currency converted from.
+        base: String,
+        /// Currency converted to.
+        quote: String,
+        /// Amount of the quote currency for one unit of the base currency.
+        rate: rust_decimal::Decimal,
+        /// Date from which the rate is valid (YYYY-MM-DD), defaults to today.
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        /// Where the rate comes from.
+        #[arg(long, default_value = "manual")]
+        source: String,
+    },
+    /// Delete an exchange rate.
+    Delete {
+        /// Record id of the exchange rate.
+        rate: String,
+    },
+    /// Import exchange rates from CSV files with `date,base,quote,rate` lines.
+    Import {
+        /// Paths to the files.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BudgetCommand {
+    /// List all budgets.
+    List,
+    /// Add a budget.
+    Add(AddBudgetArgs),
+    /// Delete a budget.
+    Delete {
+        /// Record id of the budget.
+        budget: String,
+    },
+    /// Compare the allocations of a budget with the actual spendings of a period.
+    Show {
+        /// Record id of the budget.
+        budget: String,
+        /// Show the period that contains this date (YYYY-MM-DD), defaults to today.
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+    },
+}
+
+#[derive(Args)]
+struct AddBudgetArgs {
+    /// Name of the budget.
+    name: String,
+    /// Name or record id of an account counted by the budget, can be repeated.
+    #[arg(short, long = "account", required = true)]
+    accounts: Vec<String>,
+    /// Length of the periods (daily, weekly, monthly or yearly).
+    #[arg(short, long, value_parser = parse_frequency, default_value = "monthly")]
+    period: Frequency,
+    /// Start of the first period (YYYY-MM-DD), defaults to the first day of the current month.
+    #[arg(long)]
+    start: Option<chrono::NaiveDate>,
+    /// Amount that can be spent each period on a tag (TAG:AMOUNT), can be repeated.
+    #[arg(long = "allocation", value_parser = parse_allocation)]
+    allocations: Vec<Allocation>,
+    /// Carry what is left of an allocation over to the next period.
+    #[arg(long)]
+    rollover: bool,
+    /// Only count transactions matching this filter, like 'not tag:reimbursed'.
+    #[arg(long)]
+    filter: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommand {
+    /// List all schedules.
+    List,
+    /// Add a recurring transaction to an account. Occurrences are created once they are due.
+    Add(AddScheduleArgs),
+    /// Delete a schedule, keeping the transactions it already created.
+    Delete {
+        /// Record id of the schedule.
+        schedule: String,
+    },
+    /// Do not create an occurrence of a schedule.
+    Skip {
+        /// Record id of the schedule.
+        schedule: String,
+        /// Date of the occurrence (YYYY-MM-DD).
+        date: chrono::NaiveDate,
+    },
+    /// Create an occurrence of a schedule on another date.
+    Postpone {
+        /// Record id of the schedule.
+        schedule: String,
+        /// Date of the occurrence (YYYY-MM-DD).
+        date: chrono::NaiveDate,
+        /// New date of the occurrence (YYYY-MM-DD).
+        to: chrono::NaiveDate,
+    },
+    /// List occurrences that are not created yet.
+    Upcoming {
+        /// List occurrences up to this date (YYYY-MM-DD), defaults to 30 days from now.
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+    },
+}
+
+#[derive(Args)]
+struct AddScheduleArgs {
+    /// Name or record id of the account.
+    account: String,
+    /// Amount of the transactions, negative for spendings.
+    #[arg(short, long, allow_negative_numbers = true)]
+    amount: rust_decimal::Decimal,
+    /// Description of the transactions.
+    #[arg(short, long, default_value = "")]
+    description: String,
+    /// Tags of the transactions, can be repeated.
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Date of the first occurrence (YYYY-MM-DD), defaults to today.
+    #[arg(long)]
+    start: Option<chrono::NaiveDate>,
+    /// How often the transaction occurs (daily, weekly, monthly or yearly).
+    #[arg(short, long, value_parser = parse_frequency)]
+    frequency: Frequency,
+    /// Number of periods between two occurrences.
+    #[arg(short, long, default_value_t = 1)]
+    interval: u32,
+    /// Occur on the nth weekday of the month (NTH:WEEKDAY, `2:tue` or `-1:fri` for the last
+    /// friday) instead of the day of the start date.
+    #[arg(long, value_parser = parse_nth_weekday, allow_hyphen_values = true)]
+    nth_weekday: Option<NthWeekday>,
+    /// Date of the last possible occurrence (YYYY-MM-DD).
+    #[arg(long)]
+    until: Option<chrono::NaiveDate>,
+    /// Total number of occurrences.
+    #[arg(long)]
+    count: Option<u32>,
+}
+
+#[derive(Subcommand)]
+enum CheckpointCommand {
+    /// List the checkpoints of an account.
+    List {
+        /// Name or record id of the account.
+        account: String,
+    },
+    /// Record the balance of an account given by the bank at the end of a day.
+    Add {
+        /// Name or record id of the account.
+        account: String,
+        /// Balance of the account.
+        #[arg(short, long, allow_negative_numbers = true)]
+        balance: rust_decimal::Decimal,
+        /// Date of the balance (YYYY-MM-DD), defaults to today.
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+    },
+    /// Delete a checkpoint.
+    Delete {
+        /// Record id of the checkpoint.
+        checkpoint: String,
+    },
+    /// Compare a checkpoint with the balance of its account, and mark the transactions
+    /// up to the checkpoint as reconciled if both agree.
+    Reconcile {
+        /// Record id of the checkpoint.
+        checkpoint: String,
+        /// Only display the difference, without reconciling transactions.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RuleCommand {
+    /// List all rules in the order they are applied.
+    List,
+    /// Add a rule, applied to every transaction added from now on. Conditions must all
+    /// match for the rule to apply.
+    Add(Box<AddRuleArgs>),
+    /// Apply a rule again.
+    Enable {
+        /// Record id of the rule.
+        rule: String,
+    },
+    /// Stop applying a rule, keeping the changes it already made.
+    Disable {
+        /// Record id of the rule.
+        rule: String,
+    },
+    /// Delete a rule, keeping the changes it already made.
+    Delete {
+        /// Record id of the rule.
+        rule: String,
+    },
+    /// Run enabled rules over existing transactions and display the changes.
+    Run {
+        /// Name or record id of the account. Transactions of all accounts are changed if omitted.
+        #[arg(short, long)]
+        account: Option<String>,
+        /// Only display the changes, without saving them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Args)]
+struct AddRuleArgs {
+    /// Name of the rule.
+    name: String,
+    /// Match descriptions containing this text, ignoring case.
+    #[arg(long)]
+    description_contains: Option<String>,
+    /// Match descriptions matching this regular expression.
+    #[arg(long)]
+    description_matches: Option<String>,
+    /// Match amounts greater than or equal to this amount.
+    #[arg(long, allow_negative_numbers = true)]
+    min: Option<rust_decimal::Decimal>,
+    /// Match amounts lower than or equal to this amount.
+    #[arg(long, allow_negative_numbers = true)]
+    max: Option<rust_decimal::Decimal>,
+    /// Match transactions of this account, name or record id, can be repeated.
+    #[arg(long = "account")]
+    accounts: Vec<String>,
+    #[command(flatten)]
+    period: PeriodArgs,
+    /// Add this tag to matching transactions, can be repeated.
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Replace the parts of the description matching PATTERN with REPLACEMENT, which can
+    /// reference groups of the pattern like `$1`.
+    #[arg(long, num_args = 2, value_names = ["PATTERN", "REPLACEMENT"])]
+    rewrite: Option<Vec<String>>,
+    /// Set the payee of matching transactions.
+    #[arg(long)]
+    payee: Option<String>,
+    /// Rules are applied by increasing priority.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    priority: i64,
+    /// Add the rule without applying it.
+    #[arg(long)]
+    disabled: bool,
+}
+
+#[derive(Subcommand)]
+enum DuplicateCommand {
+    /// List pairs of transactions that are likely duplicates, by decreasing score.
+    List {
+        /// Name or record id of the account. Transactions of all accounts are searched if omitted.
+        account: Option<String>,
+        /// Maximum number of days between the dates of duplicates.
+        #[arg(long, default_value_t = DEFAULT_DAYS)]
+        days: u32,
+        /// Minimum score of the listed pairs, between 0 and 1.
+        #[arg(long, default_value_t = DEFAULT_MIN_SCORE)]
+        min_score: f64,
+    },
+    /// Merge a duplicate into another transaction, which gets the tags of both, and
+    /// move the duplicate to the trash.
+    Merge {
+        /// Record id of the transaction to keep.
+        keep: String,
+        /// Record id of the duplicate.
+        duplicate: String,
+    },
+    /// Record that two transactions are not duplicates, so they are not listed again.
+    Dismiss {
+        /// Record id of the first transaction.
+        first: String,
+        /// Record id of the second transaction.
+        second: String,
+    },
+    /// List merged and dismissed pairs of transactions.
+    Log,
+}
+
+#[derive(Subcommand)]
+enum ReportCommand {
+    /// Display the balance at the end of each period, of an account or of all accounts
+    /// grouped by currency.
+    Balance {
+        /// Name or record id of the account. Balances of all accounts are displayed if omitted.
+        account: Option<String>,
+        /// Length of the periods (daily, weekly, monthly or yearly).
+        #[arg(short, long, value_parser = parse_frequency, default_value = "monthly")]
+        period: Frequency,
+        /// Date in the first period (YYYY-MM-DD).
+        #[arg(long)]
+        start: chrono::NaiveDate,
+        /// Date in the last period (YYYY-MM-DD), defaults to today.
+        #[arg(long)]
+        end: Option<chrono::NaiveDate>,
+        /// Only count transactions matching this filter, like 'not tag:savings'.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Display the incomes, expenses and net of each period, transfers excluded.
+    CashFlow {
+        #[command(flatten)]
+        report: ReportArgs,
+        /// Length of the periods (daily, weekly, monthly or yearly).
+        #[arg(short, long, value_parser = parse_frequency, default_value = "monthly")]
+        period: Frequency,
+    },
+    /// Display the spendings by tag, with their share of the total spendings.
+    Tags {
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+    /// Compare the spendings by tag and the incomes and spendings by account with
+    /// another range of dates, transfers excluded.
+    Compare {
+        #[command(flatten)]
+        report: ReportArgs,
+        /// Range to compare with (previous-period or previous-year).
+        #[arg(long, value_parser = parse_baseline, default_value = "previous-period")]
+        against: Baseline,
+        /// Start of the range to compare with (YYYY-MM-DD), included. Replaces --against.
+        #[arg(long, requires = "baseline_end", conflicts_with = "against")]
+        baseline_start: Option<chrono::NaiveDate>,
+        /// End of the range to compare with (YYYY-MM-DD), excluded.
+        #[arg(long, requires = "baseline_start")]
+        baseline_end: Option<chrono::NaiveDate>,
+    },
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// Name or record id of an account to report on, can be repeated. All accounts are
+    /// reported on if omitted.
+    #[arg(short, long = "account")]
+    accounts: Vec<String>,
+    /// Start of the report (YYYY-MM-DD), included.
+    #[arg(long)]
+    start: chrono::NaiveDate,
+    /// End of the report (YYYY-MM-DD), excluded. Defaults to now.
+    #[arg(long)]
+    end: Option<chrono::NaiveDate>,
+    /// Only count transactions matching this filter, like 'not tag:work'.
+    #[arg(long)]
+    filter: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ImportCommand {
+    /// Import JSON account files from the first versions of the cli. Accounts are named after the files.
+    Legacy {
+        /// Paths to the account files.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Import a CSV bank statement in an account, reading it with a saved profile.
+    Csv {
+        /// Path to the statement.
+        path: PathBuf,
+        /// Name or record id of the account.
+        #[arg(short, long)]
+        account: String,
+        /// Name of the profile describing the columns of the file.
+        #[arg(short, long)]
+        profile: String,
+        /// Only print the transactions that would be imported.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import a bank statement in an account, skipping transactions already imported.
+    Statement {
+        /// Path to the statement.
+        path: PathBuf,
+        /// Name or record id of the account.
+        #[arg(short, long)]
+        account: String,
+        /// Format of the statement (ofx, camt, mt940 or qif), detected from the extension
+        /// of the file by default.
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+    /// Import a beancount or hledger journal, creating its asset and liability accounts.
+    /// Income and expense accounts become tags.
+    Journal {
+        /// Path to the journal.
+        path: PathBuf,
+    },
+    /// Manage the profiles used to read CSV bank statements.
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+}
+
+#[derive(Subcommand)]
+enum ExportCommand {
+    /// Export the transactions of an account to a QIF file.
+    Qif {
+        /// Name or record id of the account.
+        account: String,
+        /// Destination of the export. Printed to the standard output by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export all accounts and transactions as a ledger journal.
+    Ledger(JournalArgs),
+    /// Export all accounts and transactions as an hledger journal.
+    Hledger(JournalArgs),
+    /// Export all accounts and transactions as a beancount file.
+    Beancount(JournalArgs),
+}
+
+#[derive(Args)]
+struct JournalArgs {
+    /// Destination of the export. Printed to the standard output by default.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Post amounts to income and expense accounts named after their first tag.
+    #[arg(long)]
+    tags_as_accounts: bool,
+    /// Account amounts with a tag are posted to (TAG=ACCOUNT), can be repeated.
+    #[arg(long = "map", value_parser = parse_mapping, requires = "tags_as_accounts")]
+    mappings: Vec<(String, String)>,
+    /// Only export transactions from this date (YYYY-MM-DD), older ones are summed
+    /// into opening balances.
+    #[arg(long)]
+    start: Option<chrono::NaiveDate>,
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List all profiles.
+    List,
+    /// Create or replace a profile from a JSON file.
+    Save {
+        /// Path to the profile.
+        path: PathBuf,
+    },
+    /// Delete a profile.
+    Delete {
+        /// Name of the profile.
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagCommand {
+    /// List all tags.
+    List,
+    /// Create or update a tag.
+    Add {
+        /// Label of the tag.
+        label: String,
+        /// Color of the tag.
+        #[arg(short, long)]
+        color: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct PeriodArgs {
+    /// Start of the period (YYYY-MM-DD).
+    #[arg(long)]
+    start: Option<chrono::NaiveDate>,
+    /// End of the period (YYYY-MM-DD).
+    #[arg(long)]
+    end: Option<chrono::NaiveDate>,
+}
+
+#[derive(Args)]
+struct BalanceArgs {
+    /// Name or record id of the account. Balances of all accounts are displayed if omitted.
+    account: Option<String>,
+    #[command(flatten)]
+    period: PeriodArgs,
+    /// Only sum transactions with this tag.
+    #[arg(short, long)]
+    tag: Option<String>,
+    /// Ignore transfers between accounts.
+    #[arg(long)]
+    exclude_transfers: bool,
+    /// Only sum transactions matching this filter, like 'tag:food and amount < -50'.
+    #[arg(long, requires = "account")]
+    filter: Option<String>,
+}
+
+#[derive(Args)]
+struct TransferArgs {
+    /// Name or record id of the account to withdraw from.
+    from: String,
+    /// Name or record id of the account to deposit to.
+    to: String,
+    /// Amount withdrawn, in the currency of the `from` account.
+    #[arg(short, long)]
+    amount: rust_decimal::Decimal,
+    /// Amount of the `to` account currency for one unit of the `from` account currency.
+    /// Required when both accounts have different currencies.
+    #[arg(short, long)]
+    rate: Option<rust_decimal::Decimal>,
+    /// Description of the transfer.
+    #[arg(short, long, default_value = "")]
+    description: String,
+    /// Tags of the transfer, can be repeated.
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Date of the transfer (YYYY-MM-DD), defaults to now.
+    #[arg(long)]
+    date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Subcommand)]
+enum BackupCommand {
+    /// Export the store as a SurrealQL file.
+    Export {
+        /// Destination of the backup. Defaults to a timestamped file in the backups directory.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a SurrealQL backup file into the store.
+    Import {
+        /// Path to the backup.
+        path: PathBuf,
+    },
+    /// Export accounts, transactions, tags and settings as a portable archive that does
+    /// not depend on the version of the database.
+    ExportArchive {
+        /// Destination of the archive, a file for JSON archives or a directory for CSV archives.
+        output: PathBuf,
+        /// Write a directory of CSV files, one per table, instead of a JSON file.
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Import a portable archive, a JSON file or a directory of CSV files. Records of the
+    /// store with the same ids are replaced.
+    ImportArchive {
+        /// Path to the archive.
+        path: PathBuf,
+    },
+}
+
+fn to_datetime(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+fn to_surreal_datetime(date: Option<chrono::NaiveDate>) -> Option<surrealdb::Datetime> {
+    date.map(|date| to_datetime(date).into())
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Find an account by record id (`account:xxx`) or by name.
+async fn resolve_account(db: &Surreal<Db>, account: &str) -> Result<RecordId> {
+    if let Ok(id) = account.parse::<RecordId>() {
+        if id.table() == "account" {
+            return Ok(id);
+        }
+    }
+
+    thunes_cli::list_account(db)
+        .await?
+        .into_iter()
+        .find(|identifiers| identifiers.name == account)
+        .map(|identifiers| identifiers.id)
+        .ok_or_else(|| format!("account '{account}' not found").into())
+}
+
+/// Parse a line of a split transaction, given as `AMOUNT[:TAGS[:MEMO]]`.
+fn parse_split(split: &str) -> std::result::Result<Split, String> {
+    let mut parts = split.splitn(3, ':');
+    let amount = parts
+        .next()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|error| format!("invalid amount: {error}"))?;
+    let tags = parts
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|label| !label.is_empty())
+        .map(|label| Tag {
+            label: label.to_string(),
+            color: None,
+        })
+        .collect();
+
+    Ok(Split {
+        amount,
+        tags,
+        memo: parts.next().unwrap_or_default().to_string(),
+    })
+}
+
+fn parse_frequency(frequency: &str) -> std::result::Result<Frequency, String> {
+    match frequency {
+        "daily" => Ok(Frequency::Daily),
+        "weekly" => Ok(Frequency::Weekly),
+        "monthly" => Ok(Frequency::Monthly),
+        "yearly" => Ok(Frequency::Yearly),
+        _ => Err("expected daily, weekly, monthly or yearly".to_string()),
+    }
+}
+
+fn parse_baseline(baseline: &str) -> std::result::Result<Baseline, String> {
+    match baseline {
+        "previous-period" => Ok(Baseline::PreviousPeriod),
+        "previous-year" => Ok(Baseline::PreviousYear),
+        _ => Err("expected previous-period or previous-year".to_string()),
+    }
+}
+
+fn parse_status(status: &str) -> std::result::Result<Status, String> {
+    match status {
+        "uncleared" => Ok(Status::Uncleared),
+        "cleared" => Ok(Status::Cleared),
+        "reconciled" => Ok(Status::Reconciled),
+        _ => Err("expected uncleared, cleared or reconciled".to_string()),
+    }
+}
+
+fn parse_sort(sort: &str) -> std::result::Result<Sort, String> {
+    let (field, direction) = sort.split_once(':').unwrap_or((sort, "asc"));
+
+    Ok(Sort {
+        field: match field {
+            "date" => SortField::Date,
+            "description" => SortField::Description,
+            "amount" => SortField::Amount,
+            "tags" => SortField::Tags,
+            "payee" => SortField::Payee,
+            "status" => SortField::Status,
+            _ => {
+                return Err("expected date, description, amount, tags, payee or status".to_string())
+            }
+        },
+        sort: match direction {
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => return Err("expected asc or desc after ':'".to_string()),
+        },
+    })
+}
+
+fn parse_allocation(allocation: &str) -> std::result::Result<Allocation, String> {
+    let (tag, amount) = allocation.rsplit_once(':').ok_or("expected TAG:AMOUNT")?;
+
+    Ok(Allocation {
+        tag: tag.to_string(),
+        amount: amount
+            .parse()
+            .map_err(|error| format!("invalid amount: {error}"))?,
+    })
+}
+
+fn parse_mapping(mapping: &str) -> std::result::Result<(String, String), String> {
+    let (tag, account) = mapping.split_once('=').ok_or("expected TAG=ACCOUNT")?;
+
+    Ok((tag.to_string(), account.to_string()))
+}
+
+fn parse_nth_weekday(nth_weekday: &str) -> std::result::Result<NthWeekday, String> {
+    let (nth, weekday) = nth_weekday.split_once(':').ok_or("expected NTH:WEEKDAY")?;
+
+    Ok(NthWeekday {
+        nth: nth
+            .parse()
+            .map_err(|error| format!("invalid nth: {error}"))?,
+        weekday: weekday
+            .parse()
+            .map_err(|_| format!("invalid weekday: {weekday}"))?,
+    })
+}
+
+/// Parse a record id of `table`, given either as `table:key` or as the key alone.
+fn parse_record_id(table: &str, id: &str) -> RecordId {
+    match id.parse::<RecordId>() {
+        Ok(id) if id.table() == table => id,
+        _ => RecordId::from((table, id)),
+    }
+}
+
+async fn open(store: Option<PathBuf>) -> Result<Surreal<Db>> {
+    let store = match store {
+        Some(store) => store,
+        None => dirs::config_dir()
+            .ok_or("failed to find the configuration directory, use --store")?
+            .join(APP_IDENTIFIER)
+            .join("store"),
+    };
+
+    let db: Surreal<Db> = Surreal::init();
+    db.connect::<RocksDb>(store).await?;
+    db.use_ns("user").use_db("accounts").await?;
+    thunes_cli::migration::run(&db).await?;
+
+    let backups_path = dirs::data_dir()
+        .unwrap_or_default()
+        .join(APP_IDENTIFIER)
+        .join("backups");
+
+    let result: std::result::Result<Option<Record>, surrealdb::Error> = db
+        .insert(("settings", "main"))
+        .content(Settings::new(backups_path))
+        .await;
+
+    match result {
+        Ok(_) | Err(surrealdb::Error::Db(surrealdb::error::Db::RecordExists { .. })) => {}
+        Err(error) => return Err(error.into()),
+    }
+
+    thunes_cli::purge_trash(&db).await?;
+    thunes_cli::schedule::materialize(&db, chrono::Utc::now()).await?;
+
+    Ok(db)
+}
+
+async fn account(db: &Surreal<Db>, json: bool, command: AccountCommand) -> Result<()> {
+    match command {
+        AccountCommand::List => {
+            let accounts = thunes_cli::list_account(db).await?;
+
+            if json {
+                return print_json(&accounts);
+            }
+
+            for account in accounts {
+                println!("{}\t{}", account.id, account.name);
+            }
+        }
+        AccountCommand::Show { account } => {
+            let account_id = resolve_account(db, &account).await?;
+            let account = thunes_cli::get_account(db, account_id).await?;
+
+            if json {
+                return print_json(&account);
+            }
+
+            println!("id:       {}", account.id);
+            println!("name:     {}", account.data.name);
+            println!("currency: {}", account.data.currency);
+        }
+        AccountCommand::Add { name, currency } => {
+            let account = thunes_cli::add_account(db, AddAccountOptions { name, currency }).await?;
+
+            if json {
+                return print_json(&account);
+            }
+
+            println!("{}", account.id);
+        }
+        AccountCommand::Delete { account } => {
+            let account_id = resolve_account(db, &account).await?;
+            thunes_cli::delete_account(db, account_id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn transaction(db: &Surreal<Db>, json: bool, command: TransactionCommand) -> Result<()> {
+    match command {
+        TransactionCommand::List {
+            account,
+            period,
+            last_x_days,
+            filter,
+            sort,
+            limit,
+            cursor,
+            running_balance,
+        } => {
+            let account_id = resolve_account(db, &account).await?;
+            let page = thunes_cli::get_transaction_page(
+                db,
+                account_id,
+                GetTransactionOptions {
+                    start: to_surreal_datetime(period.start),
+                    end: to_surreal_datetime(period.end),
+                    last_x_days,
+                    filter,
+                    sort,
+                    limit,
+                    cursor,
+                    running_balance: Some(running_balance),
+                },
+            )
+            .await?;
+
+            if json {
+                return if limit.is_some() || running_balance {
+                    print_json(&page)
+                } else {
+                    print_json(&page.transactions)
+                };
+            }
+
+            let listed = page.transactions.len();
+            for (index, transaction) in page.transactions.into_iter().enumerate() {
+                let tags = transaction
+                    .inner
+                    .tags
+                    .iter()
+                    .map(|tag| tag.label.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                let balance = page
+                    .running_balances
+                    .get(index)
+                    .map(|balance| format!("\t{balance}"))
+                    .unwrap_or_default();
+
+                println!(
+                    "{}\t{}\t{}{balance}\t{}\t{}",
+                    transaction.id,
+                    transaction.inner.date.format("%Y-%m-%d"),
+                    transaction.inner.amount,
+                    transaction.inner.description,
+                    tags
+                );
+
+                for split in transaction.inner.splits {
+                    let tags = split
+                        .tags
+                        .iter()
+                        .map(|tag| tag.label.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",");
+
+                    println!("\t\t{}\t{}\t{}", split.amount, split.memo, tags);
+                }
+            }
+
+            if limit.is_some() {
+                println!("{listed} of {} transaction(s)", page.total);
+                if let Some(cursor) = page.next_cursor {
+                    println!("next page: --cursor {cursor}");
+                }
+            }
+        }
+        TransactionCommand::Add {
+            account,
+            amount,
+            description,
+            tags,
+            splits,
+            date,
+        } => {
+            let account_id = resolve_account(db, &account).await?;
+            let tags = tags
+                .into_iter()
+                .map(|label| Tag { label, color: None })
+                .collect::<Vec<_>>();
+
+            thunes_cli::add_tags(
+                db,
+                tags.iter()
+                    .chain(splits.iter().flat_map(|split| split.tags.iter()))
+                    .cloned()
+                    .collect(),
+            )
+            .await?;
+            thunes_cli::add_transaction(
+                db,
+                account_id,
+                AddTransactionOptions {
+                    amount,
+                    description,
+                    tags,
+                    splits,
+                    date: date.map(to_datetime),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        TransactionCommand::Delete { transactions } => {
+            thunes_cli::delete_transactions(
+                db,
+                transactions
+                    .iter()
+                    .map(|transaction| parse_record_id("transaction", transaction))
+                    .collect(),
+            )
+            .await?;
+        }
+        TransactionCommand::Status {
+            status,
+            transactions,
+        } => {
+            thunes_cli::set_transactions_status(
+                db,
+                transactions
+                    .iter()
+                    .map(|transaction| parse_record_id("transaction", transaction))
+                    .collect(),
+                status,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn trash(db: &Surreal<Db>, json: bool, command: TrashCommand) -> Result<()> {
+    match command {
+        TrashCommand::List => {
+            let trash = thunes_cli::get_trash(db).await?;
+
+            if json {
+                return print_json(&trash);
+            }
+
+            for trashed in trash {
+                println!(
+                    "{}\t{}\t{}\t{}\tdeleted {}",
+                    trashed.transaction.id,
+                    trashed.transaction.inner.date.format("%Y-%m-%d"),
+                    trashed.transaction.inner.amount,
+                    trashed.transaction.inner.description,
+                    trashed.deleted_at.format("%Y-%m-%d %H:%M")
+                );
+            }
+        }
+        TrashCommand::Restore { transactions } => {
+            let requested = transactions.len();
+            let restored = thunes_cli::restore_transactions(
+                db,
+                transactions
+                    .iter()
+                    .map(|transaction| parse_record_id("transaction", transaction))
+                    .collect(),
+            )
+            .await?;
+
+            if json {
+                return print_json(&restored);
+            }
+
+            println!("{}/{requested} transaction(s) restored", restored.len());
+        }
+        TrashCommand::Empty => thunes_cli::empty_trash(db).await?,
+    }
+
+    Ok(())
+}
+
+async fn tag(db: &Surreal<Db>, json: bool, command: TagCommand) -> Result<()> {
+    match command {
+        TagCommand::List => {
+            let tags = thunes_cli::get_tags(db).await?;
+
+            if json {
+                return print_json(&tags);
+            }
+
+            for tag in tags {
+                match tag.color {
+                    Some(color) => println!("{}\t{color}", tag.label),
+                    None => println!("{}", tag.label),
+                }
+            }
+        }
+        TagCommand::Add { label, color } => {
+            thunes_cli::add_tags(db, vec![Tag { label, color }]).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn rate(db: &Surreal<Db>, json: bool, command: RateCommand) -> Result<()> {
+    match command {
+        RateCommand::List => {
+            let rates = thunes_cli::exchange::list_exchange_rates(db).await?;
+
+            if json {
+                return print_json(&rates);
+            }
+
+            for rate in rates {
+                println!(
+                    "{}\t{}\t{}/{}\t{}\t{}",
+                    rate.id,
+                    rate.date.format("%Y-%m-%d"),
+                    rate.base,
+                    rate.quote,
+                    rate.rate,
+                    rate.source
+                );
+            }
+        }
+        RateCommand::Add {
+            base,
+            quote,
+            rate,
+            date,
+            source,
+        } => {
+            let rate = thunes_cli::exchange::add_exchange_rate(
+                db,
+                AddExchangeRateOptions {
+                    base,
+                    quote,
+                    date: to_datetime(date.unwrap_or_else(|| chrono::Utc::now().date_naive())),
+                    rate,
+                    source,
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&rate);
+            }
+
+            println!("{}", rate.id);
+        }
+        RateCommand::Delete { rate } => {
+            thunes_cli::exchange::delete_exchange_rate(db, parse_record_id("exchange_rate", &rate))
+                .await?;
+        }
+        RateCommand::Import { paths } => {
+            for path in paths {
+                let imported = thunes_cli::exchange::import_file(db, &path).await?;
+
+                if !json {
+                    println!("{}: {imported} rate(s) imported", path.display());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn net_worth(
+    db: &Surreal<Db>,
+    json: bool,
+    currency: Option<String>,
+    date: Option<chrono::NaiveDate>,
+    at_transaction_date: bool,
+    filter: Option<String>,
+) -> Result<()> {
+    let balances = thunes_cli::exchange::convert_balances(
+        db,
+        ConversionOptions {
+            currency,
+            date: date.map(to_datetime),
+            at_transaction_date: Some(at_transaction_date),
+            filter,
+        },
+    )
+    .await?;
+
+    if json {
+        return print_json(&balances);
+    }
+
+    println!("{} {}", balances.total, balances.currency);
+    for account in balances.accounts {
+        println!(
+            "  {}\t{} {}\t({} {})",
+            account.account.data.name,
+            account.converted,
+            balances.currency,
+            account.balance,
+            account.account.data.currency
+        );
+    }
+
+    Ok(())
+}
+
+async fn report_options(db: &Surreal<Db>, args: ReportArgs) -> Result<ReportOptions> {
+    let mut accounts = vec![];
+    for account in &args.accounts {
+        accounts.push(resolve_account(db, account).await?);
+    }
+
+    Ok(ReportOptions {
+        accounts,
+        start: to_datetime(args.start),
+        end: args.end.map(to_datetime),
+        filter: args.filter,
+    })
+}
+
+async fn report(db: &Surreal<Db>, json: bool, command: ReportCommand) -> Result<()> {
+    match command {
+        ReportCommand::Balance {
+            account,
+            period,
+            start,
+            end,
+            filter,
+        } => {
+            let account = match account {
+                Some(account) => Some(resolve_account(db, &account).await?),
+                None => None,
+            };
+            let histories = thunes_cli::report::balance_history(
+                db,
+                BalanceHistoryOptions {
+                    account,
+                    period,
+                    start: to_datetime(start),
+                    end: end.map(to_datetime),
+                    filter,
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&histories);
+            }
+
+            for history in histories {
+                println!("{}", history.currency);
+                for point in history.points {
+                    // Periods are displayed by their last day.
+                    let last_day = point.period_end.date_naive().pred_opt().unwrap_or_default();
+                    println!("  {last_day}\t{}", point.balance);
+                }
+            }
+        }
+        ReportCommand::CashFlow { report, period } => {
+            let options = report_options(db, report).await?;
+            let cash_flows = thunes_cli::report::cash_flow(db, options, period).await?;
+
+            if json {
+                return print_json(&cash_flows);
+            }
+
+            for cash_flow in cash_flows {
+                let total = cash_flow.total;
+                println!(
+                    "{}\t(income {}, expenses {}, net {})",
+                    cash_flow.currency, total.income, total.expenses, total.net
+                );
+                for period in cash_flow.periods {
+                    println!(
+                        "  {}\t{}\t{}\t{}",
+                        period.period_start.date_naive(),
+                        period.income,
+                        period.expenses,
+                        period.net
+                    );
+                }
+            }
+        }
+        ReportCommand::Tags { report } => {
+            let options = report_options(db, report).await?;
+            let spendings = thunes_cli::report::spending_by_tag(db, options).await?;
+
+            if json {
+                return print_json(&spendings);
+            }
+
+            for spending in spendings {
+                println!("{}\t{}", spending.currency, spending.total);
+                for tag in spending.tags {
+                    println!(
+                        "  {}\t{}\t{}%",
+                        tag.tag.as_deref().unwrap_or("(untagged)"),
+                        tag.spent,
+                        tag.percentage
+                    );
+                }
+            }
+        }
+        ReportCommand::Compare {
+            report,
+            against,
+            baseline_start,
+            baseline_end,
+        } => {
+            let baseline = match baseline_start.zip(baseline_end) {
+                Some((start, end)) => Baseline::Range {
+                    start: to_datetime(start),
+                    end: to_datetime(end),
+                },
+                None => against,
+            };
+            let options = CompareOptions {
+                report: report_options(db, report).await?,
+                baseline,
+            };
+            let comparisons = thunes_cli::report::compare(db, options).await?;
+
+            if json {
+                return print_json(&comparisons);
+            }
+
+            for comparison in comparisons {
+                println!(
+                    "{}\t{} - {} against {} - {}",
+                    comparison.currency,
+                    comparison.start.date_naive(),
+                    comparison.end.date_naive(),
+                    comparison.baseline_start.date_naive(),
+                    comparison.baseline_end.date_naive()
+                );
+                println!("  spent\t{}", format_delta(&comparison.spent));
+                for tag in comparison.tags {
+                    println!(
+                        "  {}\t{}",
+                        tag.tag.as_deref().unwrap_or("(untagged)"),
+                        format_delta(&tag.spent)
+                    );
+                }
+                for account in comparison.accounts {
+                    println!("  {}", account.name);
+                    println!("    income\t{}", format_delta(&account.income));
+                    println!("    spent\t{}", format_delta(&account.spent));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Current and previous amounts, with the change between them.
+fn format_delta(delta: &Delta) -> String {
+    let percentage = match delta.percentage {
+        Some(percentage) => format!("{percentage:+}%"),
+        None => "-".to_string(),
+    };
+
+    format!(
+        "{}\t{}\t{:+}\t{percentage}",
+        delta.current, delta.previous, delta.change
+    )
+}
+
+async fn budget(db: &Surreal<Db>, json: bool, command: BudgetCommand) -> Result<()> {
+    match command {
+        BudgetCommand::List => {
+            let budgets = thunes_cli::budget::list_budgets(db).await?;
+
+            if json {
+                return print_json(&budgets);
+            }
+
+            for budget in budgets {
+                println!("{}\t{}", budget.id, budget.name);
+            }
+        }
+        BudgetCommand::Add(args) => {
+            let mut accounts = vec![];
+            for account in &args.accounts {
+                accounts.push(resolve_account(db, account).await?);
+            }
+
+            thunes_cli::add_tags(
+                db,
+                args.allocations
+                    .iter()
+                    .map(|allocation| Tag {
+                        label: allocation.tag.clone(),
+                        color: None,
+                    })
+                    .collect(),
+            )
+            .await?;
+
+            let budget = thunes_cli::budget::add_budget(
+                db,
+                AddBudgetOptions {
+                    name: args.name,
+                    accounts,
+                    period: args.period,
+                    start: to_datetime(args.start.unwrap_or_else(|| {
+                        chrono::Utc::now()
+                            .date_naive()
+                            .with_day(1)
+                            .unwrap_or_default()
+                    })),
+                    allocations: args.allocations,
+                    rollover: args.rollover,
+                    filter: args.filter,
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&budget);
+            }
+
+            println!("{}", budget.id);
+        }
+        BudgetCommand::Delete { budget } => {
+            thunes_cli::budget::delete_budget(db, parse_record_id("budget", &budget)).await?;
+        }
+        BudgetCommand::Show { budget, date } => {
+            let report = thunes_cli::budget::budget_vs_actual(
+                db,
+                parse_record_id("budget", &budget),
+                date.map(to_datetime).unwrap_or_else(chrono::Utc::now),
+            )
+            .await?;
+
+            if json {
+                return print_json(&report);
+            }
+
+            println!(
+                "{} from {} to {}",
+                report.name,
+                report.period_start.format("%Y-%m-%d"),
+                report.period_end.format("%Y-%m-%d")
+            );
+            for envelope in report.envelopes {
+                println!(
+                    "  {}\tspent {} of {} (rolled over {})\tremaining {} {}",
+                    envelope.tag,
+                    envelope.spent,
+                    envelope.allocated,
+                    envelope.rolled_over,
+                    envelope.remaining,
+                    report.currency
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn schedule(db: &Surreal<Db>, json: bool, command: ScheduleCommand) -> Result<()> {
+    match command {
+        ScheduleCommand::List => {
+            let schedules = thunes_cli::schedule::list_schedules(db).await?;
+
+            if json {
+                return print_json(&schedules);
+            }
+
+            for schedule in schedules {
+                let next = schedule
+                    .recurrence
+                    .occurrence(schedule.start, schedule.next_occurrence)
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "ended".to_string());
+
+                println!(
+                    "{}\t{}\t{}\t{}\tnext: {next}",
+                    schedule.id, schedule.account, schedule.amount, schedule.description
+                );
+            }
+        }
+        ScheduleCommand::Add(args) => {
+            let tags = args
+                .tags
+                .into_iter()
+                .map(|label| Tag { label, color: None })
+                .collect::<Vec<_>>();
+
+            thunes_cli::add_tags(db, tags.clone()).await?;
+
+            let schedule = thunes_cli::schedule::add_schedule(
+                db,
+                AddScheduleOptions {
+                    account: resolve_account(db, &args.account).await?,
+                    amount: args.amount,
+                    description: args.description,
+                    tags,
+                    start: to_datetime(
+                        args.start
+                            .unwrap_or_else(|| chrono::Utc::now().date_naive()),
+                    ),
+                    recurrence: Recurrence {
+                        frequency: args.frequency,
+                        interval: args.interval,
+                        nth_weekday: args.nth_weekday,
+                        until: args.until.map(to_datetime),
+                        count: args.count,
+                    },
+                },
+            )
+            .await?;
+
+            // Create occurrences that are already due.
+            thunes_cli::schedule::materialize(db, chrono::Utc::now()).await?;
+
+            if json {
+                return print_json(&schedule);
+            }
+
+            println!("{}", schedule.id);
+        }
+        ScheduleCommand::Delete { schedule } => {
+            thunes_cli::schedule::delete_schedule(db, parse_record_id("schedule", &schedule))
+                .await?;
+        }
+        ScheduleCommand::Skip { schedule, date } => {
+            thunes_cli::schedule::skip_occurrence(
+                db,
+                parse_record_id("schedule", &schedule),
+                to_datetime(date),
+            )
+            .await?;
+        }
+        ScheduleCommand::Postpone { schedule, date, to } => {
+            thunes_cli::schedule::postpone_occurrence(
+                db,
+                parse_record_id("schedule", &schedule),
+                to_datetime(date),
+                to_datetime(to),
+            )
+            .await?;
+        }
+        ScheduleCommand::Upcoming { until } => {
+            let until = until
+                .map(to_datetime)
+                .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(30));
+            let occurrences = thunes_cli::schedule::upcoming_occurrences(db, until).await?;
+
+            if json {
+                return print_json(&occurrences);
+            }
+
+            for occurrence in occurrences {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    occurrence.date.format("%Y-%m-%d"),
+                    occurrence.schedule,
+                    occurrence.account,
+                    occurrence.amount,
+                    occurrence.description
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_rule_enabled(db: &Surreal<Db>, rule: &str, enabled: bool) -> Result<()> {
+    let rule_id = parse_record_id("rule", rule);
+    let mut rule = thunes_cli::rule::list_rules(db)
+        .await?
+        .into_iter()
+        .find(|rule| rule.id == rule_id)
+        .ok_or_else(|| format!("rule '{rule_id}' not found"))?;
+
+    rule.enabled = enabled;
+    thunes_cli::rule::update_rule(db, rule).await?;
+
+    Ok(())
+}
+
+async fn checkpoint(db: &Surreal<Db>, json: bool, command: CheckpointCommand) -> Result<()> {
+    match command {
+        CheckpointCommand::List { account } => {
+            let account_id = resolve_account(db, &account).await?;
+            let checkpoints = thunes_cli::checkpoint::list_checkpoints(db, account_id).await?;
+
+            if json {
+                return print_json(&checkpoints);
+            }
+
+            for checkpoint in checkpoints {
+                println!(
+                    "{}\t{}\t{}\t{}{}",
+                    checkpoint.id,
+                    checkpoint.date.format("%Y-%m-%d"),
+                    checkpoint.balance,
+                    checkpoint.source,
+                    if checkpoint.reconciled_at.is_some() {
+                        "\treconciled"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+        CheckpointCommand::Add {
+            account,
+            balance,
+            date,
+        } => {
+            let checkpoint = thunes_cli::checkpoint::add_checkpoint(
+                db,
+                AddCheckpointOptions {
+                    account: resolve_account(db, &account).await?,
+                    date: to_datetime(date.unwrap_or_else(|| chrono::Utc::now().date_naive())),
+                    balance,
+                    source: "manual".to_string(),
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&checkpoint);
+            }
+
+            println!("{}", checkpoint.id);
+        }
+        CheckpointCommand::Delete { checkpoint } => {
+            thunes_cli::checkpoint::delete_checkpoint(
+                db,
+                parse_record_id("checkpoint", &checkpoint),
+            )
+            .await?;
+        }
+        CheckpointCommand::Reconcile {
+            checkpoint,
+            dry_run,
+        } => {
+            let checkpoint_id = parse_record_id("checkpoint", &checkpoint);
+            let reconciliation = if dry_run {
+                thunes_cli::checkpoint::get_reconciliation(db, checkpoint_id).await?
+            } else {
+                thunes_cli::checkpoint::reconcile(db, checkpoint_id).await?
+            };
+
+            if json {
+                return print_json(&reconciliation);
+            }
+
+            println!("checkpoint: {}", reconciliation.checkpoint.balance);
+            println!("balance:    {}", reconciliation.balance);
+            println!("difference: {}", reconciliation.difference);
+
+            for transaction in &reconciliation.transactions {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    transaction.id,
+                    transaction.inner.date.format("%Y-%m-%d"),
+                    transaction.inner.amount,
+                    transaction.inner.description
+                );
+            }
+
+            println!(
+                "{} transaction(s) {}",
+                reconciliation.transactions.len(),
+                if dry_run {
+                    "not reconciled yet"
+                } else {
+                    "reconciled"
+                }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn rule(db: &Surreal<Db>, json: bool, command: RuleCommand) -> Result<()> {
+    match command {
+        RuleCommand::List => {
+            let rules = thunes_cli::rule::list_rules(db).await?;
+
+            if json {
+                return print_json(&rules);
+            }
+
+            for rule in rules {
+                println!(
+                    "{}\t{}\t{}{}",
+                    rule.id,
+                    rule.priority,
+                    rule.name,
+                    if rule.enabled { "" } else { "\t(disabled)" }
+                );
+            }
+        }
+        RuleCommand::Add(args) => {
+            let mut accounts = vec![];
+            for account in &args.accounts {
+                accounts.push(resolve_account(db, account).await?);
+            }
+
+            let rule = thunes_cli::rule::add_rule(
+                db,
+                AddRuleOptions {
+                    name: args.name,
+                    enabled: !args.disabled,
+                    priority: args.priority,
+                    conditions: Conditions {
+                        description_contains: args.description_contains,
+                        description_matches: args.description_matches,
+                        amount_min: args.min,
+                        amount_max: args.max,
+                        accounts,
+                        date_start: args.period.start.map(to_datetime),
+                        date_end: args.period.end.map(to_datetime),
+                    },
+                    actions: Actions {
+                        add_tags: args.tags,
+                        rewrite_description: args.rewrite.map(|rewrite| Rewrite {
+                            pattern: rewrite[0].clone(),
+                            replacement: rewrite[1].clone(),
+                        }),
+                        set_payee: args.payee,
+                    },
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&rule);
+            }
+
+            println!("{}", rule.id);
+        }
+        RuleCommand::Enable { rule } => set_rule_enabled(db, &rule, true).await?,
+        RuleCommand::Disable { rule } => set_rule_enabled(db, &rule, false).await?,
+        RuleCommand::Delete { rule } => {
+            thunes_cli::rule::delete_rule(db, parse_record_id("rule", &rule)).await?;
+        }
+        RuleCommand::Run { account, dry_run } => {
+            let account = match account {
+                Some(account) => Some(resolve_account(db, &account).await?),
+                None => None,
+            };
+            let changes =
+                thunes_cli::rule::run_rules(db, RunRulesOptions { account, dry_run }).await?;
+
+            if json {
+                return print_json(&changes);
+            }
+
+            for change in &changes {
+                println!(
+                    "{}\t{}\t{}",
+                    change.date.format("%Y-%m-%d"),
+                    change.transaction,
+                    change.rules.join(", ")
+                );
+                if let Some(description) = &change.description {
+                    println!(
+                        "  description: {} -> {}",
+                        description.before, description.after
+                    );
+                }
+                if let Some(payee) = &change.payee {
+                    println!(
+                        "  payee: {} -> {}",
+                        payee.before.as_deref().unwrap_or("none"),
+                        payee.after.as_deref().unwrap_or("none")
+                    );
+                }
+                if !change.added_tags.is_empty() {
+                    println!("  tags: +{}", change.added_tags.join(", +"));
+                }
+            }
+
+            println!(
+                "{} transaction(s) {}",
+                changes.len(),
+                if dry_run {
+                    "would be changed"
+                } else {
+                    "changed"
+                }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn duplicate(db: &Surreal<Db>, json: bool, command: DuplicateCommand) -> Result<()> {
+    match command {
+        DuplicateCommand::List {
+            account,
+            days,
+            min_score,
+        } => {
+            let account = match account {
+                Some(account) => Some(resolve_account(db, &account).await?),
+                None => None,
+            };
+            let duplicates = thunes_cli::duplicate::find_duplicates(
+                db,
+                FindDuplicatesOptions {
+                    account,
+                    days: Some(days),
+                    min_score: Some(min_score),
+                },
+            )
+            .await?;
+
+            if json {
+                return print_json(&duplicates);
+            }
+
+            for duplicate in duplicates {
+                println!("score {:.2}", duplicate.score);
+                for (label, transaction) in [
+                    ("keep", &duplicate.keep),
+                    ("duplicate", &duplicate.duplicate),
+                ] {
+                    println!(
+                        "  {label}\t{}\t{}\t{}\t{}",
+                        transaction.id,
+                        transaction.inner.date.format("%Y-%m-%d"),
+                        transaction.inner.amount,
+                        transaction.inner.description
+                    );
+                }
+            }
+        }
+        DuplicateCommand::Merge { keep, duplicate } => {
+            let transaction = thunes_cli::duplicate::merge_duplicates(
+                db,
+                parse_record_id("transaction", &keep),
+                parse_record_id("transaction", &duplicate),
+            )
+            .await?;
+
+            if json {
+                return print_json(&transaction);
+            }
+
+            println!("{}", transaction.id);
+        }
+        DuplicateCommand::Dismiss { first, second } => {
+            thunes_cli::duplicate::dismiss_duplicate(
+                db,
+                parse_record_id("transaction", &first),
+                parse_record_id("transaction", &second),
+            )
+            .await?;
+        }
+        DuplicateCommand::Log => {
+            let decisions = thunes_cli::duplicate::list_duplicate_decisions(db).await?;
+
+            if json {
+                return print_json(&decisions);
+            }
+
+            for decision in decisions {
+                println!(
+                    "{}\t{:?}\t{}",
+                    decision.decided_at.format("%Y-%m-%d %H:%M"),
+                    decision.decision,
+                    decision
+                        .transactions
+                        .iter()
+                        .map(|transaction| transaction.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\t")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn transfer(db: &Surreal<Db>, json: bool, args: TransferArgs) -> Result<()> {
+    let tags = args
+        .tags
+        .into_iter()
+        .map(|label| Tag { label, color: None })
+        .collect::<Vec<_>>();
+
+    thunes_cli::add_tags(db, tags.clone()).await?;
+
+    let transfer = thunes_cli::add_transfer(
+        db,
+        AddTransferOptions {
+            from: resolve_account(db, &args.from).await?,
+            to: resolve_account(db, &args.to).await?,
+            amount: args.amount,
+            rate: args.rate,
+            description: args.description,
+            tags,
+            date: args.date.map(to_datetime),
+            fitid: None,
+        },
+    )
+    .await?;
+
+    if json {
+        return print_json(&transfer);
+    }
+
+    println!("{}\t{}", transfer.from.id, transfer.from.inner.amount);
+    println!("{}\t{}", transfer.to.id, transfer.to.inner.amount);
+
+    Ok(())
+}
+
+async fn balance(db: &Surreal<Db>, json: bool, args: BalanceArgs) -> Result<()> {
+    let Some(account) = args.account else {
+        let currencies = thunes_cli::balances_by_currency(db).await?;
+
+        if json {
+            return print_json(&currencies);
+        }
+
+        for currency in currencies {
+            println!(
+                "{} {}\t(income {}, expenses {})",
+                currency.total_balance,
+                currency.currency,
+                currency.total_income,
+                currency.total_expenses
+            );
+            for account in currency.accounts {
+                println!(
+                    "  {}\t{} {}\t(income {}, expenses {})",
+                    account.account.data.name,
+                    account.balance,
+                    currency.currency,
+                    account.income,
+                    account.expenses
+                );
+            }
+        }
+
+        return Ok(());
+    };
+
+    let account_id = resolve_account(db, &account).await?;
+    let currency = thunes_cli::get_currency(db, account_id.clone()).await?;
+    let balance = match thunes_cli::balance(
+        db,
+        account_id,
+        BalanceOptions {
+            period_start: to_surreal_datetime(args.period.start),
+            period_end: to_surreal_datetime(args.period.end),
+            tag: args.tag,
+            exclude_transfers: Some(args.exclude_transfers),
+            filter: args.filter,
+        },
+    )
+    .await
+    {
+        Ok(balance) => balance,
+        // No transactions matched the options.
+        Err(Error::RecordNotFound) => rust_decimal::Decimal::ZERO,
+        Err(error) => return Err(error.into()),
+    };
+
+    if json {
+        return print_json(&serde_json::json!({ "currency": currency, "balance": balance }));
+    }
+
+    println!("{balance} {currency}");
+
+    Ok(())
+}
+
+async fn backup(db: &Surreal<Db>, json: bool, command: BackupCommand) -> Result<()> {
+    match command {
+        BackupCommand::Export { output } => {
+            let path = match output {
+                Some(output) => output,
+                None => {
+                    let settings: Settings = db
+                        .select(("settings", "main"))
+                        .await?
+                        .ok_or("settings not found")?;
+                    let format = time::format_description::parse(
+                        "[year]-[month]-[day]T[hour]:[minute]:[second].surql",
+                    )
+                    .expect("format is not valid");
+
+                    std::fs::create_dir_all(&settings.backups_path)?;
+                    settings
+                        .backups_path
+                        .join(time::OffsetDateTime::now_utc().format(&format)?)
+                }
+            };
+
+            db.export(&path).await?;
+            println!("{}", path.display());
+        }
+        BackupCommand::Import { path } => db.import(path).await?,
+        BackupCommand::ExportArchive { output, csv } => {
+            let format = if csv {
+                ArchiveFormat::Csv
+            } else {
+                ArchiveFormat::Json
+            };
+            thunes_cli::archive::export_file(db, format, &output).await?;
+        }
+        BackupCommand::ImportArchive { path } => {
+            let import = thunes_cli::archive::import_file(db, &path).await?;
+
+            if json {
+                return print_json(&import);
+            }
+
+            println!(
+                "{} account(s), {} transaction(s) and {} tag(s) imported",
+                import.accounts, import.transactions, import.tags
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn print_import_report(report: &ImportReport) {
+    println!(
+        "{}: {} transaction(s) imported{}",
+        report.account_name,
+        report.transactions_imported,
+        if report.account_created {
+            " in a new account"
+        } else {
+            ""
+        }
+    );
+
+    if !report.tags_created.is_empty() {
+        println!("  tags created: {}", report.tags_created.join(", "));
+    }
+
+    for skipped in &report.skipped {
+        println!("  skipped {skipped}");
+    }
+}
+
+async fn import(db: &Surreal<Db>, json: bool, command: ImportCommand) -> Result<()> {
+    let reports = match command {
+        ImportCommand::Legacy { paths } => {
+            let mut reports = vec![];
+            for path in paths {
+                reports.push(thunes_cli::import::legacy::import_file(db, &path).await?);
+            }
+            reports
+        }
+        ImportCommand::Csv {
+            path,
+            account,
+            profile,
+            dry_run,
+        } => {
+            let account_id = resolve_account(db, &account).await?;
+            let profile = thunes_cli::import::csv::get_profile(db, &profile).await?;
+            let import =
+                thunes_cli::import::csv::import_file(db, account_id, &path, &profile, dry_run)
+                    .await?;
+
+            if json {
+                return print_json(&import);
+            }
+
+            if dry_run {
+                for transaction in &import.transactions {
+                    println!(
+                        "{}\t{}\t{}",
+                        transaction
+                            .date
+                            .map(|date| date.format("%Y-%m-%d").to_string())
+                            .unwrap_or_default(),
+                        transaction.amount,
+                        transaction.description
+                    );
+                }
+                import
+                    .report
+                    .skipped
+                    .iter()
+                    .for_each(|skipped| println!("skipped {skipped}"));
+
+                return Ok(());
+            }
+
+            vec![import.report]
+        }
+        ImportCommand::Statement {
+            path,
+            account,
+            format,
+        } => {
+            let account_id = resolve_account(db, &account).await?;
+            let import = match format.as_deref() {
+                None => thunes_cli::import::import_statement(db, account_id, &path).await?,
+                Some("ofx") => thunes_cli::import::ofx::import_file(db, account_id, &path).await?,
+                Some("camt") => {
+                    thunes_cli::import::camt::import_file(db, account_id, &path).await?
+                }
+                Some("mt940") => {
+                    thunes_cli::import::mt940::import_file(db, account_id, &path).await?
+                }
+                Some("qif") => thunes_cli::import::qif::import_file(db, account_id, &path).await?,
+                Some(format) => return Err(format!("unknown statement format: {format}").into()),
+            };
+
+            if json {
+                return print_json(&import);
+            }
+
+            for checkpoint in &import.checkpoints {
+                println!(
+                    "{}: balance of {} at {} recorded",
+                    import.report.account_name,
+                    checkpoint.balance,
+                    checkpoint.date.format("%Y-%m-%d")
+                );
+            }
+
+            vec![import.report]
+        }
+        ImportCommand::Journal { path } => {
+            let import = thunes_cli::import::journal::import_file(db, &path).await?;
+
+            if json {
+                return print_json(&import);
+            }
+
+            import.accounts.iter().for_each(print_import_report);
+            println!("{} transfer(s) imported", import.transfers_imported);
+            println!("{} exchange rate(s) imported", import.rates_imported);
+            println!("{} checkpoint(s) recorded", import.checkpoints.len());
+            if !import.tags_created.is_empty() {
+                println!("tags created: {}", import.tags_created.join(", "));
+            }
+            import
+                .skipped
+                .iter()
+                .for_each(|skipped| println!("skipped {skipped}"));
+
+            return Ok(());
+        }
+        ImportCommand::Profile(command) => return profile(db, json, command).await,
+    };
+
+    if json {
+        return print_json(&reports);
+    }
+
+    reports.iter().for_each(print_import_report);
+
+    Ok(())
+}
+
+async fn profile(db: &Surreal<Db>, json: bool, command: ProfileCommand) -> Result<()> {
+    match command {
+        ProfileCommand::List => {
+            let profiles = thunes_cli::import::csv::list_profiles(db).await?;
+
+            if json {
+                return print_json(&profiles);
+            }
+
+            for profile in profiles {
+                println!("{}", profile.name);
+            }
+        }
+        ProfileCommand::Save { path } => {
+            let profile =
+                serde_json::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))?;
+            let profile = thunes_cli::import::csv::save_profile(db, profile).await?;
+
+            if json {
+                return print_json(&profile);
+            }
+
+            println!("{}", profile.name);
+        }
+        ProfileCommand::Delete { name } => {
+            thunes_cli::import::csv::delete_profile(db, &name).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn export(db: &Surreal<Db>, command: ExportCommand) -> Result<()> {
+    match command {
+        ExportCommand::Qif { account, output } => {
+            let account_id = resolve_account(db, &account).await?;
+            let content = thunes_cli::export::qif::export(db, account_id).await?;
+
+            match output {
+                Some(path) => std::fs::write(path, content)?,
+                None => print!("{content}"),
+            }
+        }
+        ExportCommand::Ledger(args) => journal(db, JournalFormat::Ledger, args).await?,
+        ExportCommand::Hledger(args) => journal(db, JournalFormat::Hledger, args).await?,
+        ExportCommand::Beancount(args) => journal(db, JournalFormat::Beancount, args).await?,
+    }
+
+    Ok(())
+}
+
+async fn journal(db: &Surreal<Db>, format: JournalFormat, args: JournalArgs) -> Result<()> {
+    let options = JournalOptions {
+        tags_as_accounts: args.tags_as_accounts,
+        accounts: args.mappings.into_iter().collect(),
+        start: args.start.map(to_datetime),
+    };
+    let content = thunes_cli::export::export_journal(db, format, &options).await?;
+
+    match args.output {
+        Some(path) => std::fs::write(path, content)?,
+        None => print!("{content}"),
+    }
+
+    Ok(())
+}
+
+async fn script(db: &Surreal<Db>, json: bool, path: PathBuf) -> Result<()> {
+    let engine = thunes_cli::script::build_engine(&path);
+    let ast = engine.compile_file(path)?;
+    let mut results = vec![];
+
+    for account in thunes_cli::list_account(db).await? {
+        let function = format!("on_{}", account.name);
+
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == function && f.params.len() == 1)
+        {
+            continue;
+        }
+
+        let transactions: rhai::Array =
+            thunes_cli::get_transactions(db, account.id, GetTransactionOptions::default())
+                .await?
+                .into_iter()
+                .map(|transaction| rhai::Dynamic::from(TransactionRhai::from(transaction.inner)))
+                .collect();
+
+        let balance: rhai::Dynamic =
+            engine.call_fn(&mut rhai::Scope::new(), &ast, &function, (transactions,))?;
+        let balance = ScriptAccountBalance::try_from(balance)?;
+
+        results.push(serde_json::json!({
+            "account": account.name,
+            "currency": balance.currency,
+            "amount": balance.amount,
+        }));
+
+        if !json {
+            println!("{}: {} {}", account.name, balance.amount, balance.currency);
+        }
+    }
+
+    if json {
+        return print_json(&results);
+    }
+
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let db = open(cli.store).await?;
+
+    match cli.command {
+        Command::Account(command) => account(&db, cli.json, command).await,
+        Command::Transaction(command) => transaction(&db, cli.json, command).await,
+        Command::Tag(command) => tag(&db, cli.json, command).await,
+        Command::Trash(command) => trash(&db, cli.json, command).await,
+        Command::Budget(command) => budget(&db, cli.json, command).await,
+        Command::Schedule(command) => schedule(&db, cli.json, command).await,
+        Command::Checkpoint(command) => checkpoint(&db, cli.json, command).await,
+        Command::Rule(command) => rule(&db, cli.json, command).await,
+        Command::Duplicate(command) => duplicate(&db, cli.json, command).await,
+        Command::Transfer(args) => transfer(&db, cli.json, args).await,
+        Command::Balance(args) => balance(&db, cli.json, args).await,
+        Command::NetWorth {
+            currency,
+            date,
+            at_transaction_date,
+            filter,
+        } => net_worth(&db, cli.json, currency, date, at_transaction_date, filter).await,
+        Command::Report(command) => report(&db, cli.json, command).await,
+        Command::Rate(command) => rate(&db, cli.json, command).await,
+        Command::Backup(command) => backup(&db, cli.json, command).await,
+        Command::Import(command) => import(&db, cli.json, command).await,
+        Command::Export(command) => export(&db, command).await,
+        Command::Script { path } => script(&db, cli.json, path).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Schema definitions and data migrations, applied each time the database is opened.
+//!
+//! Every step must be idempotent.
+
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::Error;
+
+const SCHEMA: &str = r#"
+    -- Amounts are stored as decimals, even when they are sent as strings or floats.
+    DEFINE FIELD OVERWRITE amount ON TABLE transaction VALUE <decimal> $value;
+    -- Dates are sent as strings by serde, but must be datetimes to be compared with periods.
+    DEFINE FIELD OVERWRITE date ON TABLE transaction VALUE <datetime> $value;
+    DEFINE FIELD OVERWRITE value_date ON TABLE transaction VALUE IF $value THEN <datetime> $value END;
+    DEFINE FIELD OVERWRITE splits.*.amount ON TABLE transaction VALUE <decimal> $value;
+    DEFINE FIELD OVERWRITE amount ON TABLE schedule VALUE <decimal> $value;
+    DEFINE FIELD OVERWRITE rate ON TABLE exchange_rate VALUE <decimal> $value;
+    DEFINE FIELD OVERWRITE balance ON TABLE checkpoint VALUE <decimal> $value;
+"#;
+
+/// Apply the schema and migrate data written by previous versions.
+pub async fn run(db: &Surreal<Db>) -> Result<(), Error> {
+    db.query(SCHEMA).await?.check()?;
+
+    float_amounts_to_decimal(db).await?;
+    string_dates_to_datetime(db).await
+}
+
+#[derive(serde::Deserialize)]
+struct FloatAmount {
+    id: RecordId,
+    amount: f64,
+    currency: Option<String>,
+}
+
+/// Amounts used to be stored as floats, convert them to decimals rounded to the minor
+/// units of the account currency.
+async fn float_amounts_to_decimal(db: &Surreal<Db>) -> Result<(), Error> {
+    let transactions: Vec<FloatAmount> = db
+        .query(
+            "SELECT id, amount, account.currency AS currency FROM transaction WHERE type::is::float(amount)",
+        )
+        .await?
+        .take(0)?;
+
+    for transaction in transactions {
+        let amount = Decimal::from_f64(transaction.amount).unwrap_or_default();
+        let amount = crate::money::round(amount, &transaction.currency.unwrap_or_default());
+
+        // Note: SurrealDB skips writes of documents that did not change, and `0.1f` is
+        //       equal to `0.1dec`, so a temporary field is set to force the update.
+        db.query("UPDATE $id SET amount = $amount, migrating = true; UPDATE $id UNSET migrating;")
+            .bind(("id", transaction.id))
+            .bind(("amount", amount))
+            .await?
+            .check()?;
+    }
+
+    Ok(())
+}
+
+/// Dates used to be stored as strings, which are never equal nor ordered with datetimes.
+async fn string_dates_to_datetime(db: &Surreal<Db>) -> Result<(), Error> {
+    db.query("UPDATE transaction SET date = <datetime> date WHERE type::is::string(date)")
+        .await?
+        .check()?;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Number of digits after the decimal separator used by a currency.
+///
+/// Follows ISO 4217 for fiat currencies, unknown currencies default to two digits.
+pub fn minor_units(currency: &str) -> u32 {
+    match currency.to_uppercase().as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        "ETH" => 18,
+        "BTC" | "LTC" | "BCH" => 8,
+        _ => 2,
+    }
+}
+
+/// Round an amount to the minor units of a currency, and use them as its scale
+/// (`12.5` EUR becomes `12.50`).
+pub fn round(amount: Decimal, currency: &str) -> Decimal {
+    let minor_units = minor_units(currency);
+    let mut amount =
+        amount.round_dp_with_strategy(minor_units, RoundingStrategy::MidpointAwayFromZero);
+
+    amount.rescale(minor_units);
+    amount
+}
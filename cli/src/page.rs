@@ -0,0 +1,261 @@
+//! Sorting and pagination of transactions.
+//!
+//! Pages are fetched with a cursor holding the sort keys and the id of the last
+//! transaction of the previous page, instead of an offset, so that adding or removing
+//! transactions does not shift the following pages.
+
+use rust_decimal::Decimal;
+
+use crate::transaction::{Status, TransactionWithId};
+
+/// Column of the transactions to sort by.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    Date,
+    /// Ignoring case.
+    Description,
+    Amount,
+    /// Labels of the tags, in order.
+    Tags,
+    /// Ignoring case, transactions without payee first.
+    Payee,
+    /// Uncleared first, then cleared and reconciled.
+    Status,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// A column to sort by, in the format of the items of the sort model of the
+/// transaction grid, `transaction_grid_sort_model`.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Sort {
+    pub field: SortField,
+    /// The column is ignored without direction.
+    #[ts(optional)]
+    #[serde(default)]
+    pub sort: Option<SortDirection>,
+}
+
+/// A page of the transactions of an account.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Serialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<TransactionWithId>,
+    /// Number of transactions matching the options, on all pages.
+    pub total: usize,
+    /// Cursor of the next page, `None` on the last page.
+    pub next_cursor: Option<String>,
+    /// Balance of the account after each of the transactions, in the same order, when
+    /// the running balance is requested. Empty otherwise.
+    #[ts(type = "Array<string>")]
+    pub running_balances: Vec<Decimal>,
+}
+
+impl SortField {
+    /// Sort key of the transactions in SurrealQL.
+    fn expression(self) -> &'static str {
+        match self {
+            SortField::Date => "date",
+            SortField::Description => "string::lowercase(description)",
+            SortField::Amount => "amount",
+            SortField::Tags => "array::join(tags.label, ' ')",
+            SortField::Payee => "string::lowercase(payee ?? '')",
+            SortField::Status => {
+                "IF status = 'reconciled' THEN 2 ELSE IF status = 'cleared' THEN 1 ELSE 0 END"
+            }
+        }
+    }
+
+    /// Sort key of a transaction, as computed by [`Self::expression`].
+    fn key(self, transaction: &TransactionWithId) -> String {
+        let inner = &transaction.inner;
+
+        match self {
+            SortField::Date => inner
+                .date
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            SortField::Description => inner.description.to_lowercase(),
+            SortField::Amount => inner.amount.to_string(),
+            SortField::Tags => inner
+                .tags
+                .iter()
+                .map(|tag| tag.label.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            SortField::Payee => inner.payee.as_deref().unwrap_or_default().to_lowercase(),
+            SortField::Status => match inner.status {
+                Status::Uncleared => "0",
+                Status::Cleared => "1",
+                Status::Reconciled => "2",
+            }
+            .to_string(),
+        }
+    }
+
+    /// Cast of a key bound as a text parameter back to the type of the expression.
+    fn cast(self) -> &'static str {
+        match self {
+            SortField::Date => "<datetime>",
+            SortField::Amount => "<decimal>",
+            SortField::Status => "<int>",
+            SortField::Description | SortField::Tags | SortField::Payee => "",
+        }
+    }
+}
+
+/// Position after a transaction, in the order of a sort.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Cursor {
+    sort: Vec<(SortField, SortDirection)>,
+    keys: Vec<String>,
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        serde_json::to_vec(self)
+            .unwrap_or_default()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(cursor.get(index..index + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+/// Order of a query on transactions, and the position to start from.
+pub(crate) struct Sorting {
+    sort: Vec<(SortField, SortDirection)>,
+    cursor: Option<Cursor>,
+}
+
+impl Sorting {
+    /// Transactions are sorted by date when no column is given, and by id last so
+    /// that the order is always the same.
+    pub(crate) fn new(sort: &[Sort], cursor: Option<&str>) -> Result<Self, String> {
+        let mut columns: Vec<(SortField, SortDirection)> = vec![];
+        for Sort { field, sort } in sort {
+            if let Some(direction) = sort {
+                if !columns.iter().any(|(column, _)| column == field) {
+                    columns.push((*field, *direction));
+                }
+            }
+        }
+        if columns.is_empty() {
+            columns.push((SortField::Date, SortDirection::Asc));
+        }
+
+        let cursor = match cursor {
+            Some(cursor) => {
+                let cursor = Cursor::decode(cursor).ok_or("invalid cursor".to_string())?;
+                if cursor.sort != columns || cursor.keys.len() != columns.len() {
+                    return Err("the cursor was made for another sort".to_string());
+                }
+                Some(cursor)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            sort: columns,
+            cursor,
+        })
+    }
+
+    /// Sort keys to select along with the transactions.
+    pub(crate) fn projection(&self) -> String {
+        self.sort
+            .iter()
+            .enumerate()
+            .map(|(index, (field, _))| format!(", {} AS sort_key_{index}", field.expression()))
+            .collect()
+    }
+
+    /// Condition selecting the transactions after the cursor, with its parameters.
+    pub(crate) fn push_condition(&self, query: &mut String) -> Vec<(String, String)> {
+        let Some(cursor) = &self.cursor else {
+            return vec![];
+        };
+
+        let mut params = vec![];
+        let mut equal: Vec<String> = vec![];
+        let mut after: Vec<String> = vec![];
+        for (index, ((field, direction), key)) in self.sort.iter().zip(&cursor.keys).enumerate() {
+            let param = format!("{}$cursor_{index}", field.cast());
+            let operator = match direction {
+                SortDirection::Asc => ">",
+                SortDirection::Desc => "<",
+            };
+
+            after.push(
+                equal
+                    .iter()
+                    .cloned()
+                    .chain([format!("{} {operator} {param}", field.expression())])
+                    .collect::<Vec<_>>()
+                    .join(" AND "),
+            );
+            equal.push(format!("{} = {param}", field.expression()));
+            params.push((format!("cursor_{index}"), key.clone()));
+        }
+        after.push(
+            equal
+                .into_iter()
+                .chain(["id > <record>$cursor_id".to_string()])
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        );
+        params.push(("cursor_id".to_string(), cursor.id.clone()));
+
+        query.push_str(" AND ((");
+        query.push_str(&after.join(") OR ("));
+        query.push_str("))");
+
+        params
+    }
+
+    pub(crate) fn order(&self) -> String {
+        let mut order: Vec<String> = self
+            .sort
+            .iter()
+            .enumerate()
+            .map(|(index, (_, direction))| match direction {
+                SortDirection::Asc => format!("sort_key_{index} ASC"),
+                SortDirection::Desc => format!("sort_key_{index} DESC"),
+            })
+            .collect();
+        order.push("id ASC".to_string());
+
+        format!(" ORDER BY {}", order.join(", "))
+    }
+
+    /// Cursor of the page following a transaction.
+    pub(crate) fn cursor(&self, last: &TransactionWithId) -> String {
+        Cursor {
+            sort: self.sort.clone(),
+            keys: self.sort.iter().map(|(field, _)| field.key(last)).collect(),
+            id: last.id.to_string(),
+        }
+        .encode()
+    }
+}
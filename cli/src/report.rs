@@ -0,0 +1,837 @@
+//! Reports on the transactions of the accounts over periods of time.
+//!
+//! Transactions are summed by SurrealDB, grouped by day, month or year, then the
+//! sums are gathered into the periods of the report. Weeks start on monday.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    filter::{Filter, FilterError},
+    schedule::Frequency,
+    Error,
+};
+
+/// Maximum number of periods of a report.
+pub const MAX_PERIODS: usize = 10_000;
+
+/// First instant of a period, and first instant of the next one.
+pub(crate) type Period = (DateTime<Utc>, DateTime<Utc>);
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct BalanceHistoryOptions {
+    /// Account to get the balance of, all accounts grouped by currency if omitted.
+    #[ts(type = "{ tb: string, id: { String: string }}", optional)]
+    #[serde(default)]
+    pub account: Option<RecordId>,
+    /// Length of the periods.
+    pub period: Frequency,
+    /// Date in the first period.
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    /// Date in the last period, defaults to now.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    /// Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+/// Balance at the end of a period.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BalancePoint {
+    /// Included.
+    #[ts(as = "String")]
+    pub period_start: DateTime<Utc>,
+    /// Excluded.
+    #[ts(as = "String")]
+    pub period_end: DateTime<Utc>,
+    /// Sum of the transactions dated before the end of the period.
+    #[ts(type = "string")]
+    pub balance: Decimal,
+}
+
+/// Balances of an account, or of all the accounts of a currency, period by period.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BalanceHistory {
+    pub currency: String,
+    pub points: Vec<BalancePoint>,
+}
+
+/// First day of the period containing `date`.
+fn period_start(date: NaiveDate, frequency: Frequency) -> NaiveDate {
+    match frequency {
+        Frequency::Daily => date,
+        Frequency::Weekly => date - Days::new(date.weekday().num_days_from_monday().into()),
+        Frequency::Monthly => date.with_day(1).unwrap_or(date),
+        Frequency::Yearly => date.with_ordinal(1).unwrap_or(date),
+    }
+}
+
+/// First day of the period following the one starting on `start`.
+fn next_period_start(start: NaiveDate, frequency: Frequency) -> Option<NaiveDate> {
+    match frequency {
+        Frequency::Daily => start.checked_add_days(Days::new(1)),
+        Frequency::Weekly => start.checked_add_days(Days::new(7)),
+        Frequency::Monthly => start.checked_add_months(Months::new(1)),
+        Frequency::Yearly => start.checked_add_months(Months::new(12)),
+    }
+}
+
+/// Periods from the one containing `start` to the one containing `end`.
+pub(crate) fn periods(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    frequency: Frequency,
+) -> Result<Vec<Period>, String> {
+    if end < start {
+        return Err("the end of the report is before its start".to_string());
+    }
+
+    let midnight = |date: NaiveDate| date.and_time(chrono::NaiveTime::MIN).and_utc();
+    let last = end.date_naive();
+    let mut periods = vec![];
+    let mut current = period_start(start.date_naive(), frequency);
+
+    while current <= last {
+        if periods.len() == MAX_PERIODS {
+            return Err(format!(
+                "the report has more than {MAX_PERIODS} periods, use longer periods"
+            ));
+        }
+
+        let next = next_period_start(current, frequency).ok_or("the report ends too late")?;
+        periods.push((midnight(current), midnight(next)));
+        current = next;
+    }
+
+    Ok(periods)
+}
+
+/// Unit of time to group transactions by in SurrealQL, periods are made of whole units.
+fn group_unit(frequency: Frequency) -> &'static str {
+    match frequency {
+        Frequency::Daily | Frequency::Weekly => "day",
+        Frequency::Monthly => "month",
+        Frequency::Yearly => "year",
+    }
+}
+
+/// Get the balance at the end of each period between two dates, for an account or for
+/// the accounts of each currency.
+pub async fn balance_history(
+    db: &Surreal<Db>,
+    options: BalanceHistoryOptions,
+) -> Result<Vec<BalanceHistory>, Error> {
+    #[derive(serde::Deserialize)]
+    struct Sum {
+        currency: String,
+        #[serde(default)]
+        period: Option<DateTime<Utc>>,
+        amount: Decimal,
+    }
+
+    let end = options.end.unwrap_or_else(Utc::now);
+    let periods = periods(options.start, end, options.period).map_err(Error::Invalid)?;
+    let (Some((first, _)), Some((_, last))) = (periods.first(), periods.last()) else {
+        return Ok(vec![]);
+    };
+
+    let currencies: BTreeSet<String> = match &options.account {
+        Some(account) => [crate::get_currency(db, account.clone()).await?].into(),
+        None => {
+            let currencies: Vec<String> = db
+                .query("SELECT VALUE currency FROM account")
+                .await?
+                .take(0)?;
+            currencies.into_iter().collect()
+        }
+    };
+
+    let mut conditions = String::new();
+    if options.account.is_some() {
+        conditions.push_str(" AND account = $account");
+    }
+    let filter = crate::filter::parse(options.filter.as_deref())?;
+    let params = Filter::push_condition(filter.as_ref(), &mut conditions);
+
+    let query = format!(
+        r#"
+    SELECT account.currency AS currency, math::sum(amount) AS amount FROM transaction
+        WHERE date < $start{conditions} GROUP BY currency;
+    SELECT account.currency AS currency, time::group(date, $unit) AS period, math::sum(amount) AS amount FROM transaction
+        WHERE date >= $start AND date < $end{conditions} GROUP BY currency, period;"#
+    );
+
+    let mut response = crate::filter::bind(db.query(query), params)
+        .bind(("account", options.account))
+        .bind(("start", surrealdb::Datetime::from(*first)))
+        .bind(("end", surrealdb::Datetime::from(*last)))
+        .bind(("unit", group_unit(options.period)))
+        .await?;
+    let openings: Vec<Sum> = response.take(0)?;
+    let sums: Vec<Sum> = response.take(1)?;
+
+    let mut balances: HashMap<String, Decimal> = openings
+        .into_iter()
+        .map(|sum| (sum.currency, sum.amount))
+        .collect();
+    let mut changes: HashMap<(String, NaiveDate), Decimal> = HashMap::new();
+    for sum in sums {
+        let Some(period) = sum.period else {
+            continue;
+        };
+        *changes
+            .entry((
+                sum.currency,
+                period_start(period.date_naive(), options.period),
+            ))
+            .or_default() += sum.amount;
+    }
+
+    Ok(currencies
+        .into_iter()
+        .map(|currency| {
+            let mut balance = balances.remove(&currency).unwrap_or_default();
+            let points = periods
+                .iter()
+                .map(|(start, end)| {
+                    balance += changes
+                        .get(&(currency.clone(), start.date_naive()))
+                        .copied()
+                        .unwrap_or_default();
+
+                    BalancePoint {
+                        period_start: *start,
+                        period_end: *end,
+                        balance,
+                    }
+                })
+                .collect();
+
+            BalanceHistory { currency, points }
+        })
+        .collect())
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct ReportOptions {
+    /// Accounts to report on, all accounts if empty.
+    #[ts(type = "Array<{ tb: string, id: { String: string }}>")]
+    #[serde(default)]
+    pub accounts: Vec<RecordId>,
+    /// Included.
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    /// Excluded, defaults to now.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    /// Only count transactions matching this filter, see [`crate::filter`] for the syntax.
+    #[ts(optional)]
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+impl ReportOptions {
+    fn range(&self) -> Result<Period, String> {
+        let end = self.end.unwrap_or_else(Utc::now);
+        if end < self.start {
+            return Err("the end of the report is before its start".to_string());
+        }
+
+        Ok((self.start, end))
+    }
+
+    /// Conditions on the accounts and the filter of the report, with their parameters.
+    /// Transfers between accounts are neither incomes nor expenses, they are excluded.
+    fn conditions(&self) -> Result<(String, Vec<(String, String)>), FilterError> {
+        let mut conditions = "date >= $start AND date < $end AND transfer = NONE".to_string();
+        if !self.accounts.is_empty() {
+            conditions.push_str(" AND account IN $accounts");
+        }
+        let filter = crate::filter::parse(self.filter.as_deref())?;
+        let params = Filter::push_condition(filter.as_ref(), &mut conditions);
+
+        Ok((conditions, params))
+    }
+
+    /// Currencies of the accounts of the report.
+    async fn currencies(&self, db: &Surreal<Db>) -> Result<BTreeSet<String>, Error> {
+        let currencies: Vec<String> = if self.accounts.is_empty() {
+            db.query("SELECT VALUE currency FROM account")
+                .await?
+                .take(0)?
+        } else {
+            db.query("SELECT VALUE currency FROM account WHERE id IN $accounts")
+                .bind(("accounts", self.accounts.clone()))
+                .await?
+                .take(0)?
+        };
+
+        Ok(currencies.into_iter().collect())
+    }
+}
+
+/// Incomes and expenses of a period.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct CashFlowPeriod {
+    /// Included.
+    #[ts(as = "String")]
+    pub period_start: DateTime<Utc>,
+    /// Excluded.
+    #[ts(as = "String")]
+    pub period_end: DateTime<Utc>,
+    /// Sum of incoming transactions.
+    #[ts(type = "string")]
+    pub income: Decimal,
+    /// Sum of outgoing transactions, negative.
+    #[ts(type = "string")]
+    pub expenses: Decimal,
+    /// Incomes plus expenses.
+    #[ts(type = "string")]
+    pub net: Decimal,
+}
+
+/// Incomes and expenses of the accounts of a currency, period by period, transfers
+/// between accounts excluded.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CashFlow {
+    pub currency: String,
+    pub periods: Vec<CashFlowPeriod>,
+    /// Sums of all the periods.
+    pub total: CashFlowPeriod,
+}
+
+/// Get the incomes, expenses and net of each period between two dates, for the
+/// accounts of each currency. The first and last periods are cut to the dates.
+pub async fn cash_flow(
+    db: &Surreal<Db>,
+    options: ReportOptions,
+    period: Frequency,
+) -> Result<Vec<CashFlow>, Error> {
+    #[derive(serde::Deserialize)]
+    struct Sum {
+        currency: String,
+        period: DateTime<Utc>,
+        income: Decimal,
+        expenses: Decimal,
+    }
+
+    let (start, end) = options.range().map_err(Error::Invalid)?;
+    let periods: Vec<Period> = periods(start, end, period)
+        .map_err(Error::Invalid)?
+        .into_iter()
+        .map(|(period_start, period_end)| (period_start.max(start), period_end.min(end)))
+        .filter(|(period_start, period_end)| period_start < period_end)
+        .collect();
+
+    let currencies = options.currencies(db).await?;
+    let (conditions, params) = options.conditions()?;
+    let query = format!(
+        r#"
+    SELECT
+        account.currency AS currency,
+        time::group(date, $unit) AS period,
+        math::sum(IF amount > 0 THEN amount ELSE 0dec END) AS income,
+        math::sum(IF amount < 0 THEN amount ELSE 0dec END) AS expenses
+    FROM transaction WHERE {conditions} GROUP BY currency, period"#
+    );
+
+    let sums: Vec<Sum> = crate::filter::bind(db.query(query), params)
+        .bind(("start", surrealdb::Datetime::from(start)))
+        .bind(("end", surrealdb::Datetime::from(end)))
+        .bind(("accounts", options.accounts))
+        .bind(("unit", group_unit(period)))
+        .await?
+        .take(0)?;
+
+    let mut changes: HashMap<(String, NaiveDate), (Decimal, Decimal)> = HashMap::new();
+    for sum in sums {
+        let (income, expenses) = changes
+            .entry((sum.currency, period_start(sum.period.date_naive(), period)))
+            .or_default();
+        *income += sum.income;
+        *expenses += sum.expenses;
+    }
+
+    Ok(currencies
+        .into_iter()
+        .map(|currency| {
+            let mut total = CashFlowPeriod {
+                period_start: start,
+                period_end: end,
+                ..Default::default()
+            };
+            let periods = periods
+                .iter()
+                .map(|(period_start_date, period_end)| {
+                    let key = (
+                        currency.clone(),
+                        period_start(period_start_date.date_naive(), period),
+                    );
+                    let (income, expenses) = changes.get(&key).copied().unwrap_or_default();
+                    total.income += income;
+                    total.expenses += expenses;
+
+                    CashFlowPeriod {
+                        period_start: *period_start_date,
+                        period_end: *period_end,
+                        income,
+                        expenses,
+                        net: income + expenses,
+                    }
+                })
+                .collect();
+            total.net = total.income + total.expenses;
+
+            CashFlow {
+                currency,
+                periods,
+                total,
+            }
+        })
+        .collect())
+}
+
+/// Spendings on a tag.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TagSpending {
+    /// `None` for the spendings without tags.
+    pub tag: Option<String>,
+    /// Sum of the outgoing amounts, positive.
+    #[ts(type = "string")]
+    pub spent: Decimal,
+    /// Share of the total spendings, in percent. Transactions with several tags are
+    /// counted for each of them, so shares can add up to more than 100.
+    #[ts(type = "string")]
+    pub percentage: Decimal,
+}
+
+/// Spendings of the accounts of a currency, tag by tag, transfers between accounts
+/// excluded.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SpendingByTag {
+    pub currency: String,
+    /// Sum of the outgoing amounts, positive.
+    #[ts(type = "string")]
+    pub total: Decimal,
+    /// By decreasing spendings.
+    pub tags: Vec<TagSpending>,
+}
+
+/// Get the spendings between two dates by tag, for the accounts of each currency.
+/// Lines of split transactions are counted with their own tags.
+pub async fn spending_by_tag(
+    db: &Surreal<Db>,
+    options: ReportOptions,
+) -> Result<Vec<SpendingByTag>, Error> {
+    #[derive(serde::Deserialize)]
+    struct Sum {
+        currency: String,
+        tags: Vec<String>,
+        amount: Decimal,
+    }
+
+    let (start, end) = options.range().map_err(Error::Invalid)?;
+    let currencies = options.currencies(db).await?;
+    let (conditions, params) = options.conditions()?;
+    let query = format!(
+        r#"
+    SELECT account.currency AS currency, tags.label AS tags, math::sum(amount) AS amount FROM transaction
+        WHERE {conditions} AND !splits AND amount < 0 GROUP BY currency, tags;
+    SELECT account.currency AS currency, line.tags.label AS tags, math::sum(line.amount) AS amount FROM (
+        SELECT account, splits AS line FROM transaction WHERE {conditions} AND splits SPLIT line
+    ) WHERE line.amount < 0 GROUP BY currency, tags;"#
+    );
+
+    let mut response = crate::filter::bind(db.query(query), params)
+        .bind(("start", surrealdb::Datetime::from(start)))
+        .bind(("end", surrealdb::Datetime::from(end)))
+        .bind(("accounts", options.accounts))
+        .await?;
+    let mut sums: Vec<Sum> = response.take(0)?;
+    sums.extend(response.take::<Vec<Sum>>(1)?);
+
+    let mut spendings: HashMap<String, (Decimal, HashMap<Option<String>, Decimal>)> =
+        HashMap::new();
+    for sum in sums {
+        let (total, tags) = spendings.entry(sum.currency).or_default();
+        let spent = -sum.amount;
+        *total += spent;
+
+        let labels: BTreeSet<String> = sum.tags.into_iter().collect();
+        if labels.is_empty() {
+            *tags.entry(None).or_default() += spent;
+        }
+        for label in labels {
+            *tags.entry(Some(label)).or_default() += spent;
+        }
+    }
+
+    Ok(currencies
+        .into_iter()
+        .map(|currency| {
+            let (total, tags) = spendings.remove(&currency).unwrap_or_default();
+            let mut tags: Vec<TagSpending> = tags
+                .into_iter()
+                .map(|(tag, spent)| TagSpending {
+                    tag,
+                    spent,
+                    percentage: percentage(spent, total),
+                })
+                .collect();
+            tags.sort_by(|a, b| b.spent.cmp(&a.spent).then_with(|| a.tag.cmp(&b.tag)));
+
+            SpendingByTag {
+                currency,
+                total,
+                tags,
+            }
+        })
+        .collect())
+}
+
+/// Share of `part` in `total` in percent, rounded to two decimals.
+fn percentage(part: Decimal, total: Decimal) -> Decimal {
+    if total.is_zero() {
+        return Decimal::ZERO;
+    }
+
+    (part * Decimal::ONE_HUNDRED / total).round_dp(2)
+}
+
+/// Range of dates to compare a report with.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Baseline {
+    /// The range of the same length ending at the start of the report. Ranges of whole
+    /// months are moved by their number of months, so a quarter is compared with the
+    /// previous quarter.
+    PreviousPeriod,
+    /// The same dates one year earlier.
+    PreviousYear,
+    Range {
+        /// Included.
+        #[ts(as = "String")]
+        start: DateTime<Utc>,
+        /// Excluded.
+        #[ts(as = "String")]
+        end: DateTime<Utc>,
+    },
+}
+
+impl Baseline {
+    /// Range to compare the range of a report with.
+    fn range(self, (start, end): Period) -> Result<Period, String> {
+        let too_early = || "the baseline of the report starts too early".to_string();
+
+        match self {
+            Baseline::PreviousPeriod => {
+                let first_of_month =
+                    |date: DateTime<Utc>| date.day() == 1 && date.time() == chrono::NaiveTime::MIN;
+                if first_of_month(start) && first_of_month(end) {
+                    let months = (end.year() - start.year()) * 12 + end.month() as i32
+                        - start.month() as i32;
+                    let previous = start
+                        .checked_sub_months(Months::new(months.unsigned_abs()))
+                        .ok_or_else(too_early)?;
+                    Ok((previous, start))
+                } else {
+                    let previous = start
+                        .checked_sub_signed(end - start)
+                        .ok_or_else(too_early)?;
+                    Ok((previous, start))
+                }
+            }
+            Baseline::PreviousYear => {
+                let year_before = |date: DateTime<Utc>| date.checked_sub_months(Months::new(12));
+                Ok((
+                    year_before(start).ok_or_else(too_early)?,
+                    year_before(end).ok_or_else(too_early)?,
+                ))
+            }
+            Baseline::Range { start, end } => {
+                if end < start {
+                    return Err("the end of the baseline is before its start".to_string());
+                }
+                Ok((start, end))
+            }
+        }
+    }
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct CompareOptions {
+    /// Range of the report, the accounts and the filter apply to both ranges.
+    #[serde(flatten)]
+    pub report: ReportOptions,
+    /// Range to compare the report with.
+    pub baseline: Baseline,
+}
+
+/// Evolution of an amount from the baseline to the range of the report.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, Default, serde::Serialize)]
+pub struct Delta {
+    /// Over the range of the report.
+    #[ts(type = "string")]
+    pub current: Decimal,
+    /// Over the baseline.
+    #[ts(type = "string")]
+    pub previous: Decimal,
+    /// Current minus previous amount.
+    #[ts(type = "string")]
+    pub change: Decimal,
+    /// Change relative to the previous amount in percent, `None` if it was zero.
+    #[ts(type = "string | null")]
+    pub percentage: Option<Decimal>,
+}
+
+impl Delta {
+    fn new(current: Decimal, previous: Decimal) -> Self {
+        let change = current - previous;
+        Self {
+            current,
+            previous,
+            change,
+            percentage: (!previous.is_zero()).then(|| percentage(change, previous.abs())),
+        }
+    }
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TagDelta {
+    /// `None` for the spendings without tags.
+    pub tag: Option<String>,
+    /// Sum of the outgoing amounts, positive.
+    pub spent: Delta,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AccountDelta {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    pub name: String,
+    /// Sum of the incoming amounts.
+    pub income: Delta,
+    /// Sum of the outgoing amounts, positive.
+    pub spent: Delta,
+}
+
+/// Spendings and incomes of the accounts of a currency over the range of a report,
+/// compared with a baseline. Transfers between accounts are excluded.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Comparison {
+    pub currency: String,
+    /// Included.
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    /// Excluded.
+    #[ts(as = "String")]
+    pub end: DateTime<Utc>,
+    /// Included.
+    #[ts(as = "String")]
+    pub baseline_start: DateTime<Utc>,
+    /// Excluded.
+    #[ts(as = "String")]
+    pub baseline_end: DateTime<Utc>,
+    /// Sum of the outgoing amounts, positive.
+    pub spent: Delta,
+    /// By decreasing current spendings.
+    pub tags: Vec<TagDelta>,
+    /// Accounts with transactions in either range, by name.
+    pub accounts: Vec<AccountDelta>,
+}
+
+/// Incomes and spendings of an account over a range.
+#[derive(serde::Deserialize)]
+struct AccountSum {
+    account: RecordId,
+    name: String,
+    currency: String,
+    income: Decimal,
+    spent: Decimal,
+}
+
+async fn account_sums(db: &Surreal<Db>, options: &ReportOptions) -> Result<Vec<AccountSum>, Error> {
+    let (start, end) = options.range().map_err(Error::Invalid)?;
+    let (conditions, params) = options.conditions()?;
+    let query = format!(
+        r#"
+    SELECT
+        account,
+        account.name AS name,
+        account.currency AS currency,
+        math::sum(IF amount > 0 THEN amount ELSE 0dec END) AS income,
+        math::sum(IF amount < 0 THEN -amount ELSE 0dec END) AS spent
+    FROM transaction WHERE {conditions} GROUP BY account, name, currency"#
+    );
+
+    Ok(crate::filter::bind(db.query(query), params)
+        .bind(("start", surrealdb::Datetime::from(start)))
+        .bind(("end", surrealdb::Datetime::from(end)))
+        .bind(("accounts", options.accounts.clone()))
+        .await?
+        .take(0)?)
+}
+
+/// Compare the spendings by tag and the incomes and spendings by account between the
+/// range of a report and a baseline, for the accounts of each currency.
+pub async fn compare(db: &Surreal<Db>, options: CompareOptions) -> Result<Vec<Comparison>, Error> {
+    let range = options.report.range().map_err(Error::Invalid)?;
+    let baseline = options.baseline.range(range).map_err(Error::Invalid)?;
+
+    let current = ReportOptions {
+        start: range.0,
+        end: Some(range.1),
+        ..options.report
+    };
+    let previous = ReportOptions {
+        start: baseline.0,
+        end: Some(baseline.1),
+        ..current.clone()
+    };
+
+    let mut current_tags: HashMap<String, SpendingByTag> = spending_by_tag(db, current.clone())
+        .await?
+        .into_iter()
+        .map(|spending| (spending.currency.clone(), spending))
+        .collect();
+    let mut previous_tags: HashMap<String, SpendingByTag> = spending_by_tag(db, previous.clone())
+        .await?
+        .into_iter()
+        .map(|spending| (spending.currency.clone(), spending))
+        .collect();
+
+    let mut accounts: HashMap<String, (AccountSum, AccountSum)> = HashMap::new();
+    for (sum, is_current) in account_sums(db, &current)
+        .await?
+        .into_iter()
+        .map(|sum| (sum, true))
+        .chain(
+            account_sums(db, &previous)
+                .await?
+                .into_iter()
+                .map(|sum| (sum, false)),
+        )
+    {
+        let (current, previous) = accounts.entry(sum.account.to_string()).or_insert_with(|| {
+            let empty = || AccountSum {
+                account: sum.account.clone(),
+                name: sum.name.clone(),
+                currency: sum.currency.clone(),
+                income: Decimal::ZERO,
+                spent: Decimal::ZERO,
+            };
+            (empty(), empty())
+        });
+        let totals = if is_current { current } else { previous };
+        totals.income += sum.income;
+        totals.spent += sum.spent;
+    }
+    let mut accounts: Vec<(String, AccountDelta)> = accounts
+        .into_values()
+        .map(|(current, previous)| {
+            (
+                current.currency,
+                AccountDelta {
+                    income: Delta::new(current.income, previous.income),
+                    spent: Delta::new(current.spent, previous.spent),
+                    account: current.account,
+                    name: current.name,
+                },
+            )
+        })
+        .collect();
+    accounts.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    Ok(current
+        .currencies(db)
+        .await?
+        .into_iter()
+        .map(|currency| {
+            let current = current_tags.remove(&currency);
+            let previous = previous_tags.remove(&currency);
+            let spent = Delta::new(
+                current
+                    .as_ref()
+                    .map(|spending| spending.total)
+                    .unwrap_or_default(),
+                previous
+                    .as_ref()
+                    .map(|spending| spending.total)
+                    .unwrap_or_default(),
+            );
+
+            let mut tags: HashMap<Option<String>, (Decimal, Decimal)> = HashMap::new();
+            for tag in current.into_iter().flat_map(|spending| spending.tags) {
+                tags.entry(tag.tag).or_default().0 += tag.spent;
+            }
+            for tag in previous.into_iter().flat_map(|spending| spending.tags) {
+                tags.entry(tag.tag).or_default().1 += tag.spent;
+            }
+            let mut tags: Vec<TagDelta> = tags
+                .into_iter()
+                .map(|(tag, (current, previous))| TagDelta {
+                    tag,
+                    spent: Delta::new(current, previous),
+                })
+                .collect();
+            tags.sort_by(|a, b| {
+                b.spent
+                    .current
+                    .cmp(&a.spent.current)
+                    .then_with(|| b.spent.previous.cmp(&a.spent.previous))
+                    .then_with(|| a.tag.cmp(&b.tag))
+            });
+
+            Comparison {
+                start: range.0,
+                end: range.1,
+                baseline_start: baseline.0,
+                baseline_end: baseline.1,
+                spent,
+                tags,
+                accounts: accounts
+                    .iter()
+                    .filter(|(account_currency, _)| *account_currency == currency)
+                    .map(|(_, delta)| delta.clone())
+                    .collect(),
+                currency,
+            }
+        })
+        .collect())
+}
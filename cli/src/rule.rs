@@ -0,0 +1,506 @@
+//! Rules tagging and cleaning up transactions, like tagging "CB CARREFOUR 1234 PARIS"
+//! as groceries.
+//!
+//! Enabled rules are applied to every transaction added to the store, imported ones
+//! included and transfers excepted, and can be run over existing transactions with
+//! [`run_rules`]. A rule applies when all of its conditions match, and rules are
+//! applied by increasing priority, each one seeing the changes of the previous ones.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{
+    transaction::{Tag, TransactionWithId},
+    AddTransactionOptions, Error, Record,
+};
+
+/// Conditions a transaction must all match for a rule to apply. Conditions that
+/// are not set always match.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Conditions {
+    /// The description contains this text, ignoring case.
+    #[ts(optional)]
+    #[serde(default)]
+    pub description_contains: Option<String>,
+    /// The description matches this regular expression.
+    #[ts(optional)]
+    #[serde(default)]
+    pub description_matches: Option<String>,
+    /// Included.
+    #[ts(type = "string", optional)]
+    #[serde(default)]
+    pub amount_min: Option<Decimal>,
+    /// Included.
+    #[ts(type = "string", optional)]
+    #[serde(default)]
+    pub amount_max: Option<Decimal>,
+    /// The transaction belongs to one of these accounts.
+    #[ts(type = "Array<{ tb: string, id: { String: string }}>")]
+    #[serde(default)]
+    pub accounts: Vec<RecordId>,
+    /// Included.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub date_start: Option<DateTime<Utc>>,
+    /// Excluded.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub date_end: Option<DateTime<Utc>>,
+}
+
+/// Replacement of the parts of a description matching a regular expression.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Rewrite {
+    pub pattern: String,
+    /// Can reference the groups of the pattern, like `$1` or `${name}`.
+    pub replacement: String,
+}
+
+/// Changes made to the transactions a rule applies to.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Actions {
+    /// Labels of the tags added to the transaction.
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[ts(optional)]
+    #[serde(default)]
+    pub rewrite_description: Option<Rewrite>,
+    #[ts(optional)]
+    #[serde(default)]
+    pub set_payee: Option<String>,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Rule {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    pub name: String,
+    pub enabled: bool,
+    /// Rules are applied by increasing priority.
+    pub priority: i64,
+    pub conditions: Conditions,
+    pub actions: Actions,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddRuleOptions {
+    pub name: String,
+    pub enabled: bool,
+    pub priority: i64,
+    pub conditions: Conditions,
+    pub actions: Actions,
+}
+
+/// A rule with its regular expressions compiled.
+pub(crate) struct CompiledRule {
+    rule: Rule,
+    description_matches: Option<regex::Regex>,
+    rewrite: Option<(regex::Regex, String)>,
+}
+
+fn compile(pattern: &str) -> Result<regex::Regex, String> {
+    regex::Regex::new(pattern).map_err(|error| format!("invalid regular expression: {error}"))
+}
+
+impl CompiledRule {
+    fn new(rule: Rule) -> Result<Self, String> {
+        let description_matches = rule
+            .conditions
+            .description_matches
+            .as_deref()
+            .map(compile)
+            .transpose()?;
+        let rewrite = rule
+            .actions
+            .rewrite_description
+            .as_ref()
+            .map(|rewrite| {
+                compile(&rewrite.pattern).map(|regex| (regex, rewrite.replacement.clone()))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            rule,
+            description_matches,
+            rewrite,
+        })
+    }
+
+    fn matches(&self, transaction: &Candidate) -> bool {
+        let conditions = &self.rule.conditions;
+
+        conditions.description_contains.as_ref().is_none_or(|text| {
+            transaction
+                .description
+                .to_lowercase()
+                .contains(&text.to_lowercase())
+        }) && self
+            .description_matches
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(&transaction.description))
+            && conditions
+                .amount_min
+                .is_none_or(|min| transaction.amount >= min)
+            && conditions
+                .amount_max
+                .is_none_or(|max| transaction.amount <= max)
+            && (conditions.accounts.is_empty() || conditions.accounts.contains(transaction.account))
+            && conditions
+                .date_start
+                .is_none_or(|start| transaction.date >= start)
+            && conditions.date_end.is_none_or(|end| transaction.date < end)
+    }
+
+    /// Apply the actions of the rule, returning whether the transaction changed.
+    fn apply(&self, transaction: &mut Candidate) -> bool {
+        let actions = &self.rule.actions;
+        let mut changed = false;
+
+        for label in &actions.add_tags {
+            if !transaction.tags.contains(label) {
+                transaction.tags.push(label.clone());
+                changed = true;
+            }
+        }
+
+        if let Some((regex, replacement)) = &self.rewrite {
+            let description = regex
+                .replace_all(&transaction.description, replacement.as_str())
+                .trim()
+                .to_string();
+            if description != transaction.description {
+                transaction.description = description;
+                changed = true;
+            }
+        }
+
+        if let Some(payee) = &actions.set_payee {
+            if transaction.payee.as_ref() != Some(payee) {
+                transaction.payee = Some(payee.clone());
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// Fields of a transaction read and written by rules.
+struct Candidate<'a> {
+    account: &'a RecordId,
+    date: DateTime<Utc>,
+    amount: Decimal,
+    description: String,
+    payee: Option<String>,
+    /// Labels of the tags.
+    tags: Vec<String>,
+}
+
+impl Candidate<'_> {
+    /// Apply every rule matching the transaction, returning the names of the rules
+    /// that changed it.
+    fn apply(&mut self, rules: &[CompiledRule]) -> Vec<String> {
+        let mut applied = vec![];
+
+        for rule in rules {
+            if rule.matches(self) && rule.apply(self) {
+                applied.push(rule.rule.name.clone());
+            }
+        }
+
+        applied
+    }
+}
+
+fn check(conditions: &Conditions, actions: &Actions) -> Result<(), String> {
+    if conditions.description_contains.is_none()
+        && conditions.description_matches.is_none()
+        && conditions.amount_min.is_none()
+        && conditions.amount_max.is_none()
+        && conditions.accounts.is_empty()
+        && conditions.date_start.is_none()
+        && conditions.date_end.is_none()
+    {
+        return Err("a rule needs at least one condition".to_string());
+    }
+
+    if actions.add_tags.is_empty()
+        && actions.rewrite_description.is_none()
+        && actions.set_payee.is_none()
+    {
+        return Err("a rule needs at least one action".to_string());
+    }
+
+    if let Some(pattern) = &conditions.description_matches {
+        compile(pattern)?;
+    }
+
+    if let Some(rewrite) = &actions.rewrite_description {
+        compile(&rewrite.pattern)?;
+    }
+
+    Ok(())
+}
+
+/// Create the tags added by a rule that do not exist yet.
+async fn create_tags(db: &Surreal<Db>, actions: &Actions) -> Result<(), Error> {
+    let existing = crate::get_tags(db).await?;
+
+    crate::add_tags(
+        db,
+        actions
+            .add_tags
+            .iter()
+            .filter(|label| !existing.iter().any(|tag| &tag.label == *label))
+            .map(|label| Tag {
+                label: label.clone(),
+                color: None,
+            })
+            .collect(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn add_rule(db: &Surreal<Db>, options: AddRuleOptions) -> Result<Rule, Error> {
+    check(&options.conditions, &options.actions).map_err(Error::Invalid)?;
+    create_tags(db, &options.actions).await?;
+
+    let query = r#"
+    CREATE ONLY rule SET
+        name = $name,
+        enabled = $enabled,
+        priority = $priority,
+        conditions = $conditions,
+        actions = $actions"#;
+
+    let rule: Option<Rule> = db
+        .query(query)
+        .bind(("name", options.name))
+        .bind(("enabled", options.enabled))
+        .bind(("priority", options.priority))
+        .bind(("conditions", options.conditions))
+        .bind(("actions", options.actions))
+        .await?
+        .take(0)?;
+
+    rule.ok_or(Error::RecordNotFound)
+}
+
+/// List rules in the order they are applied.
+pub async fn list_rules(db: &Surreal<Db>) -> Result<Vec<Rule>, surrealdb::Error> {
+    db.query("SELECT * FROM rule ORDER BY priority, name")
+        .await?
+        .take(0)
+}
+
+pub async fn update_rule(db: &Surreal<Db>, rule: Rule) -> Result<(), Error> {
+    check(&rule.conditions, &rule.actions).map_err(Error::Invalid)?;
+    create_tags(db, &rule.actions).await?;
+
+    let _: Option<Record> = db
+        .update(("rule", rule.id.key().clone()))
+        .content(rule)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_rule(db: &Surreal<Db>, rule_id: RecordId) -> Result<(), surrealdb::Error> {
+    let _: Option<Record> = db.delete(rule_id).await?;
+
+    Ok(())
+}
+
+/// Enabled rules in the order they are applied. Imports load them once for all the
+/// transactions they add.
+pub(crate) async fn enabled_rules(db: &Surreal<Db>) -> Result<Vec<CompiledRule>, Error> {
+    list_rules(db)
+        .await?
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| {
+            let name = rule.name.clone();
+            CompiledRule::new(rule).map_err(|error| format!("rule {name}: {error}"))
+        })
+        .collect::<Result<_, _>>()
+        .map_err(Error::Invalid)
+}
+
+/// Tags with the labels added by rules, keeping the colors of the tags of the store.
+fn to_tags(labels: Vec<String>, existing: &[Tag], tags: &[Tag]) -> Vec<Tag> {
+    labels
+        .into_iter()
+        .map(|label| {
+            tags.iter()
+                .chain(existing)
+                .find(|tag| tag.label == label)
+                .cloned()
+                .unwrap_or(Tag { label, color: None })
+        })
+        .collect()
+}
+
+/// Apply enabled rules to a transaction about to be added to an account.
+pub(crate) async fn apply_on_insert(
+    db: &Surreal<Db>,
+    rules: &[CompiledRule],
+    account_id: &RecordId,
+    options: &mut AddTransactionOptions,
+) -> Result<(), Error> {
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    let mut candidate = Candidate {
+        account: account_id,
+        date: options.date.unwrap_or_else(Utc::now),
+        amount: options.amount,
+        description: options.description.clone(),
+        payee: options.payee.clone(),
+        tags: options.tags.iter().map(|tag| tag.label.clone()).collect(),
+    };
+
+    if candidate.apply(rules).is_empty() {
+        return Ok(());
+    }
+
+    let existing = crate::get_tags(db).await?;
+    options.tags = to_tags(candidate.tags, &existing, &options.tags);
+    options.description = candidate.description;
+    options.payee = candidate.payee;
+
+    Ok(())
+}
+
+/// A value before and after rules are applied.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// Changes made by rules to an existing transaction.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RuleChange {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub transaction: RecordId,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    /// Names of the rules that changed the transaction.
+    pub rules: Vec<String>,
+    pub description: Option<Change<String>>,
+    pub payee: Option<Change<Option<String>>>,
+    /// Labels of the tags added to the transaction.
+    pub added_tags: Vec<String>,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Default, Debug, serde::Deserialize)]
+pub struct RunRulesOptions {
+    /// Only run the rules over the transactions of this account.
+    #[ts(type = "{ tb: string, id: { String: string }}", optional)]
+    #[serde(default)]
+    pub account: Option<RecordId>,
+    /// Only compute the changes, without writing them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Run enabled rules over existing transactions, transfers excepted.
+///
+/// Returns the changes made to the transactions, or that would be made with `dry_run`.
+pub async fn run_rules(
+    db: &Surreal<Db>,
+    options: RunRulesOptions,
+) -> Result<Vec<RuleChange>, Error> {
+    let rules = enabled_rules(db).await?;
+    if rules.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let transactions: Vec<TransactionWithId> = match options.account {
+        Some(account) => db
+            .query("SELECT * FROM transaction WHERE account = $account ORDER BY date")
+            .bind(("account", account))
+            .await?
+            .take(0)?,
+        None => db
+            .query("SELECT * FROM transaction ORDER BY date")
+            .await?
+            .take(0)?,
+    };
+    let existing = crate::get_tags(db).await?;
+    let mut changes = vec![];
+
+    for transaction in transactions {
+        // Rules do not apply to transfers, whose legs must keep the same description.
+        if transaction.transfer.is_some() {
+            continue;
+        }
+
+        let inner = &transaction.inner;
+        let mut candidate = Candidate {
+            account: &transaction.account,
+            date: inner.date,
+            amount: inner.amount,
+            description: inner.description.clone(),
+            payee: inner.payee.clone(),
+            tags: inner.tags.iter().map(|tag| tag.label.clone()).collect(),
+        };
+
+        let applied = candidate.apply(&rules);
+        if applied.is_empty() {
+            continue;
+        }
+
+        let added_tags: Vec<String> = candidate.tags[inner.tags.len()..].to_vec();
+
+        if !options.dry_run {
+            db.query("UPDATE $id SET description = $description, payee = $payee, tags = $tags")
+                .bind(("id", transaction.id.clone()))
+                .bind(("description", candidate.description.clone()))
+                .bind(("payee", candidate.payee.clone()))
+                .bind((
+                    "tags",
+                    serde_json::json!(to_tags(candidate.tags, &existing, &inner.tags)),
+                ))
+                .await?
+                .check()?;
+        }
+
+        changes.push(RuleChange {
+            transaction: transaction.id.clone(),
+            date: inner.date,
+            rules: applied,
+            description: (candidate.description != inner.description).then(|| Change {
+                before: inner.description.clone(),
+                after: candidate.description,
+            }),
+            payee: (candidate.payee != inner.payee).then(|| Change {
+                before: inner.payee.clone(),
+                after: candidate.payee,
+            }),
+            added_tags,
+        });
+    }
+
+    Ok(changes)
+}
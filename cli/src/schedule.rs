@@ -0,0 +1,445 @@
+//! Recurring transactions, like rent, salaries or subscriptions.
+//!
+//! A schedule describes a transaction and a recurrence rule. Occurrences that are due
+//! are created as transactions by [`materialize`], which is called when the store is opened.
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+
+use crate::{transaction::Tag, AddTransactionOptions, Error, Record};
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The nth weekday of a month, like the second tuesday or the last friday.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct NthWeekday {
+    /// From 1 to 5, or -1 for the last weekday of the month.
+    pub nth: i8,
+    #[ts(type = "\"Mon\" | \"Tue\" | \"Wed\" | \"Thu\" | \"Fri\" | \"Sat\" | \"Sun\"")]
+    pub weekday: Weekday,
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Number of periods between two occurrences.
+    pub interval: u32,
+    /// Occur on the nth weekday of the month instead of the day of the start date.
+    /// Only used by monthly and yearly recurrences.
+    #[ts(optional)]
+    pub nth_weekday: Option<NthWeekday>,
+    /// Date after which there are no more occurrences.
+    #[ts(as = "Option<String>", optional)]
+    pub until: Option<DateTime<Utc>>,
+    /// Total number of occurrences.
+    #[ts(optional)]
+    pub count: Option<u32>,
+}
+
+impl Recurrence {
+    /// Date of the occurrence at `index`, the first one being at index 0.
+    pub fn occurrence(&self, start: DateTime<Utc>, index: u32) -> Option<DateTime<Utc>> {
+        self.occurrences(start, index).next()
+    }
+
+    /// Dates of the occurrences from the one at `index`, the first one being at index 0.
+    ///
+    /// Days of the month that do not exist are clamped to the end of the month
+    /// (a monthly recurrence starting on the 31st occurs on the 30th in april), but
+    /// months without the nth weekday are skipped (there is no fifth friday in most
+    /// months).
+    pub fn occurrences(
+        &self,
+        start: DateTime<Utc>,
+        index: u32,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let remaining = match self.count {
+            Some(count) => count.saturating_sub(index) as usize,
+            None => usize::MAX,
+        };
+
+        (0..)
+            .map_while(move |period| self.in_period(start, period))
+            .flatten()
+            .skip(index as usize)
+            .take(remaining)
+            .take_while(|occurrence| self.until.is_none_or(|until| *occurrence <= until))
+    }
+
+    /// Date of the occurrence in the period at `index`, `Some(None)` if the period has
+    /// no occurrence, and `None` once dates overflow.
+    fn in_period(&self, start: DateTime<Utc>, index: u32) -> Option<Option<DateTime<Utc>>> {
+        let periods = index.checked_mul(self.interval.max(1))?;
+        let date = start.date_naive();
+        let date = match self.frequency {
+            Frequency::Daily => Some(date.checked_add_days(Days::new(periods.into()))?),
+            Frequency::Weekly => Some(date.checked_add_days(Days::new(u64::from(periods) * 7))?),
+            Frequency::Monthly => self.in_month(date, periods)?,
+            Frequency::Yearly => self.in_month(date, periods.checked_mul(12)?)?,
+        };
+
+        Some(date.map(|date| date.and_time(start.time()).and_utc()))
+    }
+
+    /// Date of the occurrence in the month `months` after the one of `start`,
+    /// `Some(None)` if the month has no such day.
+    fn in_month(&self, start: NaiveDate, months: u32) -> Option<Option<NaiveDate>> {
+        let first = start.with_day(1)?.checked_add_months(Months::new(months))?;
+
+        Some(match self.nth_weekday {
+            Some(NthWeekday { nth: -1, weekday }) => {
+                let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                let offset = (7 + last.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                last.checked_sub_days(Days::new(offset.into()))
+            }
+            Some(NthWeekday { nth, weekday }) => NaiveDate::from_weekday_of_month_opt(
+                first.year(),
+                first.month(),
+                weekday,
+                u8::try_from(nth).ok()?,
+            ),
+            None => {
+                let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                first.with_day(start.day().min(last.day()))
+            }
+        })
+    }
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Schedule {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    #[ts(type = "string")]
+    pub amount: Decimal,
+    pub description: String,
+    pub tags: Vec<Tag>,
+    /// Date of the first occurrence.
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    pub recurrence: Recurrence,
+    /// Index of the next occurrence to create.
+    pub next_occurrence: u32,
+    /// Occurrences that will not be created.
+    #[ts(as = "Vec<String>")]
+    pub skipped: Vec<DateTime<Utc>>,
+    /// Postponed occurrences, created at these dates instead.
+    #[ts(as = "Vec<String>")]
+    pub postponed: Vec<DateTime<Utc>>,
+}
+
+/// Where an occurrence that is not created yet comes from.
+#[derive(Clone, Copy)]
+enum Pending {
+    /// The occurrence of the recurrence at this index.
+    Regular(u32),
+    Postponed,
+}
+
+impl Schedule {
+    /// Dates of the occurrences not created yet, up to `until` included, sorted.
+    fn pending(&self, until: DateTime<Utc>) -> Vec<(DateTime<Utc>, Pending)> {
+        let mut dates: Vec<(DateTime<Utc>, Pending)> = (self.next_occurrence..)
+            .zip(
+                self.recurrence
+                    .occurrences(self.start, self.next_occurrence),
+            )
+            .take_while(|(_, date)| *date <= until)
+            .filter(|(_, date)| !self.skipped.contains(date))
+            .map(|(index, date)| (date, Pending::Regular(index)))
+            .chain(
+                self.postponed
+                    .iter()
+                    .filter(|date| **date <= until)
+                    .map(|date| (*date, Pending::Postponed)),
+            )
+            .collect();
+
+        dates.sort_by_key(|(date, _)| *date);
+        dates
+    }
+
+    /// Mark an occurrence as created.
+    fn advance(&mut self, date: DateTime<Utc>, pending: Pending) {
+        match pending {
+            Pending::Regular(index) => {
+                self.next_occurrence = index + 1;
+                self.skipped.retain(|skipped| *skipped > date);
+            }
+            Pending::Postponed => {
+                if let Some(position) = self.postponed.iter().position(|other| *other == date) {
+                    self.postponed.remove(position);
+                }
+            }
+        }
+    }
+
+    /// Remove the occurrence at `date` from the occurrences to create.
+    ///
+    /// Returns false if there is no such occurrence.
+    fn remove_occurrence(&mut self, date: DateTime<Utc>) -> bool {
+        if let Some(position) = self.postponed.iter().position(|other| *other == date) {
+            self.postponed.remove(position);
+            return true;
+        }
+
+        let is_pending = self
+            .recurrence
+            .occurrences(self.start, self.next_occurrence)
+            .take_while(|occurrence| *occurrence <= date)
+            .any(|occurrence| occurrence == date);
+
+        if !is_pending || self.skipped.contains(&date) {
+            return false;
+        }
+
+        self.skipped.push(date);
+        true
+    }
+}
+
+fn not_upcoming(date: DateTime<Utc>) -> Error {
+    Error::Invalid(format!(
+        "{date} is not an upcoming occurrence of the schedule"
+    ))
+}
+
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Debug, serde::Deserialize)]
+pub struct AddScheduleOptions {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    #[ts(type = "string")]
+    pub amount: Decimal,
+    pub description: String,
+    pub tags: Vec<Tag>,
+    #[ts(as = "String")]
+    pub start: DateTime<Utc>,
+    pub recurrence: Recurrence,
+}
+
+/// A transaction that will be created by a schedule.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Occurrence {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub schedule: RecordId,
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub account: RecordId,
+    #[ts(as = "String")]
+    pub date: DateTime<Utc>,
+    #[ts(type = "string")]
+    pub amount: Decimal,
+    pub description: String,
+    pub tags: Vec<Tag>,
+}
+
+pub async fn add_schedule(
+    db: &Surreal<Db>,
+    options: AddScheduleOptions,
+) -> Result<Schedule, Error> {
+    if options.recurrence.interval == 0 {
+        return Err(Error::Invalid(
+            "the interval of a recurrence must be at least 1".to_string(),
+        ));
+    }
+
+    if let Some(NthWeekday { nth, .. }) = options.recurrence.nth_weekday {
+        if !(nth == -1 || (1..=5).contains(&nth)) {
+            return Err(Error::Invalid(
+                "the nth weekday of a recurrence must be between 1 and 5, or -1".to_string(),
+            ));
+        }
+    }
+
+    let currency = crate::get_currency(db, options.account.clone()).await?;
+    let query = r#"
+    CREATE ONLY schedule SET
+        account = $account_id,
+        amount = $amount,
+        description = $description,
+        tags = $tags,
+        start = $start,
+        recurrence = $recurrence,
+        next_occurrence = 0,
+        skipped = [],
+        postponed = []"#;
+
+    let schedule: Option<Schedule> = db
+        .query(query)
+        .bind(("account_id", options.account))
+        .bind(("amount", crate::money::round(options.amount, &currency)))
+        .bind(("description", options.description))
+        .bind(("tags", serde_json::json!(options.tags)))
+        .bind(("start", options.start))
+        .bind(("recurrence", options.recurrence))
+        .await?
+        .take(0)?;
+
+    schedule.ok_or(Error::RecordNotFound)
+}
+
+pub async fn list_schedules(db: &Surreal<Db>) -> Result<Vec<Schedule>, surrealdb::Error> {
+    db.select("schedule").await
+}
+
+pub async fn get_schedule(db: &Surreal<Db>, schedule_id: RecordId) -> Result<Schedule, Error> {
+    let schedule: Option<Schedule> = db.select(schedule_id).await?;
+
+    schedule.ok_or(Error::RecordNotFound)
+}
+
+/// Delete a schedule. Transactions already created by the schedule are kept.
+pub async fn delete_schedule(
+    db: &Surreal<Db>,
+    schedule_id: RecordId,
+) -> Result<(), surrealdb::Error> {
+    let _: Option<Record> = db.delete(schedule_id).await?;
+
+    Ok(())
+}
+
+/// Do not create the occurrence of a schedule at `date`.
+pub async fn skip_occurrence(
+    db: &Surreal<Db>,
+    schedule_id: RecordId,
+    date: DateTime<Utc>,
+) -> Result<(), Error> {
+    let mut schedule = get_schedule(db, schedule_id).await?;
+
+    if !schedule.remove_occurrence(date) {
+        return Err(not_upcoming(date));
+    }
+
+    save(db, schedule).await
+}
+
+/// Create the occurrence of a schedule at `date` on another date.
+pub async fn postpone_occurrence(
+    db: &Surreal<Db>,
+    schedule_id: RecordId,
+    date: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<(), Error> {
+    let mut schedule = get_schedule(db, schedule_id).await?;
+
+    if !schedule.remove_occurrence(date) {
+        return Err(not_upcoming(date));
+    }
+
+    schedule.postponed.push(to);
+    save(db, schedule).await
+}
+
+/// Occurrences of all schedules that are not created yet, up to `until` included,
+/// sorted by date.
+pub async fn upcoming_occurrences(
+    db: &Surreal<Db>,
+    until: DateTime<Utc>,
+) -> Result<Vec<Occurrence>, Error> {
+    let mut occurrences: Vec<Occurrence> = list_schedules(db)
+        .await?
+        .into_iter()
+        .flat_map(|schedule| {
+            schedule
+                .pending(until)
+                .into_iter()
+                .map(|(date, _)| Occurrence {
+                    schedule: schedule.id.clone(),
+                    account: schedule.account.clone(),
+                    date,
+                    amount: schedule.amount,
+                    description: schedule.description.clone(),
+                    tags: schedule.tags.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    occurrences.sort_by_key(|occurrence| occurrence.date);
+
+    Ok(occurrences)
+}
+
+/// Create the transactions of every occurrence due at `now`.
+///
+/// Each transaction is saved along with the progress of its schedule, so that an
+/// occurrence is never created twice, even when an error interrupts the creation.
+///
+/// Returns the number of created transactions.
+pub async fn materialize(db: &Surreal<Db>, now: DateTime<Utc>) -> Result<usize, Error> {
+    let mut created = 0;
+    let rules = crate::rule::enabled_rules(db).await?;
+
+    for mut schedule in list_schedules(db).await? {
+        for (date, pending) in schedule.pending(now) {
+            let mut options = AddTransactionOptions {
+                amount: schedule.amount,
+                description: schedule.description.clone(),
+                tags: schedule.tags.clone(),
+                splits: vec![],
+                date: Some(date),
+                ..Default::default()
+            };
+            crate::prepare_transaction(db, &rules, &schedule.account, &mut options).await?;
+            schedule.advance(date, pending);
+
+            let query = db
+                .query("BEGIN TRANSACTION;")
+                .query(crate::CREATE_TRANSACTION)
+                .query("UPDATE $schedule_id MERGE $schedule; COMMIT TRANSACTION;");
+            crate::bind_transaction(query, schedule.account.clone(), options)
+                .bind(("schedule_id", schedule.id.clone()))
+                .bind(("schedule", schedule.clone()))
+                .await?
+                .check()?;
+
+            created += 1;
+        }
+
+        // Skipped occurrences that are past are not pending anymore.
+        let past = schedule
+            .recurrence
+            .occurrences(schedule.start, schedule.next_occurrence)
+            .take_while(|date| *date <= now)
+            .count();
+        if past > 0 {
+            schedule.next_occurrence += u32::try_from(past).unwrap_or(u32::MAX);
+            schedule.skipped.retain(|date| *date > now);
+            save(db, schedule).await?;
+        }
+    }
+
+    Ok(created)
+}
+
+async fn save(db: &Surreal<Db>, schedule: Schedule) -> Result<(), Error> {
+    let _: Option<Record> = db
+        .update(("schedule", schedule.id.key().clone()))
+        .merge(schedule)
+        .await?;
+
+    Ok(())
+}
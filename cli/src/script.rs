@@ -1,14 +1,45 @@
 use rhai::packages::Package;
 use rhai::plugin::*;
 
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
 use crate::transaction::TransactionRhai;
 
-#[derive(Clone, serde::Deserialize)]
+/// Balance returned by the `on_<account name>` function of a script.
+#[derive(Clone)]
 pub struct ScriptAccountBalance {
-    pub amount: rhai::FLOAT,
+    pub amount: Decimal,
     pub currency: String,
 }
 
+impl TryFrom<rhai::Dynamic> for ScriptAccountBalance {
+    type Error = Box<rhai::EvalAltResult>;
+
+    fn try_from(value: rhai::Dynamic) -> Result<Self, Self::Error> {
+        let mut map = value
+            .try_cast::<rhai::Map>()
+            .ok_or("the script must return a map with the `currency` and `amount` keys")?;
+
+        let currency = map
+            .remove("currency")
+            .and_then(|currency| currency.into_string().ok())
+            .ok_or("the `currency` key must be a string")?;
+
+        let amount = map
+            .remove("amount")
+            .and_then(|amount| {
+                amount
+                    .as_decimal()
+                    .ok()
+                    .or_else(|| amount.as_int().ok().map(Decimal::from))
+                    .or_else(|| amount.as_float().ok().and_then(Decimal::from_f64))
+            })
+            .ok_or("the `amount` key must be a number")?;
+
+        Ok(Self { amount, currency })
+    }
+}
+
 #[export_module]
 pub mod env {
     /// Get an environement variable by name.
@@ -116,7 +147,9 @@ pub mod prelude {
     ///
     /// rhai-autodocs:index:2
     #[rhai_fn(return_raw)]
-    pub fn sum(transactions: &mut rhai::Array) -> Result<rhai::FLOAT, Box<rhai::EvalAltResult>> {
+    pub fn sum(
+        transactions: &mut rhai::Array,
+    ) -> Result<rust_decimal::Decimal, Box<rhai::EvalAltResult>> {
         let transactions = transactions
             .iter()
             .map(|d| d.clone().try_cast())
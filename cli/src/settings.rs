@@ -24,6 +24,20 @@ pub struct Settings {
     #[serde(default)]
     pub tags: Vec<String>,
     pub backups_path: std::path::PathBuf,
+    /// Number of days deleted transactions are kept in the trash.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Currency all balances are converted to when they are displayed together.
+    #[serde(default = "default_base_currency")]
+    pub base_currency: String,
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_base_currency() -> String {
+    "EUR".to_string()
 }
 
 impl Settings {
@@ -32,6 +46,8 @@ impl Settings {
             theme: Theme::default(),
             tags: Vec::default(),
             backups_path,
+            trash_retention_days: default_trash_retention_days(),
+            base_currency: default_base_currency(),
         }
     }
 }
@@ -1,4 +1,6 @@
+use chrono::Datelike;
 use rhai::{CustomType, TypeBuilder};
+use rust_decimal::Decimal;
 use surrealdb::RecordId;
 
 use crate::script::time_helper;
@@ -23,9 +25,70 @@ impl PartialEq for Tag {
 pub struct Transaction {
     #[ts(as = "String")]
     pub date: chrono::DateTime<chrono::Utc>,
-    pub amount: f64,
+    #[ts(type = "string")]
+    pub amount: Decimal,
     pub description: String,
     pub tags: Vec<Tag>,
+    /// Lines the amount is split into, empty if the transaction is not split.
+    /// When aggregating by tag, the tags of the lines are used instead of `tags`.
+    #[serde(default)]
+    pub splits: Vec<Split>,
+    /// Identifier given to the transaction by the bank, used to skip it when the
+    /// same statement is imported again.
+    #[ts(optional)]
+    #[serde(default)]
+    pub fitid: Option<String>,
+    /// Name of the other party of the transaction.
+    #[ts(optional)]
+    #[serde(default)]
+    pub payee: Option<String>,
+    #[ts(optional)]
+    #[serde(default)]
+    pub payee_iban: Option<String>,
+    /// Date the amount is taken into account by the bank for interests.
+    #[ts(as = "Option<String>", optional)]
+    #[serde(default)]
+    pub value_date: Option<chrono::DateTime<chrono::Utc>>,
+    #[ts(as = "Option<Status>", optional)]
+    #[serde(default)]
+    pub status: Status,
+}
+
+/// Whether a transaction was seen on a statement of the bank, ordered from uncleared
+/// to reconciled.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(
+    Default,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Uncleared,
+    /// The transaction appears on a statement.
+    Cleared,
+    /// The transaction was checked against the balance of a statement.
+    Reconciled,
+}
+
+/// Part of the amount of a split transaction, with its own tags.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Split {
+    #[ts(type = "string")]
+    pub amount: Decimal,
+    pub tags: Vec<Tag>,
+    pub memo: String,
 }
 
 #[derive(ts_rs::TS)]
@@ -38,6 +101,31 @@ pub struct TransactionWithId {
     pub id: RecordId,
     #[ts(skip)]
     pub account: RecordId,
+    /// The other leg of the transaction if it is a transfer between two accounts.
+    #[ts(type = "{ tb: string, id: { String: string }} | null")]
+    #[serde(default)]
+    pub transfer: Option<RecordId>,
+}
+
+/// Both legs of a transfer between two accounts.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Transfer {
+    pub from: TransactionWithId,
+    pub to: TransactionWithId,
+}
+
+/// A deleted transaction, kept until the trash retention period of the settings expires.
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TrashedTransaction {
+    #[ts(type = "{ tb: string, id: { String: string }}")]
+    pub id: RecordId,
+    pub transaction: TransactionWithId,
+    #[ts(as = "String")]
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, rhai::CustomType)]
@@ -47,7 +135,7 @@ pub struct TransactionRhai {
     pub date: time_helper::Date,
     /// Currency amount of the transaction, can be negative or positive depending of the transaction type.
     #[rhai_type(readonly)]
-    pub amount: rhai::FLOAT,
+    pub amount: Decimal,
     /// Description of the transaction.
     #[rhai_type(readonly)]
     pub description: String,
@@ -55,3 +143,22 @@ pub struct TransactionRhai {
     #[rhai_type(readonly)]
     pub tags: rhai::Array,
 }
+
+impl From<Transaction> for TransactionRhai {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            date: time_helper::Date::from_ordinal_date(
+                transaction.date.year(),
+                transaction.date.ordinal() as u16,
+            )
+            .expect("chrono dates are always valid ordinal dates"),
+            amount: transaction.amount,
+            description: transaction.description,
+            tags: transaction
+                .tags
+                .into_iter()
+                .map(|tag| rhai::Dynamic::from(tag.label))
+                .collect(),
+        }
+    }
+}
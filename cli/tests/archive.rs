@@ -0,0 +1,226 @@
+//! Export of the store to portable archives, and import of the archives.
+
+mod common;
+
+use common::{add_account, amount, date, tags};
+use surrealdb::{engine::local::Db, Surreal};
+use thunes_cli::{
+    archive::{self, Archive},
+    settings::{Settings, Theme},
+    transaction::{Split, Status, Tag},
+    AddTransactionOptions, AddTransferOptions, Record,
+};
+
+async fn save_settings(db: &Surreal<Db>, settings: Settings) {
+    let _: Option<Record> = db
+        .upsert(("settings", "main"))
+        .content(settings)
+        .await
+        .unwrap();
+}
+
+/// Content of an archive without the date of the export, transactions sorted by id.
+fn content(mut archive: Archive) -> serde_json::Value {
+    archive.transactions.sort_by(|a, b| a.id.cmp(&b.id));
+
+    serde_json::json!({
+        "settings": archive.settings,
+        "accounts": archive.accounts,
+        "tags": archive.tags,
+        "transactions": archive.transactions,
+    })
+}
+
+/// A store with split transactions, a transfer and a reconciled transaction.
+async fn seed(db: &Surreal<Db>) {
+    let mut settings = Settings::new(std::env::temp_dir());
+    settings.theme = Theme::Light;
+    settings.base_currency = "USD".to_string();
+    settings.trash_retention_days = 7;
+    save_settings(db, settings).await;
+
+    thunes_cli::add_tags(
+        db,
+        vec![
+            Tag {
+                label: "food".to_string(),
+                color: Some("#00ff00".to_string()),
+            },
+            Tag {
+                label: "home".to_string(),
+                color: None,
+            },
+        ],
+    )
+    .await
+    .unwrap();
+
+    let main = add_account(db, "main", "EUR").await;
+    let savings = add_account(db, "savings", "USD").await;
+
+    thunes_cli::add_transaction(
+        db,
+        main.clone(),
+        AddTransactionOptions {
+            amount: amount("-100"),
+            description: "Supermarket".to_string(),
+            tags: tags(&["shopping"]),
+            splits: vec![
+                Split {
+                    amount: amount("-60"),
+                    tags: tags(&["food"]),
+                    memo: "groceries".to_string(),
+                },
+                Split {
+                    amount: amount("-30"),
+                    tags: tags(&["home", "food"]),
+                    memo: "cleaning".to_string(),
+                },
+                Split {
+                    amount: amount("-10"),
+                    tags: vec![],
+                    memo: String::new(),
+                },
+            ],
+            date: Some(date("2025-01-05")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    thunes_cli::add_transaction(
+        db,
+        main.clone(),
+        AddTransactionOptions {
+            amount: amount("2500"),
+            description: "Salary".to_string(),
+            tags: tags(&["salary"]),
+            date: Some(date("2025-01-01T08:30:00Z")),
+            fitid: Some("FIT-1".to_string()),
+            payee: Some("Employer".to_string()),
+            payee_iban: Some("FR7630006000011234567890189".to_string()),
+            value_date: Some(date("2025-01-02")),
+            status: Status::Reconciled,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    thunes_cli::add_transfer(
+        db,
+        AddTransferOptions {
+            from: main,
+            to: savings,
+            amount: amount("200"),
+            rate: Some(amount("1.05")),
+            description: "Savings".to_string(),
+            tags: vec![],
+            date: Some(date("2025-01-10")),
+            fitid: None,
+        },
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn round_trip() {
+    let db = common::open().await;
+    seed(&db).await;
+    let exported = archive::export(&db).await.unwrap();
+    assert_eq!(exported.transactions.len(), 4);
+    let expected = content(exported.clone());
+
+    let directory = tempfile::tempdir().unwrap();
+    let json = directory.path().join("archive.json");
+    let csv = directory.path().join("archive");
+    archive::write_json(&exported, &json).unwrap();
+    archive::write_csv(&exported, &csv).unwrap();
+
+    for path in [json, csv] {
+        let imported = common::open().await;
+        // Settings are created when the app starts.
+        save_settings(&imported, Settings::new(std::env::temp_dir())).await;
+
+        let report = archive::import_file(&imported, &path).await.unwrap();
+        assert_eq!(
+            (report.accounts, report.transactions, report.tags),
+            (2, 4, 2)
+        );
+        assert_eq!(
+            content(archive::export(&imported).await.unwrap()),
+            expected,
+            "{}",
+            path.display()
+        );
+
+        // Transfer legs are still linked.
+        let savings = thunes_cli::list_account(&imported)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|account| account.name == "savings")
+            .unwrap();
+        let balance = thunes_cli::balance(&imported, savings.id, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(balance, amount("210.00"));
+    }
+}
+
+#[tokio::test]
+async fn lines_of_splits_follow_each_other() {
+    let db = common::open().await;
+    seed(&db).await;
+    let exported = archive::export(&db).await.unwrap();
+    let split = exported
+        .transactions
+        .iter()
+        .find(|transaction| !transaction.splits.is_empty())
+        .unwrap()
+        .id
+        .clone();
+
+    let directory = tempfile::tempdir().unwrap();
+    archive::write_csv(&exported, directory.path()).unwrap();
+    let splits = directory.path().join("splits.csv");
+    let content = std::fs::read_to_string(&splits).unwrap();
+
+    // Lines may be written in any order.
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines[1..].reverse();
+    std::fs::write(&splits, lines.join("\n")).unwrap();
+    let archive = archive::read_csv(directory.path()).unwrap();
+    let memos: Vec<_> = archive
+        .transactions
+        .iter()
+        .find(|transaction| transaction.id == split)
+        .unwrap()
+        .splits
+        .iter()
+        .map(|split| split.memo.as_str())
+        .collect();
+    assert_eq!(memos, ["groceries", "cleaning", ""]);
+
+    for (from, to, error) in [
+        (
+            ",2,",
+            ",3,",
+            format!("splits.csv: transaction {split} has no line 2"),
+        ),
+        (
+            ",1,",
+            ",0,",
+            format!("splits.csv: transaction {split} has line 0 twice"),
+        ),
+    ] {
+        std::fs::write(&splits, content.replace(from, to)).unwrap();
+        assert_eq!(
+            archive::read_csv(directory.path()).unwrap_err(),
+            error,
+            "{from} to {to}"
+        );
+    }
+}
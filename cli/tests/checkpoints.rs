@@ -0,0 +1,143 @@
+//! Checkpoints of the balance of accounts, and reconciliation of the transactions up
+//! to them.
+
+mod common;
+
+use common::{add_account, add_transaction, amount, date};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    checkpoint::{self, AddCheckpointOptions},
+    transaction::{Status, TransactionWithId},
+    Error,
+};
+
+async fn add_checkpoint(
+    db: &Surreal<Db>,
+    account: &RecordId,
+    day: &str,
+    balance: &str,
+) -> RecordId {
+    checkpoint::add_checkpoint(
+        db,
+        AddCheckpointOptions {
+            account: account.clone(),
+            date: date(day),
+            balance: amount(balance),
+            source: "manual".to_string(),
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+/// Transactions of an account, sorted by date.
+async fn transactions(db: &Surreal<Db>, account: &RecordId) -> Vec<TransactionWithId> {
+    let mut transactions = thunes_cli::get_transactions(db, account.clone(), Default::default())
+        .await
+        .unwrap();
+    transactions.sort_by_key(|transaction| transaction.inner.date);
+
+    transactions
+}
+
+#[tokio::test]
+async fn checkpoint_before_any_transaction() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    add_transaction(&db, &account, "2025-02-01", "100", "Salary").await;
+
+    let wrong = add_checkpoint(&db, &account, "2025-01-15", "20").await;
+    let reconciliation = checkpoint::get_reconciliation(&db, wrong.clone())
+        .await
+        .unwrap();
+    assert_eq!(reconciliation.balance, amount("0"));
+    assert_eq!(reconciliation.difference, amount("20.00"));
+    assert!(reconciliation.transactions.is_empty());
+    assert!(matches!(
+        checkpoint::reconcile(&db, wrong).await,
+        Err(Error::Invalid(_))
+    ));
+
+    let right = add_checkpoint(&db, &account, "2025-01-15", "0").await;
+    let reconciliation = checkpoint::reconcile(&db, right).await.unwrap();
+    assert!(reconciliation.transactions.is_empty());
+    assert!(reconciliation.checkpoint.reconciled_at.is_some());
+    assert_eq!(
+        transactions(&db, &account).await[0].inner.status,
+        Status::Uncleared
+    );
+}
+
+#[tokio::test]
+async fn reconciled_transactions_are_locked() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    add_transaction(&db, &account, "2025-01-01", "1000", "Salary").await;
+    add_transaction(&db, &account, "2025-01-10", "-200", "Groceries").await;
+    // Later on the day of the checkpoint.
+    add_transaction(&db, &account, "2025-01-10T18:00:00Z", "-50", "Restaurant").await;
+    add_transaction(&db, &account, "2025-01-11", "-30", "Cinema").await;
+
+    let checkpoint = add_checkpoint(&db, &account, "2025-01-10", "750").await;
+    let reconciliation = checkpoint::get_reconciliation(&db, checkpoint.clone())
+        .await
+        .unwrap();
+    assert_eq!(reconciliation.balance, amount("750"));
+    assert!(reconciliation.difference.is_zero());
+    assert_eq!(reconciliation.transactions.len(), 3);
+
+    let reconciliation = checkpoint::reconcile(&db, checkpoint).await.unwrap();
+    assert_eq!(reconciliation.transactions.len(), 3);
+    let statuses: Vec<Status> = transactions(&db, &account)
+        .await
+        .iter()
+        .map(|transaction| transaction.inner.status)
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            Status::Reconciled,
+            Status::Reconciled,
+            Status::Reconciled,
+            Status::Uncleared
+        ]
+    );
+
+    let groceries = transactions(&db, &account).await.remove(1);
+    let mut updated = groceries.clone();
+    updated.inner.amount = amount("-210");
+    assert!(matches!(
+        thunes_cli::update_transaction(&db, updated).await,
+        Err(Error::Invalid(_))
+    ));
+    let mut updated = groceries.clone();
+    updated.inner.date = date("2025-01-09");
+    assert!(matches!(
+        thunes_cli::update_transaction(&db, updated).await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(matches!(
+        thunes_cli::delete_transaction(&db, groceries.id.clone()).await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(thunes_cli::get_trash(&db).await.unwrap().is_empty());
+
+    // Other details can change.
+    let mut updated = groceries.clone();
+    updated.inner.description = "Supermarket".to_string();
+    thunes_cli::update_transaction(&db, updated).await.unwrap();
+
+    // Once set back to cleared, the transaction can change again.
+    let mut updated = transactions(&db, &account).await.remove(1);
+    assert_eq!(updated.inner.description, "Supermarket");
+    updated.inner.status = Status::Cleared;
+    thunes_cli::update_transaction(&db, updated.clone())
+        .await
+        .unwrap();
+    updated.inner.amount = amount("-210");
+    thunes_cli::update_transaction(&db, updated).await.unwrap();
+    thunes_cli::delete_transaction(&db, groceries.id.clone())
+        .await
+        .unwrap();
+}
@@ -0,0 +1,106 @@
+//! Helpers shared by the integration tests.
+
+// Each test file uses a part of the helpers only.
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use surrealdb::{
+    engine::local::{Db, RocksDb},
+    RecordId, Surreal,
+};
+use thunes_cli::{transaction::Tag, AddAccountOptions, AddTransactionOptions};
+
+/// A database in a temporary directory, removed once the database is closed.
+pub struct Store {
+    db: Surreal<Db>,
+    _directory: tempfile::TempDir,
+}
+
+impl std::ops::Deref for Store {
+    type Target = Surreal<Db>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
+
+/// Open an empty database, with the schema of the desktop app.
+pub async fn open() -> Store {
+    let store = open_without_schema().await;
+    thunes_cli::migration::run(&store).await.unwrap();
+
+    store
+}
+
+/// Open an empty database without schema, to write data like previous versions did.
+pub async fn open_without_schema() -> Store {
+    let directory = tempfile::tempdir().unwrap();
+    let db: Surreal<Db> = Surreal::init();
+    db.connect::<RocksDb>(directory.path()).await.unwrap();
+    db.use_ns("user").use_db("accounts").await.unwrap();
+
+    Store {
+        db,
+        _directory: directory,
+    }
+}
+
+/// A day at midnight, like `2025-01-03`, or an instant, like `2025-01-03T18:30:00Z`.
+pub fn date(date: &str) -> DateTime<Utc> {
+    if date.contains('T') {
+        date.parse().unwrap()
+    } else {
+        format!("{date}T00:00:00Z").parse().unwrap()
+    }
+}
+
+pub fn amount(amount: &str) -> Decimal {
+    amount.parse().unwrap()
+}
+
+/// Tags without color.
+pub fn tags(labels: &[&str]) -> Vec<Tag> {
+    labels
+        .iter()
+        .map(|label| Tag {
+            label: label.to_string(),
+            color: None,
+        })
+        .collect()
+}
+
+pub async fn add_account(db: &Surreal<Db>, name: &str, currency: &str) -> RecordId {
+    thunes_cli::add_account(
+        db,
+        AddAccountOptions {
+            name: name.to_string(),
+            currency: currency.to_string(),
+        },
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+/// Add a transaction with only an amount, a description and a date.
+pub async fn add_transaction(
+    db: &Surreal<Db>,
+    account: &RecordId,
+    date: &str,
+    amount: &str,
+    description: &str,
+) {
+    thunes_cli::add_transaction(
+        db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: self::amount(amount),
+            description: description.to_string(),
+            date: Some(self::date(date)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+}
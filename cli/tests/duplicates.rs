@@ -0,0 +1,224 @@
+//! Detection of transactions recorded twice, and their merge or dismissal.
+
+mod common;
+
+use common::{add_account, add_transaction, amount, date, tags};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    duplicate::{self, Decision, FindDuplicatesOptions},
+    transaction::{Status, TransactionWithId},
+    AddTransactionOptions, AddTransferOptions, Error,
+};
+
+async fn find(db: &Surreal<Db>) -> Vec<(String, String)> {
+    duplicate::find_duplicates(db, FindDuplicatesOptions::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|pair| {
+            (
+                pair.keep.inner.description,
+                pair.duplicate.inner.description,
+            )
+        })
+        .collect()
+}
+
+async fn transaction(db: &Surreal<Db>, account: &RecordId, description: &str) -> TransactionWithId {
+    thunes_cli::get_transactions(db, account.clone(), Default::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|transaction| transaction.inner.description == description)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn find_and_merge() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    thunes_cli::add_transaction(
+        &db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount("-15.99"),
+            description: "Netflix".to_string(),
+            tags: tags(&["fun"]),
+            date: Some(date("2025-01-05")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    thunes_cli::add_transaction(
+        &db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount("-15.99"),
+            description: "NETFLIX.COM".to_string(),
+            tags: tags(&["subscriptions", "fun"]),
+            date: Some(date("2025-01-06")),
+            fitid: Some("FIT-1".to_string()),
+            payee: Some("Netflix".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    // Too far apart, or with another amount.
+    add_transaction(&db, &account, "2025-02-05", "-15.99", "Netflix").await;
+    add_transaction(&db, &account, "2025-01-05", "-17.99", "Netflix").await;
+
+    // The imported transaction has the most details and is kept.
+    let duplicates = duplicate::find_duplicates(&db, Default::default())
+        .await
+        .unwrap();
+    let [pair] = &duplicates[..] else {
+        panic!("{duplicates:?}");
+    };
+    assert_eq!(pair.keep.inner.description, "NETFLIX.COM");
+    assert_eq!(pair.duplicate.inner.date, date("2025-01-05"));
+    assert!(pair.score >= duplicate::DEFAULT_MIN_SCORE, "{}", pair.score);
+
+    assert!(matches!(
+        duplicate::merge_duplicates(&db, pair.keep.id.clone(), pair.keep.id.clone()).await,
+        Err(Error::Invalid(_))
+    ));
+
+    let merged = duplicate::merge_duplicates(&db, pair.keep.id.clone(), pair.duplicate.id.clone())
+        .await
+        .unwrap();
+    assert_eq!(merged.id, pair.keep.id);
+    assert_eq!(merged.inner.tags, tags(&["subscriptions", "fun"]));
+    assert_eq!(merged.inner.fitid.as_deref(), Some("FIT-1"));
+    assert_eq!(merged.inner.payee.as_deref(), Some("Netflix"));
+
+    let trash = thunes_cli::get_trash(&db).await.unwrap();
+    let [trashed] = &trash[..] else {
+        panic!("{trash:?}");
+    };
+    assert_eq!(trashed.transaction.id, pair.duplicate.id);
+
+    // The merged pair is logged, and the duplicate in the trash is not proposed.
+    let decisions = duplicate::list_duplicate_decisions(&db).await.unwrap();
+    let [decision] = &decisions[..] else {
+        panic!("{decisions:?}");
+    };
+    assert_eq!(decision.decision, Decision::Merged);
+    assert_eq!(
+        decision.transactions,
+        [pair.keep.id.clone(), pair.duplicate.id.clone()]
+    );
+    assert!(find(&db).await.is_empty());
+
+    // Restoring the duplicate does not propose the pair again.
+    thunes_cli::restore_transactions(&db, vec![pair.duplicate.id.clone()])
+        .await
+        .unwrap();
+    assert!(find(&db).await.is_empty());
+}
+
+#[tokio::test]
+async fn dismiss() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    add_transaction(&db, &account, "2025-01-05", "-3", "Coffee").await;
+    add_transaction(&db, &account, "2025-01-05T16:00:00Z", "-3", "Coffee").await;
+    assert_eq!(
+        find(&db).await,
+        [("Coffee".to_string(), "Coffee".to_string())]
+    );
+
+    let duplicates = duplicate::find_duplicates(&db, Default::default())
+        .await
+        .unwrap();
+    // The order of the transactions does not matter.
+    duplicate::dismiss_duplicate(
+        &db,
+        duplicates[0].duplicate.id.clone(),
+        duplicates[0].keep.id.clone(),
+    )
+    .await
+    .unwrap();
+    assert!(find(&db).await.is_empty());
+
+    let decisions = duplicate::list_duplicate_decisions(&db).await.unwrap();
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].decision, Decision::Dismissed);
+    assert_eq!(thunes_cli::get_trash(&db).await.unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn transfers_and_reconciled_transactions() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    let savings = add_account(&db, "savings", "EUR").await;
+    for _ in 0..2 {
+        thunes_cli::add_transfer(
+            &db,
+            AddTransferOptions {
+                from: account.clone(),
+                to: savings.clone(),
+                amount: amount("100"),
+                rate: None,
+                description: "Savings".to_string(),
+                tags: vec![],
+                date: Some(date("2025-01-10")),
+                fitid: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // Legs of transfers are never paired together.
+    assert!(find(&db).await.is_empty());
+
+    // A transaction recorded by hand is paired with the legs, which are kept.
+    add_transaction(&db, &account, "2025-01-10", "-100", "Savings").await;
+    let duplicates = duplicate::find_duplicates(&db, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(duplicates.len(), 2);
+    for pair in &duplicates {
+        assert!(pair.keep.transfer.is_some());
+        assert!(pair.duplicate.transfer.is_none());
+        assert!(matches!(
+            duplicate::merge_duplicates(&db, pair.duplicate.id.clone(), pair.keep.id.clone()).await,
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    thunes_cli::add_transaction(
+        &db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount("-50"),
+            description: "Gym".to_string(),
+            date: Some(date("2025-01-20")),
+            status: Status::Reconciled,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    add_transaction(&db, &account, "2025-01-21", "-50", "GYM").await;
+    let reconciled = transaction(&db, &account, "Gym").await;
+    let other = transaction(&db, &account, "GYM").await;
+
+    // The reconciled transaction is kept.
+    assert!(matches!(
+        duplicate::merge_duplicates(&db, other.id.clone(), reconciled.id.clone()).await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(find(&db)
+        .await
+        .contains(&("Gym".to_string(), "GYM".to_string())));
+    let merged = duplicate::merge_duplicates(&db, reconciled.id.clone(), other.id.clone())
+        .await
+        .unwrap();
+    assert_eq!(merged.inner.status, Status::Reconciled);
+    assert!(!find(&db)
+        .await
+        .contains(&("Gym".to_string(), "GYM".to_string())));
+}
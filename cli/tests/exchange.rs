@@ -0,0 +1,141 @@
+//! Exchange rates, and conversion of the balances into a single currency.
+
+mod common;
+
+use common::{add_account, add_transaction, amount, date};
+use thunes_cli::{
+    exchange::{self, AddExchangeRateOptions, ConversionOptions, Rates},
+    Error,
+};
+
+fn rate(base: &str, quote: &str, day: &str, rate: &str) -> AddExchangeRateOptions {
+    AddExchangeRateOptions {
+        base: base.to_string(),
+        quote: quote.to_string(),
+        date: date(day),
+        rate: amount(rate),
+        source: "manual".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn currencies_are_case_insensitive() {
+    let db = common::open().await;
+
+    let added = exchange::add_exchange_rate(&db, rate("eur", "Usd", "2025-01-01", "1.1"))
+        .await
+        .unwrap();
+    assert_eq!((added.base.as_str(), added.quote.as_str()), ("EUR", "USD"));
+    assert!(matches!(
+        exchange::add_exchange_rate(&db, rate("eur", "EUR", "2025-01-01", "1.1")).await,
+        Err(Error::Invalid(_))
+    ));
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        file.path(),
+        "date,base,quote,rate\n2025-03-01,eur,usd,1.2\n",
+    )
+    .unwrap();
+    assert_eq!(exchange::import_file(&db, file.path()).await.unwrap(), 1);
+
+    let pairs: Vec<_> = exchange::list_exchange_rates(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|rate| (rate.base, rate.quote))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("EUR".to_string(), "USD".to_string()),
+            ("EUR".to_string(), "USD".to_string())
+        ]
+    );
+
+    let rates = Rates::load(&db).await.unwrap();
+    assert_eq!(
+        rates.get("Eur", "usd", date("2025-02-01")),
+        Some(amount("1.1"))
+    );
+    assert_eq!(
+        rates.get("usd", "eur", date("2025-03-01")),
+        Some(amount("1") / amount("1.2"))
+    );
+    assert_eq!(
+        rates.get("usd", "USD", date("2025-03-01")),
+        Some(amount("1"))
+    );
+    assert_eq!(rates.get("eur", "usd", date("2024-12-31")), None);
+}
+
+#[tokio::test]
+async fn convert_balances_at_a_date() {
+    let db = common::open().await;
+    // Currencies of accounts are not normalized.
+    let account = add_account(&db, "main", "eur").await;
+    add_transaction(&db, &account, "2025-01-10", "100", "Salary").await;
+    add_transaction(&db, &account, "2025-03-10", "50", "Bonus").await;
+    exchange::add_exchange_rate(&db, rate("EUR", "USD", "2025-01-01", "1.1"))
+        .await
+        .unwrap();
+    exchange::add_exchange_rate(&db, rate("EUR", "USD", "2025-03-01", "1.2"))
+        .await
+        .unwrap();
+
+    let convert = |options: ConversionOptions| {
+        let db = &db;
+        async move {
+            let balances = exchange::convert_balances(
+                db,
+                ConversionOptions {
+                    currency: Some("USD".to_string()),
+                    ..options
+                },
+            )
+            .await
+            .unwrap();
+            assert_eq!(balances.accounts.len(), 1);
+            (balances.accounts[0].balance, balances.total)
+        }
+    };
+
+    assert_eq!(
+        convert(Default::default()).await,
+        (amount("150"), amount("180.00"))
+    );
+    // Only the transactions dated before the date are counted.
+    assert_eq!(
+        convert(ConversionOptions {
+            date: Some(date("2025-02-01")),
+            ..Default::default()
+        })
+        .await,
+        (amount("100"), amount("110.00"))
+    );
+    assert_eq!(
+        convert(ConversionOptions {
+            date: Some(date("2025-01-10")),
+            ..Default::default()
+        })
+        .await,
+        (amount("0"), amount("0"))
+    );
+    assert_eq!(
+        convert(ConversionOptions {
+            at_transaction_date: Some(true),
+            ..Default::default()
+        })
+        .await,
+        (amount("150"), amount("170.00"))
+    );
+    assert_eq!(
+        convert(ConversionOptions {
+            date: Some(date("2025-02-01")),
+            at_transaction_date: Some(true),
+            ..Default::default()
+        })
+        .await,
+        (amount("100"), amount("110.00"))
+    );
+}
@@ -0,0 +1,151 @@
+//! Parsing of filters, and the transactions they match.
+
+mod common;
+
+use common::{add_account, add_transaction, date};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{filter::Filter, GetTransactionOptions};
+
+fn parse(filter: &str) -> Filter {
+    filter
+        .parse()
+        .unwrap_or_else(|error| panic!("{filter}: {error}"))
+}
+
+/// Descriptions of the transactions matching a filter, in date order.
+async fn matching(db: &Surreal<Db>, account: &RecordId, filter: &str) -> Vec<String> {
+    thunes_cli::get_transactions(
+        db,
+        account.clone(),
+        GetTransactionOptions {
+            filter: Some(filter.to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap_or_else(|error| panic!("{filter}: {error:?}"))
+    .into_iter()
+    .map(|transaction| transaction.inner.description)
+    .collect()
+}
+
+#[test]
+fn precedence() {
+    // `not` binds tighter than `and`, which binds tighter than `or`.
+    assert_eq!(parse("a or b and not c"), parse("a or (b and (not c))"));
+    assert_ne!(parse("a or b and not c"), parse("(a or b) and not c"));
+    assert_eq!(parse("not a and b"), parse("(not a) and b"));
+    assert_eq!(parse("a or b or c"), parse("(a or b) or c"));
+
+    // Conditions next to each other are combined with `and`.
+    assert_eq!(parse("a b or c"), parse("(a and b) or c"));
+    assert_eq!(parse("A OR b AND NOT c"), parse("a or b and not c"));
+}
+
+#[test]
+fn error_columns() {
+    for (filter, column, message) in [
+        ("color:red", 1, "unknown field 'color'"),
+        ("tag~food", 4, "expected ':' after 'tag'"),
+        ("tag:", 5, "expected a value after 'tag:'"),
+        ("amount < abc", 10, "invalid amount 'abc'"),
+        ("date >= 2025-13", 9, "invalid date '2025-13'"),
+        ("status:paid", 8, "invalid status 'paid'"),
+        ("tag:food and", 13, "expected a condition"),
+        ("and tag:food", 1, "expected a condition before 'and'"),
+        ("tag:food ! work", 10, "unexpected '!'"),
+        ("(tag:food", 10, "expected ')'"),
+        ("tag:food)", 9, "unexpected ')'"),
+        ("desc~\"abc", 6, "the quote is never closed"),
+        ("desc~\"abc\\\"", 6, "the quote is never closed"),
+        // Columns count characters, not bytes.
+        ("desc~é and amount < x", 21, "invalid amount 'x'"),
+    ] {
+        let error = filter.parse::<Filter>().unwrap_err();
+
+        assert_eq!(error.column, column, "{filter}: {error}");
+        assert!(error.message.starts_with(message), "{filter}: {error}");
+    }
+}
+
+#[tokio::test]
+async fn quoted_values() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    add_transaction(&db, &account, "2025-01-01", "-10", "Say \"hi\" there").await;
+    add_transaction(&db, &account, "2025-01-02", "-10", "Say hi there").await;
+    add_transaction(&db, &account, "2025-01-03", "-10", "C:\\temp or not").await;
+
+    assert_eq!(
+        matching(&db, &account, r#"desc~"say \"hi\"""#).await,
+        ["Say \"hi\" there"]
+    );
+    assert_eq!(
+        matching(&db, &account, r#""say hi" or "\"hi\"""#).await,
+        ["Say \"hi\" there", "Say hi there"]
+    );
+    // Backslashes not followed by a quote are kept, and operators and keywords
+    // between quotes are values.
+    assert_eq!(
+        matching(&db, &account, r#"desc~"c:\temp or not""#).await,
+        ["C:\\temp or not"]
+    );
+}
+
+#[tokio::test]
+async fn date_granularities_and_comparisons() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    let dates = [
+        "2024-12-31T23:59:59Z",
+        "2025-01-01T00:00:00Z",
+        "2025-02-13T23:59:59Z",
+        "2025-02-14T00:00:00Z",
+        "2025-02-14T18:30:00Z",
+        "2025-02-15T00:00:00Z",
+        "2025-03-31T23:59:59Z",
+        "2025-04-01T00:00:00Z",
+        "2025-12-31T23:59:59Z",
+        "2026-01-01T00:00:00Z",
+    ];
+    for value in dates {
+        add_transaction(&db, &account, value, "-10", value).await;
+    }
+
+    // Periods with their first instant included and their last excluded.
+    for (period, start, end) in [
+        ("2025", "2025-01-01T00:00:00Z", "2026-01-01T00:00:00Z"),
+        ("2025-Q1", "2025-01-01T00:00:00Z", "2025-04-01T00:00:00Z"),
+        ("2025-q1", "2025-01-01T00:00:00Z", "2025-04-01T00:00:00Z"),
+        ("2025-02", "2025-02-01T00:00:00Z", "2025-03-01T00:00:00Z"),
+        ("2025-02-14", "2025-02-14T00:00:00Z", "2025-02-15T00:00:00Z"),
+    ] {
+        let (start, end) = (date(start), date(end));
+
+        for operator in [":", "=", "!=", "<", "<=", ">", ">="] {
+            let expected: Vec<&str> = dates
+                .into_iter()
+                .filter(|value| {
+                    let date = date(value);
+                    let during = start <= date && date < end;
+                    match operator {
+                        ":" | "=" => during,
+                        "!=" => !during,
+                        // Before the period.
+                        "<" => date < start,
+                        // Before the end of the period.
+                        "<=" => date < end,
+                        // After the period.
+                        ">" => date >= end,
+                        // From the start of the period.
+                        ">=" => date >= start,
+                        _ => unreachable!(),
+                    }
+                })
+                .collect();
+            let filter = format!("date {operator} {period}");
+
+            assert_eq!(matching(&db, &account, &filter).await, expected, "{filter}");
+        }
+    }
+}
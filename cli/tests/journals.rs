@@ -0,0 +1,477 @@
+//! Export of ledger, hledger and beancount journals, checked by parsing them back,
+//! and their import.
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{add_account, amount, date, tags};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    export::{JournalFormat, JournalOptions},
+    transaction::{Split, Status},
+    AddTransactionOptions, AddTransferOptions,
+};
+
+#[derive(Debug)]
+struct Posting {
+    account: String,
+    amount: Decimal,
+    commodity: String,
+    cost: Option<(Decimal, String)>,
+    comment: Option<String>,
+}
+
+#[derive(Debug)]
+struct Transaction {
+    date: chrono::NaiveDate,
+    header: String,
+    /// Comments of ledger journals, metadata of beancount files.
+    comments: Vec<String>,
+    postings: Vec<Posting>,
+}
+
+/// Accounts, commodities and transactions of a journal.
+#[derive(Debug, Default)]
+struct Parsed {
+    commodities: BTreeSet<String>,
+    /// Declared accounts, with their opening date in beancount files.
+    accounts: BTreeMap<String, Option<chrono::NaiveDate>>,
+    transactions: Vec<Transaction>,
+}
+
+impl Parsed {
+    fn check(&self) {
+        for transaction in &self.transactions {
+            let mut sums: BTreeMap<&str, Decimal> = BTreeMap::new();
+
+            for posting in &transaction.postings {
+                let opened = self
+                    .accounts
+                    .get(&posting.account)
+                    .unwrap_or_else(|| panic!("{} is not declared", posting.account));
+                assert!(opened.is_none_or(|opened| opened <= transaction.date));
+                assert!(self.commodities.contains(&posting.commodity));
+
+                match &posting.cost {
+                    Some((cost, commodity)) if posting.amount.is_sign_negative() => {
+                        *sums.entry(commodity).or_default() -= cost
+                    }
+                    Some((cost, commodity)) => *sums.entry(commodity).or_default() += cost,
+                    None => *sums.entry(&posting.commodity).or_default() += posting.amount,
+                }
+            }
+
+            assert!(
+                sums.values().all(Decimal::is_zero),
+                "unbalanced transaction: {transaction:?}"
+            );
+        }
+    }
+
+    fn balance(&self, account: &str) -> Decimal {
+        self.transactions
+            .iter()
+            .flat_map(|transaction| &transaction.postings)
+            .filter(|posting| posting.account == account)
+            .map(|posting| posting.amount)
+            .sum()
+    }
+
+    fn find(&self, header: &str) -> &Transaction {
+        self.transactions
+            .iter()
+            .find(|transaction| transaction.header.contains(header))
+            .unwrap_or_else(|| panic!("no transaction {header}"))
+    }
+}
+
+/// Parse a posting: the account, two spaces, the amount and its commodity, and
+/// an optional total cost.
+fn parse_posting(line: &str, comment: Option<&str>) -> Posting {
+    let (account, amount) = line.split_once("  ").expect("two spaces after the account");
+    let mut words = amount.split_whitespace();
+    let mut next = || words.next().expect("truncated posting").to_string();
+
+    let posting = Posting {
+        account: account.to_string(),
+        amount: next().parse().expect("valid amount"),
+        commodity: next(),
+        cost: match words.next() {
+            Some("@@") => Some((
+                words.next().unwrap().parse().unwrap(),
+                words.next().unwrap().to_string(),
+            )),
+            None => None,
+            Some(word) => panic!("unexpected {word}"),
+        },
+        comment: comment.map(str::to_string),
+    };
+    assert!(words.next().is_none());
+
+    posting
+}
+
+fn parse_date(value: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::parse_from_str(&value[..10], "%Y-%m-%d").expect("valid date")
+}
+
+fn parse_ledger(content: &str) -> Parsed {
+    let mut parsed = Parsed::default();
+
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        if let Some(commodity) = line.strip_prefix("commodity ") {
+            parsed.commodities.insert(commodity.to_string());
+        } else if let Some(account) = line.strip_prefix("account ") {
+            parsed.accounts.insert(account.to_string(), None);
+        } else if let Some(posting) = line.strip_prefix("    ") {
+            let transaction = parsed.transactions.last_mut().expect("a transaction");
+            match posting.strip_prefix("; ") {
+                Some(comment) => transaction.comments.push(comment.to_string()),
+                None => {
+                    let (posting, comment) = match posting.split_once("  ; ") {
+                        Some((posting, comment)) => (posting, Some(comment)),
+                        None => (posting, None),
+                    };
+                    transaction.postings.push(parse_posting(posting, comment));
+                }
+            }
+        } else {
+            parsed.transactions.push(Transaction {
+                date: parse_date(line),
+                header: line[10..].trim().to_string(),
+                comments: vec![],
+                postings: vec![],
+            });
+        }
+    }
+
+    parsed
+}
+
+/// Read the strings between double quotes of a beancount line.
+fn strings(line: &str) -> Vec<String> {
+    let mut strings = vec![];
+    let mut chars = line.chars();
+
+    while chars.by_ref().any(|c| c == '"') {
+        let mut string = String::new();
+        loop {
+            match chars.next().expect("unterminated string") {
+                '"' => break,
+                '\\' => string.push(chars.next().expect("escaped character")),
+                c => string.push(c),
+            }
+        }
+        strings.push(string);
+    }
+
+    strings
+}
+
+fn parse_beancount(content: &str) -> Parsed {
+    let mut parsed = Parsed::default();
+
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        if let Some(metadata) = line.strip_prefix("    ") {
+            let posting = parsed
+                .transactions
+                .last_mut()
+                .and_then(|transaction| transaction.postings.last_mut())
+                .expect("a posting");
+            posting.comment = Some(metadata.to_string());
+        } else if let Some(posting) = line.strip_prefix("  ") {
+            let transaction = parsed.transactions.last_mut().expect("a transaction");
+            if posting.starts_with(|c: char| c.is_ascii_lowercase()) {
+                transaction.comments.push(posting.to_string());
+            } else {
+                transaction.postings.push(parse_posting(posting, None));
+            }
+        } else {
+            let date = parse_date(line);
+            let mut words = line[10..].split_whitespace();
+
+            match words.next() {
+                Some("commodity") => {
+                    parsed.commodities.insert(words.next().unwrap().to_string());
+                }
+                Some("open") => {
+                    parsed
+                        .accounts
+                        .insert(words.next().unwrap().to_string(), Some(date));
+                }
+                Some("*") => parsed.transactions.push(Transaction {
+                    date,
+                    header: line[12..].to_string(),
+                    comments: vec![],
+                    postings: vec![],
+                }),
+                directive => panic!("unexpected directive {directive:?}"),
+            }
+        }
+    }
+
+    parsed
+}
+
+/// A store with three accounts, tagged, split and cleared transactions, and
+/// transfers between accounts of the same and of different currencies.
+async fn store() -> (common::Store, [RecordId; 3]) {
+    let db = common::open().await;
+    let main = add_account(&db, "main", "EUR").await;
+    let savings = add_account(&db, "savings account", "EUR").await;
+    let travel = add_account(&db, "travel", "usd").await;
+
+    let transactions = [
+        AddTransactionOptions {
+            amount: amount("1000"),
+            description: "Previous balance".to_string(),
+            date: Some(date("2024-12-15")),
+            ..Default::default()
+        },
+        AddTransactionOptions {
+            amount: amount("2500"),
+            description: "Salary January".to_string(),
+            tags: tags(&["salary"]),
+            date: Some(date("2025-01-03")),
+            payee: Some("ACME".to_string()),
+            status: Status::Reconciled,
+            ..Default::default()
+        },
+        AddTransactionOptions {
+            amount: amount("-950"),
+            description: "Rent".to_string(),
+            tags: tags(&["rent", "needs"]),
+            date: Some(date("2025-01-05")),
+            status: Status::Cleared,
+            fitid: Some("REF-1".to_string()),
+            ..Default::default()
+        },
+        AddTransactionOptions {
+            amount: amount("-30"),
+            description: "Market".to_string(),
+            splits: vec![
+                Split {
+                    amount: amount("-10"),
+                    tags: tags(&["food"]),
+                    memo: "bread".to_string(),
+                },
+                Split {
+                    amount: amount("-20"),
+                    tags: tags(&["home", "needs"]),
+                    memo: String::new(),
+                },
+            ],
+            date: Some(date("2025-01-06")),
+            ..Default::default()
+        },
+        AddTransactionOptions {
+            amount: amount("-45.5"),
+            description: "Dinner at \"Chez Paul\" \\ with friends".to_string(),
+            tags: tags(&["eating out"]),
+            date: Some(date("2025-01-07")),
+            ..Default::default()
+        },
+    ];
+    for options in transactions {
+        thunes_cli::add_transaction(&db, main.clone(), options)
+            .await
+            .unwrap();
+    }
+
+    for (to, amount, rate) in [
+        (savings.clone(), amount("500"), None),
+        (travel.clone(), amount("100"), Some(amount("1.1"))),
+    ] {
+        thunes_cli::add_transfer(
+            &db,
+            AddTransferOptions {
+                from: main.clone(),
+                to,
+                amount,
+                rate,
+                description: "Transfer".to_string(),
+                tags: vec![],
+                date: Some(date("2025-01-10")),
+                fitid: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    (db, [main, savings, travel])
+}
+
+async fn export(db: &Surreal<Db>, format: JournalFormat, options: &JournalOptions) -> String {
+    thunes_cli::export::export_journal(db, format, options)
+        .await
+        .unwrap()
+}
+
+async fn check_balances(db: &Surreal<Db>, parsed: &Parsed, accounts: &[RecordId; 3]) {
+    for (name, account) in ["Assets:Main", "Assets:Savings-account", "Assets:Travel"]
+        .into_iter()
+        .zip(accounts)
+    {
+        let balance = thunes_cli::balance(db, account.clone(), Default::default())
+            .await
+            .unwrap();
+        assert_eq!(parsed.balance(name), balance, "balance of {name}");
+    }
+}
+
+#[tokio::test]
+async fn ledger_journal() {
+    let (db, accounts) = store().await;
+    let parsed = parse_ledger(&export(&db, JournalFormat::Ledger, &Default::default()).await);
+
+    parsed.check();
+    check_balances(&db, &parsed, &accounts).await;
+    assert_eq!(
+        parsed.commodities,
+        BTreeSet::from(["EUR".to_string(), "USD".to_string()])
+    );
+    assert_eq!(parsed.transactions.len(), 7);
+
+    let salary = parsed.find("ACME");
+    assert_eq!(salary.header, "* ACME  ; Salary January");
+    assert_eq!(salary.postings[1].account, "Income:Uncategorized");
+
+    let rent = parsed.find("Rent");
+    assert_eq!(rent.header, "! Rent");
+    assert_eq!(rent.comments, vec![":rent:needs:", "fitid: REF-1"]);
+
+    let market = parsed.find("Market");
+    assert_eq!(market.postings.len(), 3);
+    assert_eq!(market.postings[2].comment.as_deref(), Some(":home:needs:"));
+
+    let travel = parsed
+        .transactions
+        .iter()
+        .flat_map(|transaction| &transaction.postings)
+        .find(|posting| posting.account == "Assets:Travel")
+        .unwrap();
+    assert_eq!(travel.amount, amount("110.00"));
+    assert_eq!(travel.cost, Some((amount("100.00"), "EUR".to_string())));
+}
+
+#[tokio::test]
+async fn hledger_journal() {
+    let (db, accounts) = store().await;
+    let parsed = parse_ledger(&export(&db, JournalFormat::Hledger, &Default::default()).await);
+
+    parsed.check();
+    check_balances(&db, &parsed, &accounts).await;
+
+    assert_eq!(parsed.find("ACME").header, "* ACME | Salary January");
+    assert_eq!(parsed.find("Rent").comments[0], "rent:, needs:");
+    assert_eq!(parsed.find("Dinner").comments[0], "eating-out:");
+}
+
+#[tokio::test]
+async fn beancount_journal() {
+    let (db, accounts) = store().await;
+    let parsed = parse_beancount(&export(&db, JournalFormat::Beancount, &Default::default()).await);
+
+    parsed.check();
+    check_balances(&db, &parsed, &accounts).await;
+    assert_eq!(
+        parsed.accounts.get("Assets:Main"),
+        Some(&chrono::NaiveDate::from_ymd_opt(2024, 12, 15))
+    );
+
+    let salary = parsed.find("ACME");
+    assert_eq!(strings(&salary.header), vec!["ACME", "Salary January"]);
+    assert_eq!(salary.comments, vec!["status: \"reconciled\""]);
+
+    let dinner = parsed.find("Dinner");
+    assert_eq!(
+        strings(&dinner.header),
+        vec!["Dinner at \"Chez Paul\" \\ with friends"]
+    );
+    assert!(dinner.header.ends_with(" #eating-out"));
+
+    let rent = parsed.find("Rent");
+    assert_eq!(
+        rent.comments,
+        vec!["status: \"cleared\"", "fitid: \"REF-1\""]
+    );
+}
+
+#[tokio::test]
+async fn tags_as_accounts_and_opening_balances() {
+    let (db, accounts) = store().await;
+    let options = JournalOptions {
+        tags_as_accounts: true,
+        accounts: [("rent".to_string(), "Expenses:Home:Rent".to_string())].into(),
+        start: Some(date("2025-01-01")),
+    };
+
+    for (format, parsed) in [
+        (
+            JournalFormat::Ledger,
+            parse_ledger(&export(&db, JournalFormat::Ledger, &options).await),
+        ),
+        (
+            JournalFormat::Beancount,
+            parse_beancount(&export(&db, JournalFormat::Beancount, &options).await),
+        ),
+    ] {
+        parsed.check();
+        check_balances(&db, &parsed, &accounts).await;
+
+        let opening = &parsed.transactions[0];
+        assert_eq!(
+            opening.date,
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            "{format:?}"
+        );
+        assert_eq!(opening.postings[0].amount, amount("1000.00"));
+        assert_eq!(opening.postings[1].account, "Equity:Opening-Balances");
+
+        assert_eq!(parsed.balance("Income:Salary"), amount("-2500.00"));
+        assert_eq!(parsed.balance("Expenses:Home:Rent"), amount("950.00"));
+        assert_eq!(parsed.balance("Expenses:Food"), amount("10.00"));
+        assert_eq!(parsed.balance("Expenses:Home"), amount("20.00"));
+        assert_eq!(parsed.balance("Expenses:Eating-out"), amount("45.50"));
+        assert_eq!(parsed.balance("Expenses:Uncategorized"), Decimal::ZERO);
+    }
+}
+
+#[tokio::test]
+async fn reimport_skips_entries_without_fitid() {
+    let db = common::open().await;
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("main.ledger");
+    std::fs::write(
+        &path,
+        "2025-01-03 Salary\n    Assets:Main  2500.00 EUR\n    Income:Salary\n\n\
+         2025-01-10 Savings\n    Assets:Savings  500.00 EUR\n    Assets:Main\n",
+    )
+    .unwrap();
+
+    let first = thunes_cli::import::journal::import_file(&db, &path)
+        .await
+        .unwrap();
+    assert_eq!(first.transfers_imported, 1);
+    assert_eq!(
+        first
+            .accounts
+            .iter()
+            .map(|report| report.transactions_imported)
+            .sum::<usize>(),
+        1
+    );
+
+    let second = thunes_cli::import::journal::import_file(&db, &path)
+        .await
+        .unwrap();
+    assert_eq!(second.transfers_imported, 0);
+    assert!(second
+        .accounts
+        .iter()
+        .all(|report| report.transactions_imported == 0));
+    assert_eq!(second.skipped.len(), 1, "{:?}", second.skipped);
+}
@@ -0,0 +1,78 @@
+//! Import of the account files of the first versions of the cli.
+
+mod common;
+
+use common::{amount, date, tags};
+use thunes_cli::import::legacy;
+
+fn example(path: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(path)
+}
+
+#[tokio::test]
+async fn import_and_reimport() {
+    let db = common::open().await;
+    let path = example("budget-planner/accounts/main");
+
+    let first = legacy::import_file(&db, &path).await.unwrap();
+    assert_eq!(first.account_name, "main");
+    assert!(first.account_created);
+    assert_eq!(first.transactions_imported, 5);
+    assert!(first.skipped.is_empty(), "{:?}", first.skipped);
+
+    let account = thunes_cli::get_account(&db, first.account_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(account.data.currency, "EUR");
+
+    let transactions =
+        thunes_cli::get_transactions(&db, first.account_id.clone(), Default::default())
+            .await
+            .unwrap();
+    let find = |description: &str| {
+        transactions
+            .iter()
+            .find(|transaction| transaction.inner.description == description)
+            .map(|transaction| &transaction.inner)
+            .unwrap()
+    };
+
+    // Dates are a year and the ordinal of the day in the year, spendings are negative,
+    // and empty tags are dropped.
+    let init = find("init");
+    assert_eq!(
+        (init.date, init.amount, init.tags.clone()),
+        (date("2023-06-03"), amount("1000"), vec![])
+    );
+    let rent = find("rent");
+    assert_eq!(
+        (rent.date, rent.amount, rent.tags.clone()),
+        (date("2024-06-16"), amount("-500"), tags(&["rent", "needs"]))
+    );
+    let cinema = find("Cinema");
+    assert_eq!(
+        (cinema.date, cinema.amount, cinema.tags.clone()),
+        (date("2024-06-16"), amount("-10"), tags(&["wants", "fun"]))
+    );
+
+    for label in ["rent", "needs", "wants", "food", "fun", "savings"] {
+        assert!(
+            first.tags_created.iter().any(|created| created == label),
+            "{label} in {:?}",
+            first.tags_created
+        );
+    }
+
+    let second = legacy::import_file(&db, &path).await.unwrap();
+    assert_eq!(second.account_id, first.account_id);
+    assert!(!second.account_created);
+    assert_eq!(second.transactions_imported, 0);
+    assert_eq!(second.skipped.len(), 5);
+
+    let balance = thunes_cli::balance(&db, first.account_id.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(balance, amount("175"));
+}
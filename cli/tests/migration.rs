@@ -0,0 +1,99 @@
+//! Migration of the data written by previous versions, and rounding of amounts to
+//! their currency.
+
+mod common;
+
+use common::amount;
+use rust_decimal::Decimal;
+use thunes_cli::money;
+
+#[test]
+fn round_to_minor_units() {
+    for (value, currency, rounded) in [
+        ("12.5", "EUR", "12.50"),
+        ("2.345", "EUR", "2.35"),
+        ("-2.345", "EUR", "-2.35"),
+        ("0.004", "EUR", "0.00"),
+        ("1234.5", "JPY", "1235"),
+        ("-1234.5", "jpy", "-1235"),
+        ("1.2345", "KWD", "1.235"),
+        ("0.123456789", "btc", "0.12345679"),
+        ("10", "XYZ", "10.00"),
+    ] {
+        assert_eq!(
+            money::round(amount(value), currency).to_string(),
+            rounded,
+            "{value} {currency}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn float_amounts_and_string_dates() {
+    let db = common::open_without_schema().await;
+
+    // Amounts were floats and dates were strings.
+    db.query(
+        r#"
+        CREATE account:eur SET name = 'eur', currency = 'EUR';
+        CREATE account:jpy SET name = 'jpy', currency = 'JPY';
+        CREATE account:btc SET name = 'btc', currency = 'BTC';
+        CREATE transaction:sum SET account = account:eur, amount = 0.1f + 0.2f, description = 'sum', date = '2024-01-01T00:00:00Z', tags = [];
+        CREATE transaction:spending SET account = account:eur, amount = -19.999f, description = 'spending', date = '2024-01-02T00:00:00Z', tags = [];
+        CREATE transaction:whole SET account = account:eur, amount = 1000f, description = 'whole', date = '2024-01-03T00:00:00Z', tags = [];
+        CREATE transaction:yen SET account = account:jpy, amount = 1234.5f, description = 'yen', date = '2024-01-04T00:00:00Z', tags = [];
+        CREATE transaction:bitcoin SET account = account:btc, amount = 0.123456789f, description = 'bitcoin', date = '2024-01-05T00:00:00Z', tags = [];
+        CREATE transaction:orphan SET account = account:deleted, amount = 1.005f, description = 'orphan', date = '2024-01-06T00:00:00Z', tags = [];
+        "#,
+    )
+    .await
+    .unwrap()
+    .check()
+    .unwrap();
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Migrated {
+        description: String,
+        amount: Decimal,
+        decimal: bool,
+        datetime: bool,
+    }
+
+    let expected = [
+        ("sum", "0.30"),
+        ("spending", "-20.00"),
+        ("whole", "1000.00"),
+        ("yen", "1235"),
+        ("bitcoin", "0.12345679"),
+        // Transactions of deleted accounts are rounded to two digits.
+        ("orphan", "1.01"),
+    ];
+
+    // Migrating again changes nothing.
+    for _ in 0..2 {
+        thunes_cli::migration::run(&db).await.unwrap();
+
+        let migrated: Vec<Migrated> = db
+            .query(
+                "SELECT description, amount, type::is::decimal(amount) AS decimal, type::is::datetime(date) AS datetime, date FROM transaction ORDER BY date",
+            )
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+
+        assert_eq!(migrated.len(), expected.len());
+        for (migrated, (description, amount)) in migrated.iter().zip(expected) {
+            assert_eq!(migrated.description, description);
+            // The scale of the amounts is the one of their currency.
+            assert_eq!(migrated.amount.to_string(), amount, "{description}");
+            assert!(migrated.decimal, "{description}");
+            assert!(migrated.datetime, "{description}");
+        }
+    }
+
+    let balance = thunes_cli::balance(&db, ("account", "eur").into(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(balance, amount("980.30"));
+}
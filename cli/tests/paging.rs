@@ -0,0 +1,335 @@
+//! Sorting and pagination of the transactions of an account, with their running
+//! balances.
+
+mod common;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use common::{add_account, add_transaction, amount, date, tags};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    page::{Sort, SortDirection, SortField},
+    transaction::{Status, TransactionWithId},
+    AddTransactionOptions, Error, GetTransactionOptions,
+};
+
+/// Transactions of an account, several of them on the same day and with the same
+/// amount or description.
+async fn seed(db: &Surreal<Db>) -> RecordId {
+    use Status::{Cleared, Reconciled, Uncleared};
+
+    let account = add_account(db, "main", "EUR").await;
+    let other = add_account(db, "other", "EUR").await;
+    add_transaction(db, &other, "2025-01-03", "-20", "Bakery").await;
+
+    for (day, value, description, payee, status, labels) in [
+        ("01-01", "1000", "Salary", "Employer", Reconciled, "salary"),
+        ("01-03", "-20", "bakery", "", Cleared, "food"),
+        ("01-03", "-20", "Bakery", "", Uncleared, "food"),
+        (
+            "01-03",
+            "-45",
+            "Supermarket",
+            "Carrefour",
+            Cleared,
+            "food home",
+        ),
+        ("01-03", "-20", "Cinema", "", Uncleared, "fun"),
+        ("01-05", "-800", "Rent", "Landlord", Reconciled, "home"),
+        ("01-05", "-12.5", "Lunch", "", Uncleared, ""),
+        ("01-05", "-12.5", "lunch", "cafe", Uncleared, "food"),
+        ("01-08", "-60", "Electricity", "EDF", Cleared, "home"),
+        ("01-08", "-20", "Cinema", "", Uncleared, "fun"),
+        ("01-10", "200", "Refund", "", Uncleared, ""),
+        ("01-10", "-5", "Coffee", "Cafe", Cleared, "food"),
+        ("01-12", "-30", "Books", "", Uncleared, "fun"),
+    ] {
+        thunes_cli::add_transaction(
+            db,
+            account.clone(),
+            AddTransactionOptions {
+                amount: amount(value),
+                description: description.to_string(),
+                tags: tags(&labels.split_whitespace().collect::<Vec<_>>()),
+                date: Some(date(&format!("2025-{day}"))),
+                payee: (!payee.is_empty()).then(|| payee.to_string()),
+                status,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    account
+}
+
+fn sort(field: SortField, direction: SortDirection) -> Sort {
+    Sort {
+        field,
+        sort: Some(direction),
+    }
+}
+
+/// Sort key of a transaction, compared like the database does.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Date(DateTime<Utc>),
+    Amount(Decimal),
+    Text(String),
+    Status(u8),
+}
+
+fn key(field: SortField, transaction: &TransactionWithId) -> Key {
+    let inner = &transaction.inner;
+
+    match field {
+        SortField::Date => Key::Date(inner.date),
+        SortField::Amount => Key::Amount(inner.amount),
+        SortField::Description => Key::Text(inner.description.to_lowercase()),
+        SortField::Tags => Key::Text(
+            inner
+                .tags
+                .iter()
+                .map(|tag| tag.label.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        SortField::Payee => Key::Text(inner.payee.clone().unwrap_or_default().to_lowercase()),
+        SortField::Status => Key::Status(match inner.status {
+            Status::Uncleared => 0,
+            Status::Cleared => 1,
+            Status::Reconciled => 2,
+        }),
+    }
+}
+
+/// Key of the id of a record, which breaks ties.
+fn id(transaction: &TransactionWithId) -> String {
+    transaction
+        .id
+        .key()
+        .to_string()
+        .trim_matches(['⟨', '⟩'])
+        .to_string()
+}
+
+/// Sort transactions by the columns of a sort, then by id.
+fn sorted(mut transactions: Vec<TransactionWithId>, sort: &[Sort]) -> Vec<RecordId> {
+    transactions.sort_by(|a, b| {
+        sort.iter()
+            .map(|column| {
+                let order = key(column.field, a).cmp(&key(column.field, b));
+                match column.sort {
+                    Some(SortDirection::Desc) => order.reverse(),
+                    _ => order,
+                }
+            })
+            .fold(std::cmp::Ordering::Equal, std::cmp::Ordering::then)
+            .then_with(|| id(a).cmp(&id(b)))
+    });
+
+    transactions
+        .into_iter()
+        .map(|transaction| transaction.id)
+        .collect()
+}
+
+fn ids(transactions: &[TransactionWithId]) -> Vec<RecordId> {
+    transactions
+        .iter()
+        .map(|transaction| transaction.id.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn pages_follow_each_other() {
+    let db = common::open().await;
+    let account = seed(&db).await;
+    let all = thunes_cli::get_transactions(&db, account.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 13);
+
+    use SortDirection::{Asc, Desc};
+    for sort in [
+        vec![],
+        vec![self::sort(SortField::Date, Desc)],
+        vec![
+            self::sort(SortField::Amount, Asc),
+            self::sort(SortField::Description, Desc),
+        ],
+        vec![self::sort(SortField::Description, Asc)],
+        vec![self::sort(SortField::Payee, Asc)],
+        vec![
+            self::sort(SortField::Status, Desc),
+            self::sort(SortField::Date, Asc),
+        ],
+        vec![self::sort(SortField::Tags, Asc)],
+    ] {
+        let columns = if sort.is_empty() {
+            vec![self::sort(SortField::Date, Asc)]
+        } else {
+            sort.clone()
+        };
+        let expected = sorted(all.clone(), &columns);
+
+        let unpaged = thunes_cli::get_transactions(
+            &db,
+            account.clone(),
+            GetTransactionOptions {
+                sort: sort.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(ids(&unpaged), expected, "{sort:?}");
+
+        for limit in [4, 5, 13] {
+            let mut paged = vec![];
+            let mut cursor = None;
+            let mut pages = 0;
+            loop {
+                let page = thunes_cli::get_transaction_page(
+                    &db,
+                    account.clone(),
+                    GetTransactionOptions {
+                        sort: sort.clone(),
+                        limit: Some(limit),
+                        cursor,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+                assert_eq!(page.total, 13, "{sort:?}");
+                assert!(page.transactions.len() <= limit);
+                paged.extend(ids(&page.transactions));
+                pages += 1;
+
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+
+            // No transaction is missing or repeated.
+            assert_eq!(paged, expected, "{sort:?} by {limit}");
+            assert_eq!(pages, 13_usize.div_ceil(limit), "{sort:?} by {limit}");
+        }
+    }
+
+    // Cursors only work with the sort they were made for.
+    let page = thunes_cli::get_transaction_page(
+        &db,
+        account.clone(),
+        GetTransactionOptions {
+            limit: Some(4),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        thunes_cli::get_transaction_page(
+            &db,
+            account.clone(),
+            GetTransactionOptions {
+                sort: vec![sort(SortField::Amount, Asc)],
+                limit: Some(4),
+                cursor: page.next_cursor,
+                ..Default::default()
+            },
+        )
+        .await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(matches!(
+        thunes_cli::get_transaction_page(
+            &db,
+            account.clone(),
+            GetTransactionOptions {
+                limit: Some(0),
+                ..Default::default()
+            },
+        )
+        .await,
+        Err(Error::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn running_balances_of_a_filtered_page() {
+    let db = common::open().await;
+    let account = seed(&db).await;
+
+    // Balances after each transaction, counting every transaction in date order, then
+    // by id for transactions of the same day.
+    let all = thunes_cli::get_transactions(&db, account.clone(), Default::default())
+        .await
+        .unwrap();
+    let amounts: HashMap<String, Decimal> = all
+        .iter()
+        .map(|transaction| (transaction.id.to_string(), transaction.inner.amount))
+        .collect();
+    let mut balance = Decimal::ZERO;
+    let balances: HashMap<String, Decimal> =
+        sorted(all.clone(), &[sort(SortField::Date, SortDirection::Asc)])
+            .into_iter()
+            .map(|id| {
+                let id = id.to_string();
+                balance += amounts[&id];
+                (id, balance)
+            })
+            .collect();
+    assert_eq!(balance, amount("155"));
+
+    let matching: Vec<&TransactionWithId> = all
+        .iter()
+        .filter(|transaction| {
+            transaction.inner.amount < Decimal::ZERO
+                && transaction.inner.date >= date("2025-01-03")
+                && transaction.inner.date <= date("2025-01-10")
+        })
+        .collect();
+    assert_eq!(matching.len(), 10);
+
+    for sort in [vec![], vec![sort(SortField::Amount, SortDirection::Desc)]] {
+        let options = |cursor| GetTransactionOptions {
+            start: Some(date("2025-01-03").into()),
+            end: Some(date("2025-01-10").into()),
+            filter: Some("amount < 0".to_string()),
+            sort: sort.clone(),
+            limit: Some(3),
+            cursor,
+            running_balance: Some(true),
+            ..Default::default()
+        };
+
+        let first = thunes_cli::get_transaction_page(&db, account.clone(), options(None))
+            .await
+            .unwrap();
+        let second =
+            thunes_cli::get_transaction_page(&db, account.clone(), options(first.next_cursor))
+                .await
+                .unwrap();
+        assert_eq!(second.total, matching.len());
+        assert_eq!(second.transactions.len(), 3);
+        assert_eq!(second.running_balances.len(), 3);
+
+        for (transaction, running) in second.transactions.iter().zip(&second.running_balances) {
+            assert!(matching
+                .iter()
+                .any(|matching| matching.id == transaction.id));
+            assert_eq!(
+                *running,
+                balances[&transaction.id.to_string()],
+                "{sort:?} {}",
+                transaction.inner.description
+            );
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Reports on the transactions of the accounts over periods of time.
+
+mod common;
+
+use common::{add_account, amount, date, tags};
+use rust_decimal::Decimal;
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    report::{
+        self, BalanceHistoryOptions, Baseline, CompareOptions, Comparison, Delta, ReportOptions,
+    },
+    schedule::Frequency,
+    transaction::Split,
+    AddTransactionOptions, AddTransferOptions,
+};
+
+/// Accounts of a store to report on.
+struct Accounts {
+    main: RecordId,
+    travel: RecordId,
+}
+
+async fn add_transaction(
+    db: &Surreal<Db>,
+    account: &RecordId,
+    day: &str,
+    value: &str,
+    labels: &[&str],
+) {
+    thunes_cli::add_transaction(
+        db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount(value),
+            description: format!("{value} on {day}"),
+            tags: tags(labels),
+            date: Some(date(day)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+}
+
+/// Transactions over 2024 and the first quarter of 2025, in two currencies, with a
+/// split transaction, untagged transactions and a transfer.
+async fn seed(db: &Surreal<Db>) -> Accounts {
+    let main = add_account(db, "main", "EUR").await;
+    let savings = add_account(db, "savings", "EUR").await;
+    let travel = add_account(db, "travel", "USD").await;
+
+    for (day, value, labels) in [
+        ("2024-01-10", "-80", &["food"][..]),
+        ("2024-02-10", "-50", &["home"]),
+        ("2024-10-15", "-120", &["food"]),
+        ("2024-11-15", "1800", &["salary"]),
+        ("2024-12-20", "500", &[]),
+        ("2025-01-06", "2000", &["salary"]),
+        ("2025-01-08", "-100", &["food"]),
+        // On sunday, the last day of the week.
+        ("2025-01-12T23:30:00Z", "-10", &[]),
+        ("2025-01-20", "-30", &[]),
+        ("2025-02-03", "-60", &["food"]),
+        ("2025-02-10", "100", &[]),
+        ("2025-03-05", "-200", &["home"]),
+        ("2025-03-20", "-45", &["food", "fun"]),
+    ] {
+        add_transaction(db, &main, day, value, labels).await;
+    }
+
+    thunes_cli::add_transaction(
+        db,
+        main.clone(),
+        AddTransactionOptions {
+            amount: amount("-150"),
+            description: "Supermarket".to_string(),
+            splits: vec![
+                Split {
+                    amount: amount("-90"),
+                    tags: tags(&["food"]),
+                    memo: String::new(),
+                },
+                Split {
+                    amount: amount("-40"),
+                    tags: tags(&["home"]),
+                    memo: String::new(),
+                },
+                Split {
+                    amount: amount("-20"),
+                    tags: vec![],
+                    memo: String::new(),
+                },
+            ],
+            date: Some(date("2025-01-15")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    thunes_cli::add_transfer(
+        db,
+        AddTransferOptions {
+            from: main.clone(),
+            to: savings,
+            amount: amount("300"),
+            rate: None,
+            description: "Savings".to_string(),
+            tags: vec![],
+            date: Some(date("2025-02-14")),
+            fitid: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    add_transaction(db, &travel, "2024-01-12", "-25", &["food"]).await;
+    add_transaction(db, &travel, "2025-01-10", "-40", &["food"]).await;
+
+    Accounts { main, travel }
+}
+
+#[tokio::test]
+async fn weekly_balance_history() {
+    let db = common::open().await;
+    let accounts = seed(&db).await;
+
+    let history = |account| {
+        report::balance_history(
+            &db,
+            BalanceHistoryOptions {
+                account,
+                period: Frequency::Weekly,
+                start: date("2025-01-01"),
+                end: Some(date("2025-01-31")),
+                filter: None,
+            },
+        )
+    };
+
+    // Weeks start on monday, the first one before the start of the report. The
+    // balance of the first week counts the transactions before it.
+    let histories = history(Some(accounts.main.clone())).await.unwrap();
+    let [main] = &histories[..] else {
+        panic!("{histories:?}");
+    };
+    assert_eq!(main.currency, "EUR");
+    let points: Vec<_> = main
+        .points
+        .iter()
+        .map(|point| (point.period_start, point.period_end, point.balance))
+        .collect();
+    assert_eq!(
+        points,
+        [
+            (date("2024-12-30"), date("2025-01-06"), amount("2050")),
+            (date("2025-01-06"), date("2025-01-13"), amount("3940")),
+            (date("2025-01-13"), date("2025-01-20"), amount("3790")),
+            (date("2025-01-20"), date("2025-01-27"), amount("3760")),
+            (date("2025-01-27"), date("2025-02-03"), amount("3760")),
+        ]
+    );
+
+    // Accounts are grouped by currency.
+    let histories = history(None).await.unwrap();
+    let balances = |currency: &str| -> Vec<Decimal> {
+        histories
+            .iter()
+            .find(|history| history.currency == currency)
+            .unwrap()
+            .points
+            .iter()
+            .map(|point| point.balance)
+            .collect()
+    };
+    assert_eq!(histories.len(), 2);
+    assert_eq!(
+        balances("EUR"),
+        ["2050", "3940", "3790", "3760", "3760"].map(amount)
+    );
+    assert_eq!(
+        balances("USD"),
+        ["-25", "-65", "-65", "-65", "-65"].map(amount)
+    );
+
+    let histories = history(Some(accounts.travel.clone())).await.unwrap();
+    assert_eq!(histories.len(), 1);
+    assert_eq!(histories[0].currency, "USD");
+
+    // Filters apply to the opening balance too, split transactions count whole.
+    let history = report::balance_history(
+        &db,
+        BalanceHistoryOptions {
+            account: Some(accounts.main.clone()),
+            period: Frequency::Monthly,
+            start: date("2025-01-01"),
+            end: Some(date("2025-03-31")),
+            filter: Some("tag:food".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    let balances: Vec<Decimal> = history[0]
+        .points
+        .iter()
+        .map(|point| point.balance)
+        .collect();
+    assert_eq!(balances, ["-450", "-510", "-555"].map(amount));
+}
+
+#[tokio::test]
+async fn cash_flow_cut_to_the_dates() {
+    let db = common::open().await;
+    seed(&db).await;
+
+    let flows = report::cash_flow(
+        &db,
+        ReportOptions {
+            start: date("2025-01-10"),
+            end: Some(date("2025-03-15")),
+            ..Default::default()
+        },
+        Frequency::Monthly,
+    )
+    .await
+    .unwrap();
+    let flow = |currency: &str| {
+        let flow = flows.iter().find(|flow| flow.currency == currency).unwrap();
+        let periods: Vec<_> = flow
+            .periods
+            .iter()
+            .map(|period| {
+                (
+                    period.period_start,
+                    period.period_end,
+                    period.income,
+                    period.expenses,
+                    period.net,
+                )
+            })
+            .collect();
+        let total = &flow.total;
+
+        (
+            periods,
+            (
+                total.period_start,
+                total.period_end,
+                total.income,
+                total.expenses,
+                total.net,
+            ),
+        )
+    };
+
+    // The first and last months are cut to the dates of the report, and transfers are
+    // neither incomes nor expenses.
+    let (periods, total) = flow("EUR");
+    assert_eq!(
+        periods,
+        [
+            (
+                date("2025-01-10"),
+                date("2025-02-01"),
+                amount("0"),
+                amount("-190"),
+                amount("-190")
+            ),
+            (
+                date("2025-02-01"),
+                date("2025-03-01"),
+                amount("100"),
+                amount("-60"),
+                amount("40")
+            ),
+            (
+                date("2025-03-01"),
+                date("2025-03-15"),
+                amount("0"),
+                amount("-200"),
+                amount("-200")
+            ),
+        ]
+    );
+    assert_eq!(
+        total,
+        (
+            date("2025-01-10"),
+            date("2025-03-15"),
+            amount("100"),
+            amount("-450"),
+            amount("-350")
+        )
+    );
+
+    let (periods, total) = flow("USD");
+    let expenses: Vec<Decimal> = periods.iter().map(|period| period.3).collect();
+    assert_eq!(expenses, ["-40", "0", "0"].map(amount));
+    assert_eq!(total.3, amount("-40"));
+}
+
+#[tokio::test]
+async fn spending_by_tag_with_split_transactions() {
+    let db = common::open().await;
+    let accounts = seed(&db).await;
+    let options = ReportOptions {
+        start: date("2025-01-01"),
+        end: Some(date("2025-04-01")),
+        ..Default::default()
+    };
+
+    let spendings = report::spending_by_tag(&db, options.clone()).await.unwrap();
+    let eur = spendings
+        .iter()
+        .find(|spending| spending.currency == "EUR")
+        .unwrap();
+    assert_eq!(eur.total, amount("595"));
+
+    // Lines of split transactions count for their own tags, untagged lines and
+    // transactions are gathered. A transaction with two tags counts for both.
+    let tags: Vec<(Option<&str>, Decimal, Decimal)> = eur
+        .tags
+        .iter()
+        .map(|tag| (tag.tag.as_deref(), tag.spent, tag.percentage))
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            (Some("food"), amount("295"), amount("49.58")),
+            (Some("home"), amount("240"), amount("40.34")),
+            (None, amount("60"), amount("10.08")),
+            (Some("fun"), amount("45"), amount("7.56")),
+        ]
+    );
+
+    let usd = spendings
+        .iter()
+        .find(|spending| spending.currency == "USD")
+        .unwrap();
+    assert_eq!(usd.total, amount("40"));
+    assert_eq!(usd.tags.len(), 1);
+    assert_eq!(usd.tags[0].percentage, amount("100"));
+
+    // Only the accounts of the report are counted.
+    let spendings = report::spending_by_tag(
+        &db,
+        ReportOptions {
+            accounts: vec![accounts.travel.clone()],
+            ..options
+        },
+    )
+    .await
+    .unwrap();
+    let currencies: Vec<&str> = spendings
+        .iter()
+        .map(|spending| spending.currency.as_str())
+        .collect();
+    assert_eq!(currencies, ["USD"]);
+}
+
+/// Current, previous, change and percentage of a delta.
+type Values = (Decimal, Decimal, Decimal, Option<Decimal>);
+
+fn delta(delta: &Delta) -> Values {
+    (
+        delta.current,
+        delta.previous,
+        delta.change,
+        delta.percentage,
+    )
+}
+
+fn tag_deltas(comparison: &Comparison) -> Vec<(Option<&str>, Values)> {
+    comparison
+        .tags
+        .iter()
+        .map(|tag| (tag.tag.as_deref(), delta(&tag.spent)))
+        .collect()
+}
+
+async fn compare(db: &Surreal<Db>, start: &str, end: &str, baseline: Baseline) -> Vec<Comparison> {
+    report::compare(
+        db,
+        CompareOptions {
+            report: ReportOptions {
+                start: date(start),
+                end: Some(date(end)),
+                ..Default::default()
+            },
+            baseline,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn compare_with_the_previous_quarter() {
+    let db = common::open().await;
+    let accounts = seed(&db).await;
+
+    let comparisons = compare(&db, "2025-01-01", "2025-04-01", Baseline::PreviousPeriod).await;
+    let [eur, usd] = &comparisons[..] else {
+        panic!("{comparisons:?}");
+    };
+
+    // A quarter is compared with the previous quarter.
+    assert_eq!(eur.currency, "EUR");
+    assert_eq!(
+        (eur.baseline_start, eur.baseline_end),
+        (date("2024-10-01"), date("2025-01-01"))
+    );
+    assert_eq!(
+        delta(&eur.spent),
+        (
+            amount("595"),
+            amount("120"),
+            amount("475"),
+            Some(amount("395.83"))
+        )
+    );
+    assert_eq!(
+        tag_deltas(eur),
+        [
+            (
+                Some("food"),
+                (
+                    amount("295"),
+                    amount("120"),
+                    amount("175"),
+                    Some(amount("145.83"))
+                )
+            ),
+            (
+                Some("home"),
+                (amount("240"), amount("0"), amount("240"), None)
+            ),
+            (None, (amount("60"), amount("0"), amount("60"), None)),
+            (Some("fun"), (amount("45"), amount("0"), amount("45"), None)),
+        ]
+    );
+
+    // The savings account only has a transfer, which is neither an income nor a
+    // spending.
+    let [main] = &eur.accounts[..] else {
+        panic!("{:?}", eur.accounts);
+    };
+    assert_eq!(
+        (&main.account, main.name.as_str()),
+        (&accounts.main, "main")
+    );
+    assert_eq!(
+        delta(&main.income),
+        (
+            amount("2100"),
+            amount("2300"),
+            amount("-200"),
+            Some(amount("-8.70"))
+        )
+    );
+    assert_eq!(
+        delta(&main.spent),
+        (
+            amount("595"),
+            amount("120"),
+            amount("475"),
+            Some(amount("395.83"))
+        )
+    );
+
+    assert_eq!(usd.currency, "USD");
+    assert_eq!(
+        delta(&usd.spent),
+        (amount("40"), amount("0"), amount("40"), None)
+    );
+    assert_eq!(usd.accounts.len(), 1);
+    assert_eq!(usd.accounts[0].account, accounts.travel);
+
+    // Ranges of days are compared with the days right before.
+    let comparisons = compare(&db, "2025-01-10", "2025-01-20", Baseline::PreviousPeriod).await;
+    assert_eq!(
+        (comparisons[0].baseline_start, comparisons[0].baseline_end),
+        (date("2024-12-31"), date("2025-01-10"))
+    );
+}
+
+#[tokio::test]
+async fn compare_with_the_previous_year() {
+    let db = common::open().await;
+    seed(&db).await;
+
+    let comparisons = compare(&db, "2025-01-01", "2025-03-01", Baseline::PreviousYear).await;
+    let [eur, usd] = &comparisons[..] else {
+        panic!("{comparisons:?}");
+    };
+
+    assert_eq!(
+        (eur.baseline_start, eur.baseline_end),
+        (date("2024-01-01"), date("2024-03-01"))
+    );
+    assert_eq!(
+        delta(&eur.spent),
+        (
+            amount("350"),
+            amount("130"),
+            amount("220"),
+            Some(amount("169.23"))
+        )
+    );
+    assert_eq!(
+        tag_deltas(eur),
+        [
+            (
+                Some("food"),
+                (
+                    amount("250"),
+                    amount("80"),
+                    amount("170"),
+                    Some(amount("212.50"))
+                )
+            ),
+            (None, (amount("60"), amount("0"), amount("60"), None)),
+            (
+                Some("home"),
+                (
+                    amount("40"),
+                    amount("50"),
+                    amount("-10"),
+                    Some(amount("-20.00"))
+                )
+            ),
+        ]
+    );
+    assert_eq!(
+        delta(&eur.accounts[0].income),
+        (amount("2100"), amount("0"), amount("2100"), None)
+    );
+
+    assert_eq!(
+        delta(&usd.spent),
+        (
+            amount("40"),
+            amount("25"),
+            amount("15"),
+            Some(amount("60.00"))
+        )
+    );
+}
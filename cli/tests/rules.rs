@@ -0,0 +1,250 @@
+//! Rules tagging and cleaning up transactions.
+
+mod common;
+
+use common::{add_account, add_transaction, amount, date, tags};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{
+    rule::{self, Actions, AddRuleOptions, Change, Conditions, Rewrite, RunRulesOptions},
+    transaction::Transaction,
+    AddTransactionOptions, AddTransferOptions, Error,
+};
+
+async fn add_rule(
+    db: &Surreal<Db>,
+    name: &str,
+    priority: i64,
+    conditions: Conditions,
+    actions: Actions,
+) {
+    rule::add_rule(
+        db,
+        AddRuleOptions {
+            name: name.to_string(),
+            enabled: true,
+            priority,
+            conditions,
+            actions,
+        },
+    )
+    .await
+    .unwrap();
+}
+
+fn matching(pattern: &str) -> Conditions {
+    Conditions {
+        description_matches: Some(pattern.to_string()),
+        ..Default::default()
+    }
+}
+
+fn adding(labels: &[&str]) -> Actions {
+    Actions {
+        add_tags: labels.iter().map(|label| label.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// Transactions of an account, sorted by date.
+async fn transactions(db: &Surreal<Db>, account: &RecordId) -> Vec<Transaction> {
+    let mut transactions: Vec<Transaction> =
+        thunes_cli::get_transactions(db, account.clone(), Default::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|transaction| transaction.inner)
+            .collect();
+    transactions.sort_by_key(|transaction| transaction.date);
+
+    transactions
+}
+
+fn labels(transaction: &Transaction) -> Vec<&str> {
+    transaction
+        .tags
+        .iter()
+        .map(|tag| tag.label.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn applied_by_priority() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+
+    assert!(matches!(
+        rule::add_rule(
+            &db,
+            AddRuleOptions {
+                name: "invalid".to_string(),
+                enabled: true,
+                priority: 0,
+                conditions: matching("(unclosed"),
+                actions: adding(&["never"]),
+            }
+        )
+        .await,
+        Err(Error::Invalid(_))
+    ));
+
+    // Added first, but applied last: the description it matches no longer exists.
+    add_rule(&db, "card", 3, matching("^CB "), adding(&["card"])).await;
+    // Only matches the description written by the rewrite.
+    add_rule(
+        &db,
+        "groceries",
+        2,
+        matching("^Carrefour"),
+        Actions {
+            set_payee: Some("Carrefour".to_string()),
+            ..adding(&["groceries"])
+        },
+    )
+    .await;
+    add_rule(
+        &db,
+        "clean up",
+        1,
+        matching("^CB "),
+        Actions {
+            rewrite_description: Some(Rewrite {
+                pattern: r"^CB (?<shop>\w)(\w*) \d+ (\w+)$".to_string(),
+                replacement: "${shop}${2} ($3)".to_string(),
+            }),
+            ..Default::default()
+        },
+    )
+    .await;
+    rule::add_rule(
+        &db,
+        AddRuleOptions {
+            name: "disabled".to_string(),
+            enabled: false,
+            priority: 0,
+            conditions: matching("."),
+            actions: adding(&["disabled"]),
+        },
+    )
+    .await
+    .unwrap();
+
+    thunes_cli::add_transaction(
+        &db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount("-42"),
+            description: "CB Carrefour 1234 PARIS".to_string(),
+            tags: tags(&["shopping"]),
+            date: Some(date("2025-01-05")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let transactions = transactions(&db, &account).await;
+    let [transaction] = &transactions[..] else {
+        panic!("{transactions:?}");
+    };
+    assert_eq!(transaction.description, "Carrefour (PARIS)");
+    assert_eq!(transaction.payee.as_deref(), Some("Carrefour"));
+    assert_eq!(labels(transaction), ["shopping", "groceries"]);
+
+    // Tags added by rules are created.
+    let tags = thunes_cli::get_tags(&db).await.unwrap();
+    assert!(tags.iter().any(|tag| tag.label == "groceries"));
+}
+
+#[tokio::test]
+async fn run_over_existing_transactions() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    let savings = add_account(&db, "savings", "EUR").await;
+    thunes_cli::add_transaction(
+        &db,
+        account.clone(),
+        AddTransactionOptions {
+            amount: amount("-30"),
+            description: "PAYPAL *NETFLIX".to_string(),
+            tags: tags(&["fun"]),
+            date: Some(date("2025-01-01")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    add_transaction(&db, &account, "2025-01-02", "-20", "Bakery").await;
+    thunes_cli::add_transfer(
+        &db,
+        AddTransferOptions {
+            from: account.clone(),
+            to: savings.clone(),
+            amount: amount("100"),
+            rate: None,
+            description: "PAYPAL *SAVINGS".to_string(),
+            tags: vec![],
+            date: Some(date("2025-01-03")),
+            fitid: None,
+        },
+    )
+    .await
+    .unwrap();
+    let before = transactions(&db, &account).await;
+
+    add_rule(
+        &db,
+        "paypal",
+        1,
+        matching(r"^PAYPAL \*"),
+        Actions {
+            rewrite_description: Some(Rewrite {
+                pattern: r"^PAYPAL \*(\w+)".to_string(),
+                replacement: "$1".to_string(),
+            }),
+            ..adding(&["fun", "subscriptions"])
+        },
+    )
+    .await;
+
+    let run = |dry_run| {
+        rule::run_rules(
+            &db,
+            RunRulesOptions {
+                account: Some(account.clone()),
+                dry_run,
+            },
+        )
+    };
+
+    // Transfers are left alone, and only the tags the transaction did not have are
+    // reported as added.
+    let changes = run(true).await.unwrap();
+    let [change] = &changes[..] else {
+        panic!("{changes:?}");
+    };
+    assert_eq!(change.rules, ["paypal"]);
+    assert!(matches!(
+        &change.description,
+        Some(Change { before, after }) if before == "PAYPAL *NETFLIX" && after == "NETFLIX"
+    ));
+    assert!(change.payee.is_none());
+    assert_eq!(change.added_tags, ["subscriptions"]);
+
+    // A dry run writes nothing.
+    let after = transactions(&db, &account).await;
+    assert_eq!(
+        serde_json::to_value(&after).unwrap(),
+        serde_json::to_value(&before).unwrap()
+    );
+
+    let changes = run(false).await.unwrap();
+    assert_eq!(changes.len(), 1);
+    let after = transactions(&db, &account).await;
+    assert_eq!(after[0].description, "NETFLIX");
+    assert_eq!(labels(&after[0]), ["fun", "subscriptions"]);
+    assert_eq!(after[1].description, "Bakery");
+    assert_eq!(after[2].description, "PAYPAL *SAVINGS");
+
+    // Rules changed nothing left to change.
+    assert!(run(false).await.unwrap().is_empty());
+}
@@ -0,0 +1,78 @@
+//! Dates of the occurrences of recurrences.
+
+mod common;
+
+use chrono::{DateTime, Utc, Weekday};
+use common::date;
+use thunes_cli::schedule::{Frequency, NthWeekday, Recurrence};
+
+fn fifth_friday(count: Option<u32>, until: Option<DateTime<Utc>>) -> Recurrence {
+    Recurrence {
+        frequency: Frequency::Monthly,
+        interval: 1,
+        nth_weekday: Some(NthWeekday {
+            nth: 5,
+            weekday: Weekday::Fri,
+        }),
+        until,
+        count,
+    }
+}
+
+#[test]
+fn months_without_the_nth_weekday_are_skipped() {
+    // January 2025 has five fridays, february, march and april do not.
+    let recurrence = fifth_friday(None, None);
+    let start = date("2025-01-01");
+
+    let occurrences: Vec<DateTime<Utc>> = recurrence.occurrences(start, 0).take(3).collect();
+    assert_eq!(
+        occurrences,
+        [date("2025-01-31"), date("2025-05-30"), date("2025-08-29")]
+    );
+    assert_eq!(recurrence.occurrence(start, 1), Some(date("2025-05-30")));
+    assert_eq!(
+        recurrence.occurrences(start, 2).next(),
+        Some(date("2025-08-29"))
+    );
+}
+
+#[test]
+fn count_and_until_apply_to_occurrences() {
+    let start = date("2025-01-01");
+
+    let counted: Vec<DateTime<Utc>> = fifth_friday(Some(2), None).occurrences(start, 0).collect();
+    assert_eq!(counted, [date("2025-01-31"), date("2025-05-30")]);
+    assert_eq!(fifth_friday(Some(2), None).occurrence(start, 2), None);
+
+    let until: Vec<DateTime<Utc>> = fifth_friday(None, Some(date("2025-08-01")))
+        .occurrences(start, 0)
+        .collect();
+    assert_eq!(until, [date("2025-01-31"), date("2025-05-30")]);
+}
+
+#[test]
+fn last_weekday_and_day_of_month() {
+    let last_friday = Recurrence {
+        nth_weekday: Some(NthWeekday {
+            nth: -1,
+            weekday: Weekday::Fri,
+        }),
+        ..fifth_friday(Some(3), None)
+    };
+    let occurrences: Vec<DateTime<Utc>> = last_friday.occurrences(date("2025-01-01"), 0).collect();
+    assert_eq!(
+        occurrences,
+        [date("2025-01-31"), date("2025-02-28"), date("2025-03-28")]
+    );
+
+    let on_the_31st = Recurrence {
+        nth_weekday: None,
+        ..fifth_friday(Some(3), None)
+    };
+    let occurrences: Vec<DateTime<Utc>> = on_the_31st.occurrences(date("2025-01-31"), 0).collect();
+    assert_eq!(
+        occurrences,
+        [date("2025-01-31"), date("2025-02-28"), date("2025-03-31")]
+    );
+}
@@ -0,0 +1,176 @@
+//! Import of camt.053, MT940, OFX and QIF statements, checked against the fixtures.
+
+mod common;
+
+use common::{add_account, amount, date};
+use thunes_cli::import::{camt, mt940, ofx, qif, Statement};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn read(name: &str, read: fn(&[u8]) -> Result<Statement, String>) -> Statement {
+    read(&std::fs::read(fixture(name)).unwrap()).unwrap()
+}
+
+#[test]
+fn camt053_entries() {
+    let statement = read("camt053.xml", camt::read);
+
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+    assert_eq!(
+        statement.balances,
+        vec![(amount("2334.56"), date("2025-01-31"))]
+    );
+    assert_eq!(statement.transactions.len(), 4);
+
+    let salary = &statement.transactions[0];
+    assert_eq!(salary.amount, amount("2500.00"));
+    assert_eq!(salary.description, "Salary January 2025");
+    assert_eq!(salary.date, Some(date("2025-01-03")));
+    assert_eq!(salary.value_date, Some(date("2025-01-02")));
+    assert_eq!(salary.fitid.as_deref(), Some("REF-0001"));
+    assert_eq!(salary.payee.as_deref(), Some("ACME Corporation"));
+    assert_eq!(salary.payee_iban.as_deref(), Some("DE89370400440532013000"));
+
+    let rent = &statement.transactions[1];
+    assert_eq!(rent.amount, amount("-950.00"));
+    assert_eq!(rent.description, "Rent January");
+    assert_eq!(rent.value_date, None);
+    assert_eq!(rent.payee.as_deref(), Some("Landlord & Co"));
+    assert_eq!(
+        rent.payee_iban.as_deref(),
+        Some("FR1420041010050500013M02606")
+    );
+}
+
+#[test]
+fn camt053_batch_and_pending_entries() {
+    let statement = read("camt053.xml", camt::read);
+
+    let power = &statement.transactions[2];
+    assert_eq!(power.amount, amount("-65.44"));
+    assert_eq!(power.description, "RF18539007547034");
+    assert_eq!(power.fitid.as_deref(), Some("REF-0003-A"));
+    assert_eq!(power.payee.as_deref(), Some("Power Company"));
+
+    let insurance = &statement.transactions[3];
+    assert_eq!(insurance.amount, amount("-150.00"));
+    assert_eq!(insurance.fitid.as_deref(), Some("REF-0003-B"));
+
+    assert_eq!(statement.skipped.len(), 1);
+    assert!(statement.skipped[0].contains("REF-0004"));
+}
+
+#[test]
+fn mt940_entries() {
+    let statement = read("mt940.sta", mt940::read);
+
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+    assert_eq!(
+        statement.balances,
+        vec![(amount("2334.56"), date("2025-01-20"))]
+    );
+    assert_eq!(statement.transactions.len(), 3);
+
+    // Structured information of German banks.
+    let salary = &statement.transactions[0];
+    assert_eq!(salary.amount, amount("2500.00"));
+    assert_eq!(salary.description, "Salary January 2025");
+    assert_eq!(salary.date, Some(date("2025-01-03")));
+    assert_eq!(salary.value_date, Some(date("2025-01-02")));
+    assert_eq!(salary.fitid.as_deref(), Some("BREF0001"));
+    assert_eq!(salary.payee.as_deref(), Some("ACME Corporation"));
+    assert_eq!(salary.payee_iban.as_deref(), Some("DE89370400440532013000"));
+
+    // Structured information of Dutch banks.
+    let rent = &statement.transactions[1];
+    assert_eq!(rent.amount, amount("-950.00"));
+    assert_eq!(rent.description, "Rent January");
+    assert_eq!(rent.value_date, None);
+    assert_eq!(rent.payee.as_deref(), Some("Landlord"));
+    assert_eq!(rent.payee_iban.as_deref(), Some("NL91ABNA0417164300"));
+
+    // Free text, and a booking date in the previous year.
+    let fee = &statement.transactions[2];
+    assert_eq!(fee.amount, amount("-215.44"));
+    assert_eq!(fee.description, "Account maintenance fee");
+    assert_eq!(fee.date, Some(date("2024-12-31")));
+    assert_eq!(fee.value_date, Some(date("2025-01-01")));
+    assert_eq!(fee.fitid, None);
+}
+
+#[test]
+fn ofx_sgml_empty_elements() {
+    let statement = read("sgml.ofx", ofx::read);
+
+    assert_eq!(statement.currency.as_deref(), Some("EUR"));
+    assert_eq!(
+        statement.balances,
+        vec![(amount("2457.90"), date("2025-01-31"))]
+    );
+    assert_eq!(statement.transactions.len(), 2);
+
+    // An empty name is a value, not an element holding the following ones.
+    let grocery = &statement.transactions[0];
+    assert_eq!(grocery.amount, amount("-42.10"));
+    assert_eq!(grocery.description, "Card payment GROCERY");
+    assert_eq!(grocery.fitid.as_deref(), Some("OFX-0001"));
+    assert_eq!(grocery.payee, None);
+
+    let salary = &statement.transactions[1];
+    assert_eq!(salary.amount, amount("2500.00"));
+    assert_eq!(salary.description, "ACME Corporation Salary January 2025");
+    assert_eq!(salary.payee.as_deref(), Some("ACME Corporation"));
+}
+
+#[test]
+fn qif_decimal_comma_amounts() {
+    let amounts = |content: &str| {
+        qif::read(content.as_bytes())
+            .unwrap()
+            .transactions
+            .into_iter()
+            .map(|transaction| transaction.amount)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        amounts("!Type:Bank\nD01/02/2025\nT-12,50\n^\nD01/03/2025\nT1,234\n^\n"),
+        vec![amount("-12.50"), amount("1234")]
+    );
+    assert_eq!(
+        amounts("!Type:Bank\nD01/02/2025\nT-1.234,50\n^\nD01/03/2025\nT-12,5\n^\n"),
+        vec![amount("-1234.50"), amount("-12.5")]
+    );
+    assert_eq!(
+        amounts("!Type:Bank\nD01/02/2025\nT1,234.50\n^\n"),
+        vec![amount("1234.50")]
+    );
+}
+
+#[tokio::test]
+async fn reimport_skips_known_references() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+
+    let first = camt::import_file(&db, account.clone(), &fixture("camt053.xml"))
+        .await
+        .unwrap();
+    assert_eq!(first.report.transactions_imported, 4);
+    assert_eq!(first.checkpoints.len(), 1);
+
+    let second = camt::import_file(&db, account.clone(), &fixture("camt053.xml"))
+        .await
+        .unwrap();
+    assert_eq!(second.report.transactions_imported, 0);
+    assert!(second.checkpoints.is_empty());
+
+    let balance = thunes_cli::balance(&db, account.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(balance, amount("1334.56"));
+}
@@ -0,0 +1,129 @@
+//! Transfers between accounts, as pairs of linked transactions.
+
+mod common;
+
+use common::{add_account, add_transaction, amount, date};
+use thunes_cli::{transaction::Status, AddTransferOptions, Error};
+
+fn transfer(from: &surrealdb::RecordId, to: &surrealdb::RecordId) -> AddTransferOptions {
+    AddTransferOptions {
+        from: from.clone(),
+        to: to.clone(),
+        amount: amount("100"),
+        rate: None,
+        description: "Travel money".to_string(),
+        tags: vec![],
+        date: Some(date("2025-01-10")),
+        fitid: None,
+    }
+}
+
+#[tokio::test]
+async fn legs_stay_in_sync() {
+    let db = common::open().await;
+    let main = add_account(&db, "main", "EUR").await;
+    let travel = add_account(&db, "travel", "USD").await;
+    add_transaction(&db, &main, "2025-01-01", "1000", "Salary").await;
+    add_transaction(&db, &travel, "2025-01-02", "-50", "Hotel").await;
+
+    // A rate is required between currencies, and must be positive.
+    assert!(matches!(
+        thunes_cli::add_transfer(&db, transfer(&main, &travel)).await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(matches!(
+        thunes_cli::add_transfer(
+            &db,
+            AddTransferOptions {
+                rate: Some(amount("-1")),
+                ..transfer(&main, &travel)
+            }
+        )
+        .await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(matches!(
+        thunes_cli::add_transfer(&db, transfer(&main, &main)).await,
+        Err(Error::Invalid(_))
+    ));
+
+    let created = thunes_cli::add_transfer(
+        &db,
+        AddTransferOptions {
+            rate: Some(amount("1.1")),
+            ..transfer(&main, &travel)
+        },
+    )
+    .await
+    .unwrap();
+    let (from, to) = (created.from, created.to);
+    assert_eq!(from.account, main);
+    assert_eq!(from.inner.amount, amount("-100.00"));
+    assert_eq!(from.transfer.as_ref(), Some(&to.id));
+    assert_eq!(to.account, travel);
+    assert_eq!(to.inner.amount, amount("110.00"));
+    assert_eq!(to.transfer.as_ref(), Some(&from.id));
+
+    // Updating a leg updates the other, converted with the rate of the transfer, and
+    // keeps the status of the other leg.
+    let mut updated = from.clone();
+    updated.inner.amount = amount("-200");
+    updated.inner.description = "More travel money".to_string();
+    updated.inner.date = date("2025-01-11");
+    updated.inner.status = Status::Cleared;
+    thunes_cli::update_transaction(&db, updated).await.unwrap();
+
+    let other = thunes_cli::get_transactions(&db, travel.clone(), Default::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|transaction| transaction.id == to.id)
+        .unwrap();
+    assert_eq!(other.inner.amount, amount("220.00"));
+    assert_eq!(other.inner.description, "More travel money");
+    assert_eq!(other.inner.date, date("2025-01-11"));
+    assert_eq!(other.inner.status, Status::Uncleared);
+
+    // Transfers are neither incomes nor expenses.
+    let balances = thunes_cli::balances_by_currency(&db).await.unwrap();
+    let currency = |currency: &str| {
+        balances
+            .iter()
+            .find(|balance| balance.currency == currency)
+            .unwrap()
+    };
+    let eur = currency("EUR");
+    assert_eq!(
+        (eur.total_balance, eur.total_income, eur.total_expenses),
+        (amount("800"), amount("1000"), amount("0"))
+    );
+    let usd = currency("USD");
+    assert_eq!(
+        (usd.total_balance, usd.total_income, usd.total_expenses),
+        (amount("170"), amount("0"), amount("-50"))
+    );
+
+    // Deleting a leg deletes both, and restoring a leg restores both.
+    thunes_cli::delete_transaction(&db, to.id.clone())
+        .await
+        .unwrap();
+    let mut trash: Vec<_> = thunes_cli::get_trash(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|trashed| trashed.transaction.id)
+        .collect();
+    trash.sort_by_key(|id| id.to_string());
+    let mut legs = vec![from.id.clone(), to.id.clone()];
+    legs.sort_by_key(|id| id.to_string());
+    assert_eq!(trash, legs);
+
+    thunes_cli::restore_transactions(&db, vec![from.id.clone()])
+        .await
+        .unwrap();
+    assert!(thunes_cli::get_trash(&db).await.unwrap().is_empty());
+    let balance = thunes_cli::balance(&db, travel.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(balance, amount("170"));
+}
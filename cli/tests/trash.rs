@@ -0,0 +1,101 @@
+//! Deletion of transactions to the trash, their restoration and the purge of the
+//! trash after the retention period.
+
+mod common;
+
+use common::{add_account, add_transaction, amount};
+use surrealdb::{engine::local::Db, RecordId, Surreal};
+use thunes_cli::{settings::Settings, Record};
+
+/// Ids of the transactions of an account by description, and the descriptions in
+/// the trash, both sorted.
+async fn contents(db: &Surreal<Db>, account: &RecordId) -> (Vec<(String, RecordId)>, Vec<String>) {
+    let mut transactions: Vec<(String, RecordId)> =
+        thunes_cli::get_transactions(db, account.clone(), Default::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|transaction| (transaction.inner.description, transaction.id))
+            .collect();
+    transactions.sort();
+
+    let mut trash: Vec<String> = thunes_cli::get_trash(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|trashed| trashed.transaction.inner.description)
+        .collect();
+    trash.sort();
+
+    (transactions, trash)
+}
+
+#[tokio::test]
+async fn delete_restore_and_purge() {
+    let db = common::open().await;
+    let account = add_account(&db, "main", "EUR").await;
+    add_transaction(&db, &account, "2025-01-01", "-10", "a").await;
+    add_transaction(&db, &account, "2025-01-02", "-20", "b").await;
+    add_transaction(&db, &account, "2025-01-03", "-30", "c").await;
+    let (transactions, _) = contents(&db, &account).await;
+    let [(_, a), (_, b), (_, c)] = &transactions[..] else {
+        panic!("{transactions:?}");
+    };
+
+    thunes_cli::delete_transactions(&db, vec![a.clone(), b.clone()])
+        .await
+        .unwrap();
+    let (remaining, trash) = contents(&db, &account).await;
+    assert_eq!(remaining, [("c".to_string(), c.clone())]);
+    assert_eq!(trash, ["a", "b"]);
+
+    // Restored transactions keep their id and their amount.
+    let restored = thunes_cli::restore_transactions(&db, vec![a.clone()])
+        .await
+        .unwrap();
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].id, *a);
+    assert_eq!(restored[0].inner.amount, amount("-10.00"));
+    let (remaining, trash) = contents(&db, &account).await;
+    assert_eq!(
+        remaining,
+        [("a".to_string(), a.clone()), ("c".to_string(), c.clone())]
+    );
+    assert_eq!(trash, ["b"]);
+
+    let mut settings = Settings::new(std::env::temp_dir());
+    settings.trash_retention_days = 7;
+    let _: Option<Record> = db
+        .upsert(("settings", "main"))
+        .content(settings)
+        .await
+        .unwrap();
+
+    // `b` has been in the trash longer than the retention period, and is purged when
+    // `c` is deleted.
+    db.query("UPDATE trash SET deleted_at = time::now() - 8d")
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    thunes_cli::delete_transaction(&db, c.clone())
+        .await
+        .unwrap();
+    let (_, trash) = contents(&db, &account).await;
+    assert_eq!(trash, ["c"]);
+
+    db.query("UPDATE trash SET deleted_at = time::now() - 6d")
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+    thunes_cli::purge_trash(&db).await.unwrap();
+    let (_, trash) = contents(&db, &account).await;
+    assert_eq!(trash, ["c"]);
+
+    thunes_cli::restore_transactions(&db, vec![b.clone()])
+        .await
+        .unwrap();
+    let (remaining, _) = contents(&db, &account).await;
+    assert_eq!(remaining, [("a".to_string(), a.clone())]);
+}
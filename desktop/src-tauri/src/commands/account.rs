@@ -1,13 +1,17 @@
+use rust_decimal::Decimal;
 use surrealdb::engine::local::Db;
 use surrealdb::{RecordId, Surreal};
 use tauri::State;
 use thunes_cli::account::Account;
-use thunes_cli::transaction::TransactionWithId;
+use thunes_cli::page::TransactionPage;
+use thunes_cli::transaction::{Status, TransactionWithId, Transfer, TrashedTransaction};
 use thunes_cli::{
-    AccountIdentifiers, AddAccountOptions, AddTransactionOptions, BalanceOptions, CurrencyBalance,
-    Error as ThunesError, GetTransactionOptions,
+    AccountIdentifiers, AddAccountOptions, AddTransactionOptions, AddTransferOptions,
+    BalanceOptions, CurrencyBalance, Error as ThunesError, GetTransactionOptions,
 };
 
+use super::to_message;
+
 pub type Accounts = std::collections::HashMap<String, Account>;
 
 // TODO: Make errors understandable by users.
@@ -22,16 +26,7 @@ pub async fn get_account(
 
     thunes_cli::get_account(&database, account_id)
         .await
-        .map_err(|error| match error {
-            ThunesError::Database(error) => {
-                tracing::error!(%error, "database error");
-                "failed to get account".to_string()
-            }
-            ThunesError::RecordNotFound => {
-                tracing::error!("account not found");
-                "failed to get account, not found".to_string()
-            }
-        })
+        .map_err(|error| to_message(error, "failed to get account", "account"))
 }
 
 #[tauri::command]
@@ -83,6 +78,7 @@ pub async fn add_account(
                 tracing::error!("account not found after creation");
                 "failed to create account".to_string()
             }
+            ThunesError::Invalid(reason) => reason,
         })
 }
 
@@ -108,7 +104,7 @@ pub async fn get_balance(
     database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
     account_id: RecordId,
     options: Option<BalanceOptions>,
-) -> Result<f64, String> {
+) -> Result<Decimal, String> {
     let database = database.lock().await;
 
     thunes_cli::balance(&database, account_id, options.unwrap_or_default())
@@ -123,6 +119,7 @@ pub async fn get_balance(
                 tracing::error!("balance not found after computation");
                 "failed to get balance".to_string()
             }
+            ThunesError::Invalid(reason) => reason,
         })
 }
 
@@ -151,33 +148,22 @@ pub async fn get_currency(
 
     thunes_cli::get_currency(&database, account_id)
         .await
-        .map_err(|error| match error {
-            ThunesError::Database(error) => {
-                tracing::error!(%error, "database error");
-                "failed to get currency".to_string()
-            }
-            ThunesError::RecordNotFound => {
-                tracing::error!("account not found");
-                "account not found".to_string()
-            }
-        })
+        .map_err(|error| to_message(error, "failed to get currency", "account"))
 }
 
+/// Get a page of the transactions of an account, all of them without `limit`.
 #[tauri::command]
 #[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
 pub async fn get_transactions(
     database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
     account_id: RecordId,
     options: Option<GetTransactionOptions>,
-) -> Result<Vec<TransactionWithId>, String> {
+) -> Result<TransactionPage, String> {
     let database = database.lock().await;
 
-    thunes_cli::get_transactions(&database, account_id, options.unwrap_or_default())
+    thunes_cli::get_transaction_page(&database, account_id, options.unwrap_or_default())
         .await
-        .map_err(|error| {
-            tracing::error!(%error, "database error");
-            "failed to get currency".to_string()
-        })
+        .map_err(|error| to_message(error, "failed to get transactions", "account"))
 }
 
 #[tauri::command]
@@ -191,10 +177,7 @@ pub async fn add_transaction(
 
     thunes_cli::add_transaction(&database, account_id, options)
         .await
-        .map_err(|error| {
-            tracing::error!(%error, "database error");
-            "failed to add transaction".to_string()
-        })
+        .map_err(|error| to_message(error, "failed to add transaction", "account"))
 }
 
 #[tauri::command]
@@ -206,9 +189,104 @@ pub async fn update_transaction(
     let database = database.lock().await;
 
     thunes_cli::update_transaction(&database, transaction)
+        .await
+        .map_err(|error| to_message(error, "failed to update transaction", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_transfer(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddTransferOptions,
+) -> Result<Transfer, String> {
+    let database = database.lock().await;
+
+    thunes_cli::add_transfer(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add transfer", "account"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_transaction(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    transaction_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::delete_transaction(&database, transaction_id)
+        .await
+        .map_err(|error| to_message(error, "failed to delete transaction", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_transactions(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    transaction_ids: Vec<RecordId>,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::delete_transactions(&database, transaction_ids)
+        .await
+        .map_err(|error| to_message(error, "failed to delete transactions", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn set_transactions_status(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    transaction_ids: Vec<RecordId>,
+    status: Status,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::set_transactions_status(&database, transaction_ids, status)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to set the status of transactions".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_trash(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<TrashedTransaction>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::get_trash(&database).await.map_err(|error| {
+        tracing::error!(%error, "database error");
+        "failed to get deleted transactions".to_string()
+    })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn restore_transactions(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    transaction_ids: Vec<RecordId>,
+) -> Result<Vec<TransactionWithId>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::restore_transactions(&database, transaction_ids)
         .await
         .map_err(|error| {
             tracing::error!(%error, "database error");
-            "failed to update transaction".to_string()
+            "failed to restore transactions".to_string()
         })
 }
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn empty_trash(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::empty_trash(&database).await.map_err(|error| {
+        tracing::error!(%error, "database error");
+        "failed to empty trash".to_string()
+    })
+}
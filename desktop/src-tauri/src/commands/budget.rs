@@ -0,0 +1,81 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::budget::{AddBudgetOptions, Budget, BudgetReport};
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_budgets(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<Budget>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::budget::list_budgets(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list budgets".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_budget(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddBudgetOptions,
+) -> Result<Budget, String> {
+    let database = database.lock().await;
+
+    thunes_cli::budget::add_budget(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add budget", "budget or account"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn update_budget(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    budget: Budget,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::budget::update_budget(&database, budget)
+        .await
+        .map_err(|error| to_message(error, "failed to update budget", "budget or account"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_budget(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    budget_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::budget::delete_budget(&database, budget_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete budget".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_budget_report(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    budget_id: RecordId,
+    date: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<BudgetReport, String> {
+    let database = database.lock().await;
+
+    thunes_cli::budget::budget_vs_actual(
+        &database,
+        budget_id,
+        date.unwrap_or_else(chrono::Utc::now),
+    )
+    .await
+    .map_err(|error| to_message(error, "failed to compute budget", "budget or account"))
+}
@@ -0,0 +1,83 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::checkpoint::{AddCheckpointOptions, Checkpoint, Reconciliation};
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_checkpoints(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    account_id: RecordId,
+) -> Result<Vec<Checkpoint>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::checkpoint::list_checkpoints(&database, account_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list checkpoints".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_checkpoint(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddCheckpointOptions,
+) -> Result<Checkpoint, String> {
+    let database = database.lock().await;
+
+    thunes_cli::checkpoint::add_checkpoint(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add checkpoint", "checkpoint or account"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_checkpoint(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    checkpoint_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::checkpoint::delete_checkpoint(&database, checkpoint_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete checkpoint".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_reconciliation(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    checkpoint_id: RecordId,
+) -> Result<Reconciliation, String> {
+    let database = database.lock().await;
+
+    thunes_cli::checkpoint::get_reconciliation(&database, checkpoint_id)
+        .await
+        .map_err(|error| {
+            to_message(
+                error,
+                "failed to compare the checkpoint with the balance",
+                "checkpoint or account",
+            )
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn reconcile(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    checkpoint_id: RecordId,
+) -> Result<Reconciliation, String> {
+    let database = database.lock().await;
+
+    thunes_cli::checkpoint::reconcile(&database, checkpoint_id)
+        .await
+        .map_err(|error| to_message(error, "failed to reconcile", "checkpoint or account"))
+}
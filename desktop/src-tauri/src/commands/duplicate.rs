@@ -0,0 +1,63 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::duplicate::{Duplicate, DuplicateDecision, FindDuplicatesOptions};
+use thunes_cli::transaction::TransactionWithId;
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn find_duplicates(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: FindDuplicatesOptions,
+) -> Result<Vec<Duplicate>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::duplicate::find_duplicates(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to find duplicates", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn merge_duplicates(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    keep: RecordId,
+    duplicate: RecordId,
+) -> Result<TransactionWithId, String> {
+    let database = database.lock().await;
+
+    thunes_cli::duplicate::merge_duplicates(&database, keep, duplicate)
+        .await
+        .map_err(|error| to_message(error, "failed to merge transactions", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn dismiss_duplicate(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    first: RecordId,
+    second: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::duplicate::dismiss_duplicate(&database, first, second)
+        .await
+        .map_err(|error| to_message(error, "failed to dismiss duplicate", "transaction"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_duplicate_decisions(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<DuplicateDecision>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::duplicate::list_duplicate_decisions(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list duplicate decisions".to_string()
+        })
+}
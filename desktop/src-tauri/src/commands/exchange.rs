@@ -0,0 +1,81 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::exchange::{
+    AddExchangeRateOptions, ConversionOptions, ConvertedBalances, ExchangeRate,
+};
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_exchange_rates(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<ExchangeRate>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::exchange::list_exchange_rates(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list exchange rates".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_exchange_rate(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddExchangeRateOptions,
+) -> Result<ExchangeRate, String> {
+    let database = database.lock().await;
+
+    thunes_cli::exchange::add_exchange_rate(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add exchange rate", "record"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_exchange_rate(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    rate_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::exchange::delete_exchange_rate(&database, rate_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete exchange rate".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn import_exchange_rates(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    path: std::path::PathBuf,
+) -> Result<usize, String> {
+    let database = database.lock().await;
+
+    thunes_cli::exchange::import_file(&database, &path)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "import error");
+            format!("failed to import exchange rates: {error}")
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_converted_balances(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: Option<ConversionOptions>,
+) -> Result<ConvertedBalances, String> {
+    let database = database.lock().await;
+
+    thunes_cli::exchange::convert_balances(&database, options.unwrap_or_default())
+        .await
+        .map_err(|error| to_message(error, "failed to convert balances", "record"))
+}
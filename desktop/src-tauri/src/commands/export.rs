@@ -0,0 +1,52 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::export::{JournalFormat, JournalOptions};
+
+/// Export the transactions of an account to a QIF file selected with the dialog plugin.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn export_qif(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    account_id: RecordId,
+    path: std::path::PathBuf,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    let content = thunes_cli::export::qif::export(&database, account_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to export transactions".to_string()
+        })?;
+
+    std::fs::write(&path, content).map_err(|error| {
+        tracing::error!(%error, "export error");
+        format!("failed to write {}: {error}", path.display())
+    })
+}
+
+/// Export all accounts and transactions as a ledger, hledger or beancount journal
+/// to a file selected with the dialog plugin.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn export_journal(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    path: std::path::PathBuf,
+    format: JournalFormat,
+    options: JournalOptions,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    let content = thunes_cli::export::export_journal(&database, format, &options)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to export transactions".to_string()
+        })?;
+
+    std::fs::write(&path, content).map_err(|error| {
+        tracing::error!(%error, "export error");
+        format!("failed to write {}: {error}", path.display())
+    })
+}
@@ -0,0 +1,112 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::import::csv::{CsvImport, CsvProfile};
+use thunes_cli::import::journal::JournalImport;
+use thunes_cli::import::StatementImport;
+use thunes_cli::Error as ThunesError;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_csv_profiles(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<CsvProfile>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::csv::list_profiles(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list import profiles".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn save_csv_profile(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    profile: CsvProfile,
+) -> Result<CsvProfile, String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::csv::save_profile(&database, profile)
+        .await
+        .map_err(|error| match error {
+            ThunesError::Invalid(reason) => reason,
+            error => {
+                tracing::error!(%error, "database error");
+                "failed to save import profile".to_string()
+            }
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_csv_profile(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    name: String,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::csv::delete_profile(&database, &name)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete import profile".to_string()
+        })
+}
+
+/// Import a CSV file selected with the dialog plugin, or only read it when `dry_run` is set.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn import_csv(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    account_id: RecordId,
+    path: std::path::PathBuf,
+    profile: CsvProfile,
+    dry_run: bool,
+) -> Result<CsvImport, String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::csv::import_file(&database, account_id, &path, &profile, dry_run)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "import error");
+            format!("failed to import {}: {error}", path.display())
+        })
+}
+
+/// Import an OFX, camt.053, MT940 or QIF statement selected with the dialog plugin.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn import_statement(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    account_id: RecordId,
+    path: std::path::PathBuf,
+) -> Result<StatementImport, String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::import_statement(&database, account_id, &path)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "import error");
+            format!("failed to import {}: {error}", path.display())
+        })
+}
+
+/// Import a beancount or hledger journal selected with the dialog plugin.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn import_journal(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    path: std::path::PathBuf,
+) -> Result<JournalImport, String> {
+    let database = database.lock().await;
+
+    thunes_cli::import::journal::import_file(&database, &path)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "import error");
+            format!("failed to import {}: {error}", path.display())
+        })
+}
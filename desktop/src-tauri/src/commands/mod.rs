@@ -0,0 +1,34 @@
+//! Commands called by the frontend.
+
+pub mod account;
+pub mod budget;
+pub mod checkpoint;
+pub mod duplicate;
+pub mod exchange;
+pub mod export;
+pub mod import;
+pub mod report;
+pub mod rule;
+pub mod schedule;
+pub mod settings;
+pub mod tags;
+
+use thunes_cli::Error as ThunesError;
+
+/// Message shown to users when a command fails. Invalid requests are explained,
+/// other errors are logged.
+///
+/// `subject` names the records that could not be found, like `"schedule or account"`.
+pub(crate) fn to_message(error: ThunesError, message: &str, subject: &str) -> String {
+    match error {
+        ThunesError::Database(error) => {
+            tracing::error!(%error, "database error");
+            message.to_string()
+        }
+        ThunesError::RecordNotFound => {
+            tracing::error!("{subject} not found");
+            format!("{message}, not found")
+        }
+        ThunesError::Invalid(reason) => reason,
+    }
+}
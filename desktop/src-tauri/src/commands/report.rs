@@ -0,0 +1,63 @@
+use surrealdb::engine::local::Db;
+use surrealdb::Surreal;
+use tauri::State;
+use thunes_cli::report::{
+    BalanceHistory, BalanceHistoryOptions, CashFlow, CompareOptions, Comparison, ReportOptions,
+    SpendingByTag,
+};
+use thunes_cli::schedule::Frequency;
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_balance_history(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: BalanceHistoryOptions,
+) -> Result<Vec<BalanceHistory>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::report::balance_history(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to get balance history", "record"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_cash_flow(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: ReportOptions,
+    period: Frequency,
+) -> Result<Vec<CashFlow>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::report::cash_flow(&database, options, period)
+        .await
+        .map_err(|error| to_message(error, "failed to get incomes and expenses", "record"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_spending_by_tag(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: ReportOptions,
+) -> Result<Vec<SpendingByTag>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::report::spending_by_tag(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to get spendings by tag", "record"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn compare_reports(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: CompareOptions,
+) -> Result<Vec<Comparison>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::report::compare(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to compare reports", "record"))
+}
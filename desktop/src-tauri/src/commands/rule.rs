@@ -0,0 +1,76 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::rule::{AddRuleOptions, Rule, RuleChange, RunRulesOptions};
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_rules(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<Rule>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::rule::list_rules(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list rules".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_rule(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddRuleOptions,
+) -> Result<Rule, String> {
+    let database = database.lock().await;
+
+    thunes_cli::rule::add_rule(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add rule", "rule"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn update_rule(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    rule: Rule,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::rule::update_rule(&database, rule)
+        .await
+        .map_err(|error| to_message(error, "failed to update rule", "rule"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_rule(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    rule_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::rule::delete_rule(&database, rule_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete rule".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn run_rules(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: RunRulesOptions,
+) -> Result<Vec<RuleChange>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::rule::run_rules(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to run rules", "rule"))
+}
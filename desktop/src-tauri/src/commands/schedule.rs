@@ -0,0 +1,117 @@
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+use tauri::State;
+use thunes_cli::schedule::{AddScheduleOptions, Occurrence, Schedule};
+
+use super::to_message;
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn list_schedules(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+) -> Result<Vec<Schedule>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::schedule::list_schedules(&database)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to list schedules".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn add_schedule(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    options: AddScheduleOptions,
+) -> Result<Schedule, String> {
+    let database = database.lock().await;
+
+    let schedule = thunes_cli::schedule::add_schedule(&database, options)
+        .await
+        .map_err(|error| to_message(error, "failed to add schedule", "schedule or account"))?;
+
+    // Create occurrences that are already due.
+    thunes_cli::schedule::materialize(&database, chrono::Utc::now())
+        .await
+        .map_err(|error| {
+            to_message(
+                error,
+                "failed to create scheduled transactions",
+                "schedule or account",
+            )
+        })?;
+
+    Ok(schedule)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn delete_schedule(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    schedule_id: RecordId,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::schedule::delete_schedule(&database, schedule_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "database error");
+            "failed to delete schedule".to_string()
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn skip_occurrence(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    schedule_id: RecordId,
+    date: chrono::DateTime<chrono::Utc>,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::schedule::skip_occurrence(&database, schedule_id, date)
+        .await
+        .map_err(|error| to_message(error, "failed to skip occurrence", "schedule or account"))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn postpone_occurrence(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    schedule_id: RecordId,
+    date: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::schedule::postpone_occurrence(&database, schedule_id, date, to)
+        .await
+        .map_err(|error| {
+            to_message(
+                error,
+                "failed to postpone occurrence",
+                "schedule or account",
+            )
+        })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn get_upcoming_occurrences(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    until: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<Occurrence>, String> {
+    let database = database.lock().await;
+
+    thunes_cli::schedule::upcoming_occurrences(&database, until)
+        .await
+        .map_err(|error| {
+            to_message(
+                error,
+                "failed to get upcoming occurrences",
+                "schedule or account",
+            )
+        })
+}
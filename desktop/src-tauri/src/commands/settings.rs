@@ -1,5 +1,6 @@
 use surrealdb::{engine::local::Db, Surreal};
 use tauri::State;
+use thunes_cli::archive::{ArchiveFormat, ArchiveImport};
 use thunes_cli::{settings::Settings, Record};
 
 #[tauri::command]
@@ -94,3 +95,38 @@ pub async fn backup_export(
         "failed to export data".to_string()
     })
 }
+
+/// Export the store as a portable archive, a JSON file or a directory of CSV files.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn archive_export(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    path: std::path::PathBuf,
+    format: ArchiveFormat,
+) -> Result<(), String> {
+    let database = database.lock().await;
+
+    thunes_cli::archive::export_file(&database, format, &path)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "archive error");
+            format!("failed to export archive: {error}")
+        })
+}
+
+/// Import a portable archive, a JSON file or a directory of CSV files.
+#[tauri::command]
+#[tracing::instrument(skip(database), ret(level = tracing::Level::DEBUG))]
+pub async fn archive_import(
+    database: State<'_, tokio::sync::Mutex<Surreal<Db>>>,
+    path: std::path::PathBuf,
+) -> Result<ArchiveImport, String> {
+    let database = database.lock().await;
+
+    thunes_cli::archive::import_file(&database, &path)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "archive error");
+            format!("failed to import {}: {error}", path.display())
+        })
+}
@@ -1,11 +1,7 @@
 use tauri::{App, Manager};
 use thunes_cli::{settings::Settings, Record};
 
-pub mod commands {
-    pub mod account;
-    pub mod settings;
-    pub mod tags;
-}
+pub mod commands;
 
 fn setup(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Setup dev tools.
@@ -52,6 +48,10 @@ fn setup(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>> {
             .await
             .map_err(|error| error.to_string())?;
 
+        thunes_cli::migration::run(&db)
+            .await
+            .map_err(|error| error.to_string())?;
+
         // FIXME: move db seeding to an install script.
         let result: Result<Option<Record>, surrealdb::Error> = db
             .insert(("settings", "main"))
@@ -66,6 +66,14 @@ fn setup(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        thunes_cli::purge_trash(&db)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        thunes_cli::schedule::materialize(&db, chrono::Utc::now())
+            .await
+            .map_err(|error| error.to_string())?;
+
         Ok(db)
     }))??;
 
@@ -91,12 +99,63 @@ pub fn run() {
             commands::account::get_all_balance,
             commands::account::add_transaction,
             commands::account::update_transaction,
+            commands::account::add_transfer,
+            commands::account::delete_transaction,
+            commands::account::delete_transactions,
+            commands::account::set_transactions_status,
+            commands::account::get_trash,
+            commands::account::restore_transactions,
+            commands::account::empty_trash,
+            commands::budget::list_budgets,
+            commands::budget::add_budget,
+            commands::budget::update_budget,
+            commands::budget::delete_budget,
+            commands::budget::get_budget_report,
+            commands::checkpoint::list_checkpoints,
+            commands::checkpoint::add_checkpoint,
+            commands::checkpoint::delete_checkpoint,
+            commands::checkpoint::get_reconciliation,
+            commands::checkpoint::reconcile,
+            commands::duplicate::find_duplicates,
+            commands::duplicate::merge_duplicates,
+            commands::duplicate::dismiss_duplicate,
+            commands::duplicate::list_duplicate_decisions,
+            commands::exchange::list_exchange_rates,
+            commands::exchange::add_exchange_rate,
+            commands::exchange::delete_exchange_rate,
+            commands::exchange::import_exchange_rates,
+            commands::exchange::get_converted_balances,
+            commands::export::export_qif,
+            commands::export::export_journal,
+            commands::import::list_csv_profiles,
+            commands::import::save_csv_profile,
+            commands::import::delete_csv_profile,
+            commands::import::import_csv,
+            commands::import::import_statement,
+            commands::import::import_journal,
+            commands::report::compare_reports,
+            commands::report::get_balance_history,
+            commands::report::get_cash_flow,
+            commands::report::get_spending_by_tag,
+            commands::rule::list_rules,
+            commands::rule::add_rule,
+            commands::rule::update_rule,
+            commands::rule::delete_rule,
+            commands::rule::run_rules,
+            commands::schedule::list_schedules,
+            commands::schedule::add_schedule,
+            commands::schedule::delete_schedule,
+            commands::schedule::skip_occurrence,
+            commands::schedule::postpone_occurrence,
+            commands::schedule::get_upcoming_occurrences,
             commands::tags::get_tags,
             commands::tags::add_tags,
             commands::settings::get_settings,
             commands::settings::save_settings,
             commands::settings::backup_export,
             commands::settings::backup_import,
+            commands::settings::archive_export,
+            commands::settings::archive_import,
         ])
         .setup(setup)
         .run(tauri::generate_context!())
@@ -3,12 +3,53 @@ import { AddTransactionOptions } from "../../../cli/bindings/AddTransactionOptio
 import { Tag } from "../../../cli/bindings/Tag";
 import { TransactionWithId } from "../../../cli/bindings/TransactionWithId";
 import { GetTransactionOptions } from "../../../cli/bindings/GetTransactionOptions";
+import { TransactionPage } from "../../../cli/bindings/TransactionPage";
 import { Account } from "../../../cli/bindings/Account";
 import { BalanceOptions } from "../../../cli/bindings/BalanceOptions";
 import { Settings } from "../../../cli/bindings/Settings";
 import { CurrencyBalance } from "../../../cli/bindings/CurrencyBalance";
 import { AddAccountOptions } from "../../../cli/bindings/AddAccountOptions";
 import { AccountIdentifiers } from "../../../cli/bindings/AccountIdentifiers";
+import { TrashedTransaction } from "../../../cli/bindings/TrashedTransaction";
+import { AddTransferOptions } from "../../../cli/bindings/AddTransferOptions";
+import { Transfer } from "../../../cli/bindings/Transfer";
+import { Schedule } from "../../../cli/bindings/Schedule";
+import { AddScheduleOptions } from "../../../cli/bindings/AddScheduleOptions";
+import { Occurrence } from "../../../cli/bindings/Occurrence";
+import { Budget } from "../../../cli/bindings/Budget";
+import { AddBudgetOptions } from "../../../cli/bindings/AddBudgetOptions";
+import { BudgetReport } from "../../../cli/bindings/BudgetReport";
+import { ExchangeRate } from "../../../cli/bindings/ExchangeRate";
+import { AddExchangeRateOptions } from "../../../cli/bindings/AddExchangeRateOptions";
+import { ConversionOptions } from "../../../cli/bindings/ConversionOptions";
+import { ConvertedBalances } from "../../../cli/bindings/ConvertedBalances";
+import { CsvProfile } from "../../../cli/bindings/CsvProfile";
+import { CsvImport } from "../../../cli/bindings/CsvImport";
+import { StatementImport } from "../../../cli/bindings/StatementImport";
+import { JournalImport } from "../../../cli/bindings/JournalImport";
+import { JournalFormat } from "../../../cli/bindings/JournalFormat";
+import { JournalOptions } from "../../../cli/bindings/JournalOptions";
+import { ArchiveFormat } from "../../../cli/bindings/ArchiveFormat";
+import { ArchiveImport } from "../../../cli/bindings/ArchiveImport";
+import { Rule } from "../../../cli/bindings/Rule";
+import { AddRuleOptions } from "../../../cli/bindings/AddRuleOptions";
+import { RuleChange } from "../../../cli/bindings/RuleChange";
+import { RunRulesOptions } from "../../../cli/bindings/RunRulesOptions";
+import { Checkpoint } from "../../../cli/bindings/Checkpoint";
+import { AddCheckpointOptions } from "../../../cli/bindings/AddCheckpointOptions";
+import { Reconciliation } from "../../../cli/bindings/Reconciliation";
+import { Status } from "../../../cli/bindings/Status";
+import { Duplicate } from "../../../cli/bindings/Duplicate";
+import { DuplicateDecision } from "../../../cli/bindings/DuplicateDecision";
+import { FindDuplicatesOptions } from "../../../cli/bindings/FindDuplicatesOptions";
+import { BalanceHistory } from "../../../cli/bindings/BalanceHistory";
+import { BalanceHistoryOptions } from "../../../cli/bindings/BalanceHistoryOptions";
+import { ReportOptions } from "../../../cli/bindings/ReportOptions";
+import { CashFlow } from "../../../cli/bindings/CashFlow";
+import { SpendingByTag } from "../../../cli/bindings/SpendingByTag";
+import { CompareOptions } from "../../../cli/bindings/CompareOptions";
+import { Comparison } from "../../../cli/bindings/Comparison";
+import { Frequency } from "../../../cli/bindings/Frequency";
 
 // TODO: could this be automated ?
 
@@ -18,11 +59,18 @@ export const EMPTY_RECORD_ID: RecordId = { tb: "", id: { String: "" } };
 
 // Transactions.
 export const getCurrency = (accountId: RecordId): Promise<string> => invoke("get_currency", { accountId });
-export const getBalance = (accountId: RecordId, options?: BalanceOptions): Promise<number> => invoke("get_balance", { accountId, options });
+export const getBalance = (accountId: RecordId, options?: BalanceOptions): Promise<string> => invoke("get_balance", { accountId, options });
 export const getAllBalance = (): Promise<CurrencyBalance[]> => invoke("get_all_balance");
-export const getTransactions = (accountId: RecordId, options?: GetTransactionOptions): Promise<TransactionWithId[]> => invoke("get_transactions", { accountId, options });
+export const getTransactions = (accountId: RecordId, options?: GetTransactionOptions): Promise<TransactionPage> => invoke("get_transactions", { accountId, options });
 export const addTransaction = (accountId: RecordId, options: AddTransactionOptions): Promise<void> => invoke("add_transaction", { accountId, options });
 export const updateTransaction = (transaction: TransactionWithId): Promise<void> => invoke("update_transaction", { transaction });
+export const addTransfer = (options: AddTransferOptions): Promise<Transfer> => invoke("add_transfer", { options });
+export const deleteTransaction = (transactionId: RecordId): Promise<void> => invoke("delete_transaction", { transactionId });
+export const deleteTransactions = (transactionIds: RecordId[]): Promise<void> => invoke("delete_transactions", { transactionIds });
+export const setTransactionsStatus = (transactionIds: RecordId[], status: Status): Promise<void> => invoke("set_transactions_status", { transactionIds, status });
+export const getTrash = (): Promise<TrashedTransaction[]> => invoke("get_trash");
+export const restoreTransactions = (transactionIds: RecordId[]): Promise<TransactionWithId[]> => invoke("restore_transactions", { transactionIds });
+export const emptyTrash = (): Promise<void> => invoke("empty_trash");
 
 // Accounts.
 export const listAccounts = (): Promise<AccountIdentifiers[]> => invoke("list_accounts");
@@ -32,6 +80,66 @@ export const deleteAccount = (accountId: RecordId): Promise<void> => invoke("del
 // TODO: Make this Partial<Account>
 export const updateAccount = (account: Account): Promise<void> => invoke("update_account", { account });
 
+// Exchange rates.
+export const listExchangeRates = (): Promise<ExchangeRate[]> => invoke("list_exchange_rates");
+export const addExchangeRate = (options: AddExchangeRateOptions): Promise<ExchangeRate> => invoke("add_exchange_rate", { options });
+export const deleteExchangeRate = (rateId: RecordId): Promise<void> => invoke("delete_exchange_rate", { rateId });
+export const importExchangeRates = (path: string): Promise<number> => invoke("import_exchange_rates", { path });
+export const getConvertedBalances = (options?: ConversionOptions): Promise<ConvertedBalances> => invoke("get_converted_balances", { options });
+
+// Imports.
+export const listCsvProfiles = (): Promise<CsvProfile[]> => invoke("list_csv_profiles");
+export const saveCsvProfile = (profile: CsvProfile): Promise<CsvProfile> => invoke("save_csv_profile", { profile });
+export const deleteCsvProfile = (name: string): Promise<void> => invoke("delete_csv_profile", { name });
+export const importCsv = (accountId: RecordId, path: string, profile: CsvProfile, dryRun: boolean): Promise<CsvImport> => invoke("import_csv", { accountId, path, profile, dryRun });
+export const importStatement = (accountId: RecordId, path: string): Promise<StatementImport> => invoke("import_statement", { accountId, path });
+export const importJournal = (path: string): Promise<JournalImport> => invoke("import_journal", { path });
+
+// Exports.
+export const exportQif = (accountId: RecordId, path: string): Promise<void> => invoke("export_qif", { accountId, path });
+export const exportJournal = (path: string, format: JournalFormat, options: JournalOptions): Promise<void> => invoke("export_journal", { path, format, options });
+
+// Budgets.
+export const listBudgets = (): Promise<Budget[]> => invoke("list_budgets");
+export const addBudget = (options: AddBudgetOptions): Promise<Budget> => invoke("add_budget", { options });
+export const updateBudget = (budget: Budget): Promise<void> => invoke("update_budget", { budget });
+export const deleteBudget = (budgetId: RecordId): Promise<void> => invoke("delete_budget", { budgetId });
+export const getBudgetReport = (budgetId: RecordId, date?: string): Promise<BudgetReport> => invoke("get_budget_report", { budgetId, date });
+
+// Schedules.
+export const listSchedules = (): Promise<Schedule[]> => invoke("list_schedules");
+export const addSchedule = (options: AddScheduleOptions): Promise<Schedule> => invoke("add_schedule", { options });
+export const deleteSchedule = (scheduleId: RecordId): Promise<void> => invoke("delete_schedule", { scheduleId });
+export const skipOccurrence = (scheduleId: RecordId, date: string): Promise<void> => invoke("skip_occurrence", { scheduleId, date });
+export const postponeOccurrence = (scheduleId: RecordId, date: string, to: string): Promise<void> => invoke("postpone_occurrence", { scheduleId, date, to });
+export const getUpcomingOccurrences = (until: string): Promise<Occurrence[]> => invoke("get_upcoming_occurrences", { until });
+
+// Rules.
+export const listRules = (): Promise<Rule[]> => invoke("list_rules");
+export const addRule = (options: AddRuleOptions): Promise<Rule> => invoke("add_rule", { options });
+export const updateRule = (rule: Rule): Promise<void> => invoke("update_rule", { rule });
+export const deleteRule = (ruleId: RecordId): Promise<void> => invoke("delete_rule", { ruleId });
+export const runRules = (options: RunRulesOptions): Promise<RuleChange[]> => invoke("run_rules", { options });
+
+// Checkpoints.
+export const listCheckpoints = (accountId: RecordId): Promise<Checkpoint[]> => invoke("list_checkpoints", { accountId });
+export const addCheckpoint = (options: AddCheckpointOptions): Promise<Checkpoint> => invoke("add_checkpoint", { options });
+export const deleteCheckpoint = (checkpointId: RecordId): Promise<void> => invoke("delete_checkpoint", { checkpointId });
+export const getReconciliation = (checkpointId: RecordId): Promise<Reconciliation> => invoke("get_reconciliation", { checkpointId });
+export const reconcile = (checkpointId: RecordId): Promise<Reconciliation> => invoke("reconcile", { checkpointId });
+
+// Duplicates.
+export const findDuplicates = (options: FindDuplicatesOptions): Promise<Duplicate[]> => invoke("find_duplicates", { options });
+export const mergeDuplicates = (keep: RecordId, duplicate: RecordId): Promise<TransactionWithId> => invoke("merge_duplicates", { keep, duplicate });
+export const dismissDuplicate = (first: RecordId, second: RecordId): Promise<void> => invoke("dismiss_duplicate", { first, second });
+export const listDuplicateDecisions = (): Promise<DuplicateDecision[]> => invoke("list_duplicate_decisions");
+
+// Reports.
+export const getBalanceHistory = (options: BalanceHistoryOptions): Promise<BalanceHistory[]> => invoke("get_balance_history", { options });
+export const getCashFlow = (options: ReportOptions, period: Frequency): Promise<CashFlow[]> => invoke("get_cash_flow", { options, period });
+export const getSpendingByTag = (options: ReportOptions): Promise<SpendingByTag[]> => invoke("get_spending_by_tag", { options });
+export const compareReports = (options: CompareOptions): Promise<Comparison[]> => invoke("compare_reports", { options });
+
 // Settings.
 export const getSettings = (): Promise<Settings> => invoke("get_settings");
 export const saveSettings = (settings: Settings): Promise<void> => invoke("save_settings", { settings });
@@ -41,3 +149,5 @@ export const addTags = (tags: Tag[]): Promise<void> => invoke("add_tags", { tags
 // Backups.
 export const ExportBackup = (): Promise<void> => invoke("backup_export");
 export const ImportBackup = (path: string): Promise<void> => invoke("backup_import", { path });
+export const exportArchive = (path: string, format: ArchiveFormat): Promise<void> => invoke("archive_export", { path, format });
+export const importArchive = (path: string): Promise<ArchiveImport> => invoke("archive_import", { path });
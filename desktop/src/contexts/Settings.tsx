@@ -14,6 +14,8 @@ const SettingsDispatchContext = createContext<Dispatch<Action> | null>(null);
 export function SettingsProvider({ children }: { children: ReactNode }) {
   const [settings, dispatch] = useReducer(settingsReducer, {
     backups_path: "",
+    trash_retention_days: 30,
+    base_currency: "EUR",
     theme: "system",
     tags: [],
   });
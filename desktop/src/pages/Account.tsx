@@ -7,10 +7,13 @@ import {
   DialogContent,
   DialogTitle,
   Divider,
+  FormControl,
   Grid2,
   IconButton,
+  InputLabel,
   Menu,
   MenuItem,
+  Select,
   Snackbar,
   SnackbarCloseReason,
   Tab,
@@ -40,10 +43,20 @@ import {
   addAccount,
   deleteAccount,
   EMPTY_RECORD_ID,
+  exportQif,
+  importCsv,
+  importStatement,
   listAccounts,
+  listCsvProfiles,
   RecordId,
 } from "../api";
+import {
+  open as openFile,
+  save as saveFile,
+} from "@tauri-apps/plugin-dialog";
 import { Account } from "../../../cli/bindings/Account";
+import { CsvImport } from "../../../cli/bindings/CsvImport";
+import { CsvProfile } from "../../../cli/bindings/CsvProfile";
 import { AccountIdentifiers } from "../../../cli/bindings/AccountIdentifiers";
 import { useDispatchSnackbar } from "../contexts/Snackbar";
 
@@ -180,12 +193,143 @@ function AddAccountDialog({
   );
 }
 
+function ImportCsvDialog({
+  open,
+  setOpen,
+}: {
+  open: boolean;
+  setOpen: Dispatch<SetStateAction<boolean>>;
+}) {
+  const account = useAccount()!;
+  const dispatchSnackbar = useDispatchSnackbar()!;
+  const [profiles, setProfiles] = useState<CsvProfile[]>([]);
+  const [profile, setProfile] = useState<CsvProfile | null>(null);
+  const [path, setPath] = useState<string | null>(null);
+  const [preview, setPreview] = useState<CsvImport | null>(null);
+
+  useEffect(() => {
+    if (open) {
+      listCsvProfiles()
+        .then(setProfiles)
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  }, [open]);
+
+  // Read the file without importing it to show what will be added.
+  useEffect(() => {
+    setPreview(null);
+
+    if (path && profile) {
+      importCsv(account.id, path, profile, true)
+        .then(setPreview)
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  }, [path, profile]);
+
+  const handleCloseForm = () => {
+    setPath(null);
+    setPreview(null);
+    setOpen(false);
+  };
+
+  const handleSelectFile = async () => {
+    const path = await openFile({
+      filters: [{ name: "bank statement", extensions: ["csv", "txt"] }],
+      directory: false,
+    });
+
+    if (path) {
+      setPath(path);
+    }
+  };
+
+  const handleImport = async () => {
+    importCsv(account.id, path!, profile!, false)
+      .then((result) => {
+        handleCloseForm();
+        dispatchSnackbar({
+          type: "open",
+          severity: "success",
+          message: `${result.report.transactions_imported} transaction(s) imported`,
+        });
+      })
+      .catch((error) =>
+        dispatchSnackbar({ type: "open", severity: "error", message: error })
+      );
+  };
+
+  return (
+    <Dialog open={open} onClose={handleCloseForm} fullWidth>
+      <DialogTitle>Import a CSV statement in {account.name}</DialogTitle>
+      <DialogContent>
+        <Grid2 container spacing={2} sx={{ margin: 1 }}>
+          <Grid2 size={6}>
+            <FormControl fullWidth>
+              <InputLabel id="import-profile-label">Profile</InputLabel>
+              <Select
+                labelId="import-profile-label"
+                label="Profile"
+                value={profile?.name ?? ""}
+                onChange={(event) =>
+                  setProfile(
+                    profiles.find(
+                      (profile) => profile.name === event.target.value
+                    ) ?? null
+                  )
+                }
+              >
+                {profiles.map((profile) => (
+                  <MenuItem key={profile.name} value={profile.name}>
+                    {profile.name}
+                  </MenuItem>
+                ))}
+              </Select>
+            </FormControl>
+          </Grid2>
+          <Grid2 size={6}>
+            <Button variant="outlined" onClick={handleSelectFile}>
+              {path ?? "Select file"}
+            </Button>
+          </Grid2>
+          {preview && (
+            <Grid2 size={12}>
+              <Typography>
+                {preview.transactions.length} transaction(s) will be imported
+              </Typography>
+              {preview.report.skipped.map((skipped) => (
+                <Typography key={skipped} variant="body2" color="warning">
+                  skipped {skipped}
+                </Typography>
+              ))}
+            </Grid2>
+          )}
+        </Grid2>
+      </DialogContent>
+      <DialogActions>
+        <Button onClick={handleCloseForm}>Cancel</Button>
+        <Button
+          disabled={!preview || preview.transactions.length === 0}
+          onClick={handleImport}
+        >
+          Import
+        </Button>
+      </DialogActions>
+    </Dialog>
+  );
+}
+
 export function Layout() {
   const selected = useAccount();
   const dispatch = useDispatchAccount()!;
+  const dispatchSnackbar = useDispatchSnackbar()!;
 
   const [openDeleteDialog, setOpenDeleteDialog] = useState(false);
   const [openAddDialog, setOpenAddDialog] = useState(false);
+  const [openImportDialog, setOpenImportDialog] = useState(false);
   const [openFailure, setOpenFailure] = useState("");
   const [accounts, setAccounts] = useState<AccountIdentifiers[]>();
   const [accountAnchorEl, setAccountAnchorEl] = useState<null | HTMLElement>(
@@ -226,6 +370,57 @@ export function Layout() {
       account,
     });
 
+  const handleImportStatement = async () => {
+    handleClose();
+
+    const path = await openFile({
+      filters: [
+        {
+          name: "bank statement",
+          extensions: ["ofx", "qfx", "xml", "sta", "mt940", "940", "txt", "qif"],
+        },
+      ],
+      directory: false,
+    });
+
+    if (path) {
+      importStatement(selected!.id, path)
+        .then((result) =>
+          dispatchSnackbar({
+            type: "open",
+            severity: "success",
+            message: `${result.report.transactions_imported} transaction(s) imported, ${result.report.skipped.length} skipped`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
+
+  const handleExportQif = async () => {
+    handleClose();
+
+    const path = await saveFile({
+      defaultPath: `${selected!.name}.qif`,
+      filters: [{ name: "QIF", extensions: ["qif"] }],
+    });
+
+    if (path) {
+      exportQif(selected!.id, path)
+        .then(() =>
+          dispatchSnackbar({
+            type: "open",
+            severity: "success",
+            message: `transactions exported to ${path}`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
+
   const handleTabChange = (_event: SyntheticEvent, newTab: number) => {
     setTab(newTab);
   };
@@ -311,6 +506,24 @@ export function Layout() {
               "aria-labelledby": "basic-button",
             }}
           >
+            <MenuItem
+              disabled={!accountIsSelected(selected)}
+              onClick={() => setOpenImportDialog(true)}
+            >
+              Import CSV
+            </MenuItem>
+            <MenuItem
+              disabled={!accountIsSelected(selected)}
+              onClick={handleImportStatement}
+            >
+              Import statement
+            </MenuItem>
+            <MenuItem
+              disabled={!accountIsSelected(selected)}
+              onClick={handleExportQif}
+            >
+              Export QIF
+            </MenuItem>
             <MenuItem onClick={() => setOpenDeleteDialog(true)}>
               Delete
             </MenuItem>
@@ -356,6 +569,13 @@ export function Layout() {
         handleUpdateAccounts={handleUpdateAccounts}
       />
 
+      {accountIsSelected(selected) && (
+        <ImportCsvDialog
+          open={openImportDialog}
+          setOpen={setOpenImportDialog}
+        />
+      )}
+
       <DeleteAccountDialog
         open={openDeleteDialog}
         setOpen={setOpenDeleteDialog}
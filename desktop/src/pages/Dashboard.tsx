@@ -3,17 +3,34 @@ import {
   CardContent,
   CardHeader,
   Divider,
+  LinearProgress,
+  List,
+  ListItem,
+  ListItemText,
   Paper,
   Skeleton,
   Typography,
 } from "@mui/material";
-import { PieChart } from "@mui/x-charts";
+import { BarChart, PieChart } from "@mui/x-charts";
 import { useEffect, useState } from "react";
 import { CurrencyBalance } from "../../../cli/bindings/CurrencyBalance";
 import Grid from "@mui/material/Grid2";
 import { useNavigate } from "react-router-dom";
 import { useDispatchAccount } from "../contexts/Account";
-import { getAllBalance } from "../api";
+import {
+  getAllBalance,
+  getBudgetReport,
+  getCashFlow,
+  getConvertedBalances,
+  getSpendingByTag,
+  getUpcomingOccurrences,
+  listBudgets,
+} from "../api";
+import { BudgetReport } from "../../../cli/bindings/BudgetReport";
+import { ConvertedBalances } from "../../../cli/bindings/ConvertedBalances";
+import { Occurrence } from "../../../cli/bindings/Occurrence";
+import { CashFlow } from "../../../cli/bindings/CashFlow";
+import { SpendingByTag } from "../../../cli/bindings/SpendingByTag";
 import { useDispatchSnackbar } from "../contexts/Snackbar";
 
 export default function Dashboard() {
@@ -21,6 +38,11 @@ export default function Dashboard() {
   const dispatchAccount = useDispatchAccount()!;
   const dispatchSnackbar = useDispatchSnackbar()!;
   const [currencies, setCurrencies] = useState<CurrencyBalance[] | null>(null);
+  const [occurrences, setOccurrences] = useState<Occurrence[]>([]);
+  const [budgets, setBudgets] = useState<BudgetReport[]>([]);
+  const [netWorth, setNetWorth] = useState<ConvertedBalances | null>(null);
+  const [cashFlows, setCashFlows] = useState<CashFlow[]>([]);
+  const [spendings, setSpendings] = useState<SpendingByTag[]>([]);
 
   useEffect(() => {
     getAllBalance()
@@ -28,11 +50,62 @@ export default function Dashboard() {
       .catch((error) => dispatchSnackbar({ type: "open", message: error }));
   }, [dispatchSnackbar]);
 
+  useEffect(() => {
+    const until = new Date();
+    until.setDate(until.getDate() + 30);
+
+    getUpcomingOccurrences(until.toISOString())
+      .then(setOccurrences)
+      .catch((error) => dispatchSnackbar({ type: "open", message: error }));
+  }, [dispatchSnackbar]);
+
+  useEffect(() => {
+    // Note: fails when an exchange rate is missing, the net worth is then hidden.
+    getConvertedBalances()
+      .then(setNetWorth)
+      .catch((error) => console.warn(error));
+  }, []);
+
+  useEffect(() => {
+    // Current month and the five previous ones.
+    const start = new Date();
+    start.setMonth(start.getMonth() - 5, 1);
+    start.setHours(0, 0, 0, 0);
+
+    getCashFlow({ accounts: [], start: start.toISOString() }, "monthly")
+      .then(setCashFlows)
+      .catch((error) => dispatchSnackbar({ type: "open", message: error }));
+  }, [dispatchSnackbar]);
+
+  useEffect(() => {
+    const start = new Date();
+    start.setDate(1);
+    start.setHours(0, 0, 0, 0);
+
+    getSpendingByTag({ accounts: [], start: start.toISOString() })
+      .then(setSpendings)
+      .catch((error) => dispatchSnackbar({ type: "open", message: error }));
+  }, [dispatchSnackbar]);
+
+  useEffect(() => {
+    listBudgets()
+      .then((budgets) =>
+        Promise.all(budgets.map((budget) => getBudgetReport(budget.id)))
+      )
+      .then(setBudgets)
+      .catch((error) => dispatchSnackbar({ type: "open", message: error }));
+  }, [dispatchSnackbar]);
+
   return (
     <Paper elevation={0} sx={{ height: "100%" }}>
       <Typography variant="h2" sx={{ m: 2 }}>
         Portfolio
       </Typography>
+      {netWorth && (
+        <Typography variant="h5" sx={{ m: 2 }}>
+          {Number(netWorth.total).toFixed(2)} {netWorth.currency}
+        </Typography>
+      )}
 
       <Divider></Divider>
 
@@ -44,7 +117,7 @@ export default function Dashboard() {
                 title={currency}
                 action={
                   <Typography variant="subtitle1">
-                    {total_balance.toFixed(2)} {currency}
+                    {Number(total_balance).toFixed(2)} {currency}
                   </Typography>
                 }
               />
@@ -58,7 +131,7 @@ export default function Dashboard() {
                   series={[
                     {
                       data: accounts.map(({ account, balance }) => ({
-                        value: balance,
+                        value: Number(balance),
                         label: account.name,
                       })),
                       innerRadius: 30,
@@ -86,6 +159,142 @@ export default function Dashboard() {
               </CardContent>
             </Card>
           ))}
+          {budgets.map((budget) => (
+            <Card key={budget.name} variant="outlined">
+              <CardHeader
+                title={budget.name}
+                subheader={`${new Date(budget.period_start).toLocaleDateString()} - ${new Date(budget.period_end).toLocaleDateString()}`}
+              />
+              <CardContent>
+                {budget.envelopes.map((envelope) => {
+                  const available =
+                    Number(envelope.allocated) + Number(envelope.rolled_over);
+                  const spent = Number(envelope.spent);
+
+                  return (
+                    <div key={envelope.tag}>
+                      <Typography variant="body2">
+                        {envelope.tag}: {spent.toFixed(2)} /{" "}
+                        {available.toFixed(2)} {budget.currency}
+                      </Typography>
+                      <LinearProgress
+                        variant="determinate"
+                        color={spent > available ? "error" : "primary"}
+                        value={
+                          available > 0
+                            ? Math.min((spent / available) * 100, 100)
+                            : 100
+                        }
+                      />
+                    </div>
+                  );
+                })}
+              </CardContent>
+            </Card>
+          ))}
+          {cashFlows.map((cashFlow) => (
+            <Card key={`cash-flow-${cashFlow.currency}`} variant="outlined">
+              <CardHeader
+                title={`Cash flow (${cashFlow.currency})`}
+                action={
+                  <Typography variant="subtitle1">
+                    {Number(cashFlow.total.net).toFixed(2)} {cashFlow.currency}
+                  </Typography>
+                }
+              />
+              <CardContent>
+                <BarChart
+                  width={400}
+                  height={300}
+                  xAxis={[
+                    {
+                      scaleType: "band",
+                      data: cashFlow.periods.map((period) =>
+                        new Date(period.period_start).toLocaleDateString(
+                          undefined,
+                          { month: "short", year: "2-digit" }
+                        )
+                      ),
+                    },
+                  ]}
+                  series={[
+                    {
+                      label: "Income",
+                      data: cashFlow.periods.map((period) =>
+                        Number(period.income)
+                      ),
+                    },
+                    {
+                      label: "Expenses",
+                      data: cashFlow.periods.map(
+                        (period) => -Number(period.expenses)
+                      ),
+                    },
+                  ]}
+                />
+              </CardContent>
+            </Card>
+          ))}
+          {spendings
+            .filter((spending) => spending.tags.length > 0)
+            .map((spending) => (
+              <Card key={`spending-${spending.currency}`} variant="outlined">
+                <CardHeader
+                  title="Spendings this month"
+                  action={
+                    <Typography variant="subtitle1">
+                      {Number(spending.total).toFixed(2)} {spending.currency}
+                    </Typography>
+                  }
+                />
+                <CardContent>
+                  <PieChart
+                    width={400}
+                    height={300}
+                    slotProps={{
+                      legend: { hidden: true },
+                    }}
+                    series={[
+                      {
+                        data: spending.tags.map(
+                          ({ tag, spent, percentage }) => ({
+                            value: Number(spent),
+                            label: `${tag ?? "untagged"} (${percentage}%)`,
+                          })
+                        ),
+                        innerRadius: 30,
+                        outerRadius: 100,
+                        paddingAngle: 5,
+                        cornerRadius: 5,
+                        arcLabelMinAngle: 35,
+                        arcLabel: (item) => item.label ?? "",
+                        valueFormatter: (item) =>
+                          `${item.value.toFixed(2)} ${spending.currency}`,
+                      },
+                    ]}
+                  />
+                </CardContent>
+              </Card>
+            ))}
+          {occurrences.length > 0 && (
+            <Card variant="outlined">
+              <CardHeader title="Next bills" />
+              <CardContent>
+                <List dense>
+                  {occurrences.map((occurrence) => (
+                    <ListItem
+                      key={`${occurrence.schedule.id.String}-${occurrence.date}`}
+                    >
+                      <ListItemText
+                        primary={`${occurrence.description} (${Number(occurrence.amount).toFixed(2)})`}
+                        secondary={new Date(occurrence.date).toLocaleDateString()}
+                      />
+                    </ListItem>
+                  ))}
+                </List>
+              </CardContent>
+            </Card>
+          )}
         </Grid>
       ) : (
         <Skeleton animation="wave" variant="circular" />
@@ -3,20 +3,31 @@ import {
   Button,
   Divider,
   FormControl,
+  FormControlLabel,
   Grid2,
   InputLabel,
   MenuItem,
   Paper,
   Select,
   SelectChangeEvent,
+  Switch,
   TextField,
   Typography,
 } from "@mui/material";
 import { Theme } from "../../../cli/bindings/Theme";
 import { useDispatchSettings, useSettings } from "../contexts/Settings";
-import { ReactNode } from "react";
-import { open } from "@tauri-apps/plugin-dialog";
-import { ExportBackup, ImportBackup } from "../api";
+import { ReactNode, useState } from "react";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import {
+  ExportBackup,
+  exportArchive,
+  exportJournal,
+  ImportBackup,
+  importArchive,
+  importJournal,
+} from "../api";
+import { ArchiveFormat } from "../../../cli/bindings/ArchiveFormat";
+import { JournalFormat } from "../../../cli/bindings/JournalFormat";
 import { useDispatchSnackbar } from "../contexts/Snackbar";
 
 function SettingDescription({ children }: { children: ReactNode }) {
@@ -56,10 +67,127 @@ function SettingSection({
 
 const SETTINGS_GRID_PADDING = 5;
 
+const JOURNAL_EXTENSIONS: Record<JournalFormat, string> = {
+  ledger: "ledger",
+  hledger: "journal",
+  beancount: "beancount",
+};
+
 export default function Settings() {
   const settings = useSettings();
   const dispatchSettings = useDispatchSettings()!;
   const dispatchSnackbar = useDispatchSnackbar()!;
+  const [journalFormat, setJournalFormat] = useState<JournalFormat>("hledger");
+  const [tagsAsAccounts, setTagsAsAccounts] = useState(false);
+  const [archiveFormat, setArchiveFormat] = useState<ArchiveFormat>("json");
+
+  const handleExportJournal = async () => {
+    const extension = JOURNAL_EXTENSIONS[journalFormat];
+    const path = await save({
+      defaultPath: `thunes.${extension}`,
+      filters: [{ name: journalFormat, extensions: [extension] }],
+    });
+
+    if (path) {
+      exportJournal(path, journalFormat, {
+        tags_as_accounts: tagsAsAccounts,
+        accounts: {},
+      })
+        .then(() =>
+          dispatchSnackbar({
+            type: "open",
+            severity: "success",
+            message: `transactions exported to ${path}`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
+
+  // CSV archives are directories, JSON archives are files.
+  const selectArchive = (exporting: boolean) =>
+    archiveFormat === "csv"
+      ? open({ directory: true, title: "select archive directory" })
+      : exporting
+      ? save({
+          defaultPath: "thunes-archive.json",
+          filters: [{ name: "thunes archive", extensions: ["json"] }],
+        })
+      : open({
+          filters: [{ name: "thunes archive", extensions: ["json"] }],
+          directory: false,
+        });
+
+  const handleExportArchive = async () => {
+    const path = await selectArchive(true);
+
+    if (path) {
+      exportArchive(path, archiveFormat)
+        .then(() =>
+          dispatchSnackbar({
+            type: "open",
+            severity: "success",
+            message: `archive exported to ${path}`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
+
+  const handleImportArchive = async () => {
+    const path = await selectArchive(false);
+
+    if (path) {
+      importArchive(path)
+        .then((report) =>
+          dispatchSnackbar({
+            type: "open",
+            severity: "success",
+            message: `${report.accounts} accounts, ${report.transactions} transactions and ${report.tags} tags imported`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
+
+  const handleImportJournal = async () => {
+    const path = await open({
+      filters: [
+        {
+          name: "journal",
+          extensions: ["beancount", "bean", "journal", "hledger", "ledger"],
+        },
+      ],
+      directory: false,
+    });
+
+    if (path) {
+      importJournal(path)
+        .then((report) =>
+          dispatchSnackbar({
+            type: "open",
+            severity: report.skipped.length ? "warning" : "success",
+            message: `${report.accounts.reduce(
+              (total, account) => total + account.transactions_imported,
+              0
+            )} transactions and ${report.transfers_imported} transfers imported${
+              report.skipped.length
+                ? `, ${report.skipped.length} entries skipped: ${report.skipped[0]}`
+                : ""
+            }`,
+          })
+        )
+        .catch((error) =>
+          dispatchSnackbar({ type: "open", severity: "error", message: error })
+        );
+    }
+  };
 
   const handleBackupDirectoryPath = async () => {
     const backups_path = await open({
@@ -115,6 +243,31 @@ export default function Settings() {
         </Grid2>
       </SettingSection>
 
+      <SettingSection title="Currencies" description="Conversion of balances">
+        <Grid2 size={SETTINGS_GRID_PADDING}>
+          <SettingDescription>
+            Currency all balances are converted to when they are displayed
+            together
+          </SettingDescription>
+        </Grid2>
+        <Grid2 size={1}>
+          <TextField
+            fullWidth
+            label="Base currency"
+            value={settings.base_currency}
+            onChange={(event) =>
+              dispatchSettings({
+                type: "update",
+                settings: {
+                  ...settings,
+                  base_currency: event.target.value.toUpperCase(),
+                },
+              })
+            }
+          />
+        </Grid2>
+      </SettingSection>
+
       <SettingSection title="Data" description="Storage and backups">
         <Grid2 size={SETTINGS_GRID_PADDING}>
           <SettingDescription>
@@ -183,6 +336,85 @@ export default function Settings() {
             settings.
           </Alert>
         </Grid2>
+        <Grid2 size={SETTINGS_GRID_PADDING}>
+          <SettingDescription>
+            Export or import your accounts, transactions, tags and settings as
+            a portable archive, readable without the app.
+          </SettingDescription>
+        </Grid2>
+        <Grid2 size={5}>
+          <FormControl sx={{ minWidth: 150 }}>
+            <InputLabel id="archive-format-select">Format</InputLabel>
+            <Select
+              labelId="archive-format-select"
+              value={archiveFormat}
+              label="Format"
+              onChange={(event: SelectChangeEvent) =>
+                setArchiveFormat(event.target.value as ArchiveFormat)
+              }
+            >
+              <MenuItem value={"json"}>JSON file</MenuItem>
+              <MenuItem value={"csv"}>CSV directory</MenuItem>
+            </Select>
+          </FormControl>
+          <Button
+            variant="contained"
+            onClick={handleExportArchive}
+            sx={{ m: 1 }}
+          >
+            Export archive
+          </Button>
+          <Button variant="contained" onClick={handleImportArchive}>
+            Import archive
+          </Button>
+        </Grid2>
+        <Grid2 size={SETTINGS_GRID_PADDING}>
+          <SettingDescription>
+            Export your accounts and transactions as a plain text accounting
+            journal.
+          </SettingDescription>
+        </Grid2>
+        <Grid2 size={5}>
+          <FormControl sx={{ minWidth: 150 }}>
+            <InputLabel id="journal-format-select">Format</InputLabel>
+            <Select
+              labelId="journal-format-select"
+              value={journalFormat}
+              label="Format"
+              onChange={(event: SelectChangeEvent) =>
+                setJournalFormat(event.target.value as JournalFormat)
+              }
+            >
+              <MenuItem value={"ledger"}>ledger</MenuItem>
+              <MenuItem value={"hledger"}>hledger</MenuItem>
+              <MenuItem value={"beancount"}>beancount</MenuItem>
+            </Select>
+          </FormControl>
+          <FormControlLabel
+            control={
+              <Switch
+                checked={tagsAsAccounts}
+                onChange={(event) => setTagsAsAccounts(event.target.checked)}
+              />
+            }
+            label="Post tags to expense accounts"
+            sx={{ m: 1 }}
+          />
+          <Button variant="contained" onClick={handleExportJournal}>
+            Export journal
+          </Button>
+        </Grid2>
+        <Grid2 size={SETTINGS_GRID_PADDING}>
+          <SettingDescription>
+            Import a beancount or hledger journal. Asset and liability accounts
+            become accounts, income and expense accounts become tags.
+          </SettingDescription>
+        </Grid2>
+        <Grid2 size={5}>
+          <Button variant="contained" onClick={handleImportJournal}>
+            Import journal
+          </Button>
+        </Grid2>
       </SettingSection>
 
       <Divider sx={{ marginTop: 2, marginBottom: 2 }} />
@@ -16,21 +16,21 @@ export default function Details() {
 
   useEffect(() => {
     getCurrency(account.id).then(setCurrency);
-    getBalance(account.id).then(setBalance);
+    getBalance(account.id).then((balance) => setBalance(Number(balance)));
 
     // TODO: get balances for the current month.
     getBalance(account.id, { tag: "needs" })
-      .then((balance) => setBalanceNeeds((balance as number) * -1))
+      .then((balance) => setBalanceNeeds(Number(balance) * -1))
       .catch((error) =>
         dispatchSnackbar({ type: "open", severity: "error", message: error })
       );
     getBalance(account.id, { tag: "wants" })
-      .then((balance) => setBalanceWants((balance as number) * -1))
+      .then((balance) => setBalanceWants(Number(balance) * -1))
       .catch((error) =>
         dispatchSnackbar({ type: "open", severity: "error", message: error })
       );
     getBalance(account.id, { tag: "savings" })
-      .then((balance) => setBalanceSavings((balance as number) * -1))
+      .then((balance) => setBalanceSavings(Number(balance) * -1))
       .catch((error) =>
         dispatchSnackbar({ type: "open", severity: "error", message: error })
       );
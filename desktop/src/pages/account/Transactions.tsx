@@ -18,6 +18,7 @@ import {
 import {
   FormEvent,
   useEffect,
+  useRef,
   useState,
   SetStateAction,
   Dispatch,
@@ -25,10 +26,13 @@ import {
 import AddIcon from "@mui/icons-material/Add";
 import { Transaction } from "../../../../cli/bindings/Transaction";
 import { TransactionWithId } from "../../../../cli/bindings/TransactionWithId";
+import { TransactionPage } from "../../../../cli/bindings/TransactionPage";
+import { Sort } from "../../../../cli/bindings/Sort";
 import { useAccount } from "../../contexts/Account";
 import {
   DataGrid,
   GridColDef,
+  GridPaginationModel,
   GridRenderCellParams,
   GridRenderEditCellParams,
   GridSortModel,
@@ -90,7 +94,7 @@ function AddTransactionDialog({
 
     addTransaction(account.id, {
       ...form,
-      amount,
+      amount: amount.toString(),
       date: form.date.toISOString(),
     })
       .then(() => {
@@ -193,9 +197,15 @@ export default function Transactions() {
   const [account, setAccount] = useState<Account>();
   const [open, setOpen] = useState(false);
   const [currency, setCurrency] = useState<string | null>(null);
-  const [transactions, setTransactions] = useState<TransactionWithId[] | null>(
-    null
-  );
+  const [page, setPage] = useState<TransactionPage | null>(null);
+  const [paginationModel, setPaginationModel] = useState<GridPaginationModel>({
+    page: 0,
+    pageSize: 10,
+  });
+  // Cursors to fetch each page, known once the previous page is fetched.
+  const cursors = useRef<Record<number, string>>({});
+  // Incremented to fetch the current page again.
+  const [refresh, setRefresh] = useState(0);
   const [sparklineTransactions, setSparklineTransactions] = useState<
     TransactionWithId[] | null
   >(null);
@@ -245,11 +255,24 @@ export default function Transactions() {
       editable: true,
     },
     // TODO: add color
-    { field: "amount", headerName: "Amount", type: "number", editable: true },
+    {
+      field: "amount",
+      headerName: "Amount",
+      type: "number",
+      // Amounts are sent as strings to keep their precision.
+      valueGetter: (value) => Number(value),
+      editable: true,
+    },
+    {
+      field: "balance",
+      headerName: "Balance",
+      type: "number",
+      // Balances are computed in date order, whatever the sort.
+      sortable: false,
+      valueGetter: (value) => Number(value),
+    },
   ];
 
-  const paginationModel = { page: 0, pageSize: 10 };
-
   function getRowId(row: TransactionWithId) {
     return row.id.id.String;
   }
@@ -259,19 +282,33 @@ export default function Transactions() {
   };
 
   const handleRowUpdate = (transaction: TransactionWithId) => {
-    updateTransaction(transaction);
+    updateTransaction({ ...transaction, amount: String(transaction.amount) });
     handleUpdateTransactions(accountIdentifiers);
     return transaction;
   };
 
   const handleUpdateTransactions = (account: AccountIdentifiers) => {
-    getTransactions(account.id).then(setTransactions);
-    getTransactions(account.id).then(setSparklineTransactions);
+    setRefresh((refresh) => refresh + 1);
+    getTransactions(account.id, {
+      sort: [{ field: "date", sort: "desc" }],
+      limit: 30,
+    }).then((page) => setSparklineTransactions(page.transactions.reverse()));
     getCurrency(account.id).then(setCurrency);
-    getBalance(account.id).then(setBalance);
+    getBalance(account.id).then((balance) => setBalance(Number(balance)));
+  };
+
+  const handlePaginationModelChange = (model: GridPaginationModel) => {
+    if (model.pageSize !== paginationModel.pageSize) {
+      cursors.current = {};
+      model = { ...model, page: 0 };
+    }
+    setPaginationModel(model);
   };
 
   const handleSortModelChange = (sortModel: GridSortModel) => {
+    cursors.current = {};
+    setPaginationModel({ ...paginationModel, page: 0 });
+
     if (account) {
       const newAccount = {
         ...account,
@@ -288,6 +325,35 @@ export default function Transactions() {
   }, [accountIdentifiers]);
 
   useEffect(() => {
+    if (!account || account.id.id.String !== accountIdentifiers.id.id.String) {
+      return;
+    }
+
+    const current = paginationModel.page;
+    getTransactions(accountIdentifiers.id, {
+      sort: account.transaction_grid_sort_model as unknown as Sort[],
+      limit: paginationModel.pageSize,
+      cursor: cursors.current[current],
+      running_balance: true,
+    })
+      .then((page) => {
+        if (page.next_cursor) {
+          cursors.current[current + 1] = page.next_cursor;
+        }
+        setPage(page);
+      })
+      .catch((error) => dispatchSnackbar({ type: "open", message: error }));
+  }, [
+    accountIdentifiers,
+    account,
+    paginationModel,
+    refresh,
+    dispatchSnackbar,
+  ]);
+
+  useEffect(() => {
+    cursors.current = {};
+    setPaginationModel((model) => ({ ...model, page: 0 }));
     getAccount(accountIdentifiers.id)
       .then(setAccount)
       .catch((error) => dispatchSnackbar({ type: "open", message: error }));
@@ -303,21 +369,19 @@ export default function Transactions() {
         <Skeleton animation="wave" />
       )}
 
-      {transactions && sparklineTransactions ? (
+      {page && sparklineTransactions ? (
         <Box sx={{ height: 600, width: "100%" }}>
           <SparkLineChart
             // Sum account transaction until the last 30 days
             // and display each account state every day.
             data={(() => {
-              const before = sparklineTransactions.slice(
-                0,
-                sparklineTransactions.length - 30
+              let sum = sparklineTransactions.reduce(
+                (acc, t) => acc - Number(t.amount),
+                balance
               );
-              const after = sparklineTransactions.slice(-30);
-              let sum = before.reduce((acc, t) => acc + t.amount, 0);
 
-              return after.map((t) => {
-                sum += t.amount;
+              return sparklineTransactions.map((t) => {
+                sum += Number(t.amount);
                 return sum;
               });
             })()}
@@ -330,17 +394,22 @@ export default function Transactions() {
             // TODO: add options to change the date range (1m, 5m, 1y, etc.)
             xAxis={{
               scaleType: "time",
-              data: sparklineTransactions
-                .slice(-30)
-                .map((t) => new Date(t.date)),
+              data: sparklineTransactions.map((t) => new Date(t.date)),
               valueFormatter: (value) => value.toISOString().slice(0, 10),
             }}
           />
           <DataGrid
-            rows={transactions}
+            rows={page.transactions.map((transaction, index) => ({
+              ...transaction,
+              balance: page.running_balances[index],
+            }))}
             columns={columns}
             getRowId={getRowId}
-            initialState={{ pagination: { paginationModel } }}
+            paginationMode="server"
+            sortingMode="server"
+            rowCount={page.total}
+            paginationModel={paginationModel}
+            onPaginationModelChange={handlePaginationModelChange}
             pageSizeOptions={[5, 10, 25, 50, 100]}
             checkboxSelection
             processRowUpdate={handleRowUpdate}